@@ -0,0 +1,49 @@
+//! Compares sending events on a 1,000 transition machine with each map backend.
+//!
+//! Run with `cargo bench --bench backends`.
+
+use restate::blocking::{Builder, Machine};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const STATES: usize = 250;
+const TRANSITIONS_PER_STATE: usize = 4;
+const SENDS: usize = 10_000;
+const ITERATIONS: u32 = 20;
+
+fn build(
+    machine: Machine<'static, usize, usize, (), ()>,
+) -> Machine<'static, usize, usize, (), ()> {
+    let mut machine = machine;
+
+    for from in 0..STATES {
+        for event in 0..TRANSITIONS_PER_STATE {
+            let to = (from * 7 + event + 1) % STATES;
+            machine = machine.on_next(Builder::new(from).on(event).go_to(to));
+        }
+    }
+
+    machine
+}
+
+fn measure(name: &str, machine: fn() -> Machine<'static, usize, usize, (), ()>) {
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let mut sm = build(machine()).start(0);
+
+        let start = Instant::now();
+        for i in 0..SENDS {
+            black_box(sm.send(i % TRANSITIONS_PER_STATE)).unwrap();
+        }
+        total += start.elapsed();
+    }
+
+    println!("{name:<10} {:?}/{SENDS} sends", total / ITERATIONS);
+}
+
+fn main() {
+    measure("scan", Machine::new);
+    measure("ordered", Machine::new_ordered);
+    measure("hashed", Machine::new_hashed);
+}
@@ -0,0 +1,48 @@
+//! Compares building a large machine with and without capacity hints.
+//!
+//! Run with `cargo bench --bench capacity`.
+
+use restate::blocking::{Builder, Machine};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const STATES: usize = 200;
+const TRANSITIONS_PER_STATE: usize = 10;
+const ITERATIONS: u32 = 20;
+
+fn build(
+    machine: Machine<'static, usize, usize, (), ()>,
+) -> Machine<'static, usize, usize, (), ()> {
+    let mut machine = machine;
+
+    for from in 0..STATES {
+        for event in 0..TRANSITIONS_PER_STATE {
+            let to = (from + event + 1) % STATES;
+            machine = machine.on_next(Builder::new(from).on(event).go_to(to));
+        }
+    }
+
+    machine
+}
+
+fn measure(name: &str, f: impl Fn()) {
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        f();
+        total += start.elapsed();
+    }
+
+    println!("{name:<20} {:?}/iter", total / ITERATIONS);
+}
+
+fn main() {
+    measure("without hints", || {
+        black_box(build(Machine::new()).start(0));
+    });
+
+    measure("with hints", || {
+        black_box(build(Machine::with_capacity(STATES, TRANSITIONS_PER_STATE)).start(0));
+    });
+}
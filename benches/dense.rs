@@ -0,0 +1,89 @@
+//! Compares sending events on a `DenseMachine` and on the default `Machine`.
+//!
+//! Run with `cargo bench --bench dense`.
+
+use restate::blocking::{Builder, DenseMachine, Machine, StateIndex};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const STATES: usize = 64;
+const EVENTS: usize = 8;
+const SENDS: usize = 100_000;
+const ITERATIONS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct State(usize);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Event(usize);
+
+impl StateIndex for State {
+    const COUNT: usize = STATES;
+
+    fn index(&self) -> usize {
+        self.0
+    }
+
+    fn from_index(index: usize) -> Self {
+        State(index)
+    }
+}
+
+impl StateIndex for Event {
+    const COUNT: usize = EVENTS;
+
+    fn index(&self) -> usize {
+        self.0
+    }
+
+    fn from_index(index: usize) -> Self {
+        Event(index)
+    }
+}
+
+fn transitions() -> impl Iterator<Item = (State, Event, State)> {
+    (0..STATES).flat_map(|from| {
+        (0..EVENTS).map(move |event| {
+            (
+                State(from),
+                Event(event),
+                State((from * 3 + event + 1) % STATES),
+            )
+        })
+    })
+}
+
+fn measure(name: &str, mut send: impl FnMut(Event)) {
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        for i in 0..SENDS {
+            send(Event(i % EVENTS));
+        }
+        total += start.elapsed();
+    }
+
+    println!("{name:<10} {:?}/{SENDS} sends", total / ITERATIONS);
+}
+
+fn main() {
+    let mut machine = Machine::new();
+    let mut dense = DenseMachine::new();
+
+    for (from, event, to) in transitions() {
+        machine = machine.on_next(Builder::new(from).on(event).go_to(to));
+        dense = dense.on_next(Builder::new(from).on(event).go_to(to));
+    }
+
+    let mut machine = machine.start(State(0));
+    let mut dense = dense.start(State(0));
+
+    measure("machine", |event| {
+        black_box(machine.send(event)).unwrap();
+    });
+
+    measure("dense", |event| {
+        black_box(dense.send(event)).unwrap();
+    });
+}
@@ -0,0 +1,92 @@
+//! Compares classifying bytes with a `FixedMachine` and with the default `Machine`.
+//!
+//! Run with `cargo bench --bench fixed`.
+
+use restate::blocking::{Builder, Machine, Ready};
+use restate::fixed::FixedMachine;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const BYTES: usize = 100_000;
+const ITERATIONS: u32 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Space,
+    Word,
+    Number,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Space,
+    Letter,
+    Digit,
+}
+
+fn classify(byte: u8) -> Class {
+    match byte {
+        b'0'..=b'9' => Class::Digit,
+        b if b.is_ascii_alphabetic() => Class::Letter,
+        _ => Class::Space,
+    }
+}
+
+const TABLE: [(Token, Class, Token, bool); 9] = [
+    (Token::Space, Class::Space, Token::Space, false),
+    (Token::Space, Class::Letter, Token::Word, false),
+    (Token::Space, Class::Digit, Token::Number, false),
+    (Token::Word, Class::Space, Token::Space, false),
+    (Token::Word, Class::Letter, Token::Word, false),
+    (Token::Word, Class::Digit, Token::Word, false),
+    (Token::Number, Class::Space, Token::Space, false),
+    (Token::Number, Class::Letter, Token::Word, false),
+    (Token::Number, Class::Digit, Token::Number, false),
+];
+
+fn machine() -> Machine<'static, Token, Class, (), (), Ready> {
+    TABLE
+        .iter()
+        .fold(Machine::new(), |machine, &(from, class, to, _)| {
+            machine.on_next(Builder::new(from).on(class).go_to(to))
+        })
+        .start(Token::Space)
+}
+
+fn input() -> Vec<u8> {
+    b"let x1 = 42 + word99 * 7;\n"
+        .iter()
+        .copied()
+        .cycle()
+        .take(BYTES)
+        .collect()
+}
+
+fn measure(name: &str, mut f: impl FnMut(&[u8])) {
+    let input = input();
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        f(&input);
+        total += start.elapsed();
+    }
+
+    println!("{name:<10} {:?}/{BYTES} bytes", total / ITERATIONS);
+}
+
+fn main() {
+    measure("machine", |input| {
+        let mut sm = machine();
+        for &byte in input {
+            black_box(sm.send(classify(byte))).unwrap();
+        }
+    });
+
+    measure("fixed", |input| {
+        let mut sm = FixedMachine::new(TABLE, Token::Space);
+        for &byte in input {
+            black_box(sm.send(classify(byte))).unwrap();
+        }
+    });
+}
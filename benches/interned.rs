@@ -0,0 +1,67 @@
+//! Compares sending `String` events on an `InternedMachine` and on the default `Machine`,
+//! with a definition of 200 states as if it was loaded from a configuration file.
+//!
+//! Run with `cargo bench --bench interned`.
+
+use restate::blocking::{InternedMachine, Machine, TransitionTable};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const STATES: usize = 200;
+const EVENTS: [&str; 4] = ["next", "back", "skip", "reset"];
+const SENDS: usize = 100_000;
+const ITERATIONS: u32 = 20;
+
+fn state(index: usize) -> String {
+    format!("state_{}", index % STATES)
+}
+
+fn table() -> TransitionTable<String, String> {
+    (0..STATES)
+        .flat_map(|from| {
+            [from + 1, from + STATES - 1, from + 2, 0]
+                .into_iter()
+                .zip(EVENTS)
+                .map(move |(to, event)| (state(from), event.to_string(), state(to), false))
+        })
+        .collect()
+}
+
+fn events() -> Vec<String> {
+    EVENTS
+        .iter()
+        .cycle()
+        .take(SENDS)
+        .map(|event| event.to_string())
+        .collect()
+}
+
+fn measure(name: &str, mut f: impl FnMut(&[String])) {
+    let events = events();
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let start = Instant::now();
+        f(&events);
+        total += start.elapsed();
+    }
+
+    println!("{name:<10} {:?}/{SENDS} sends", total / ITERATIONS);
+}
+
+fn main() {
+    let mut machine = Machine::from_table(table()).start(state(0));
+    let mut interned = InternedMachine::from_table(table()).start(&state(0));
+
+    measure("machine", |events| {
+        for event in events {
+            black_box(machine.send_ref(event)).unwrap();
+        }
+    });
+
+    measure("interned", |events| {
+        for event in events {
+            black_box(interned.send(event)).unwrap();
+        }
+    });
+}
@@ -0,0 +1,47 @@
+//! Measures sending events while staying in the same state, and while alternating between states.
+//!
+//! Run with `cargo bench --bench send`.
+
+use restate::blocking::{Builder, Machine, Ready};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const STATES: usize = 100;
+const SENDS: usize = 100_000;
+const ITERATIONS: u32 = 20;
+
+// A machine where each state has a self transition on `0`,
+// and a pair of states on the back that alternate on `1`.
+fn machine() -> Machine<'static, usize, usize, (), (), Ready> {
+    let mut machine = Machine::new();
+
+    for state in 0..STATES {
+        machine = machine.on_next(Builder::self_transition(state, 0));
+    }
+
+    machine
+        .on_next(Builder::new(STATES - 2).on(1).go_to(STATES - 1))
+        .on_next(Builder::new(STATES - 1).on(1).go_to(STATES - 2))
+        .start(STATES - 1)
+}
+
+fn measure(name: &str, event: usize) {
+    let mut total = Duration::ZERO;
+
+    for _ in 0..ITERATIONS {
+        let mut sm = machine();
+
+        let start = Instant::now();
+        for _ in 0..SENDS {
+            black_box(sm.send(event)).unwrap();
+        }
+        total += start.elapsed();
+    }
+
+    println!("{name:<12} {:?}/{SENDS} sends", total / ITERATIONS);
+}
+
+fn main() {
+    measure("same state", 0);
+    measure("alternating", 1);
+}
@@ -0,0 +1,354 @@
+use crate::blocking::{outcome_of, Machine, Next};
+use std::collections::VecDeque;
+
+// The number of pairs of states explored by `equivalent` before giving up.
+const DEFAULT_MAX_PAIRS: usize = 10_000;
+
+/// How a counterexample tells apart two state machines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Difference {
+    /// Only the first machine accepts the last event of the sequence.
+    OnlyFirstAccepts,
+
+    /// Only the second machine accepts the last event of the sequence.
+    OnlySecondAccepts,
+
+    /// Both machines accept the sequence, but only one of them is done after it.
+    FinalityDiffers,
+
+    /// Both machines accept the last event of the sequence with a different number of guarded transitions.
+    GuardsDiffer,
+}
+
+/// A sequence of events that tells apart two state machines, returned by `equivalent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample<E> {
+    /// The events sent from the initial states, the machines only differ on the last one.
+    pub events: Vec<E>,
+
+    /// How the machines differ after the events.
+    pub difference: Difference,
+}
+
+/// The result of comparing two state machines with `equivalent`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EquivalenceResult<E> {
+    /// Both machines accept the same sequences of events and are done after the same ones.
+    Equivalent,
+
+    /// The machines are not equivalent, with the shortest sequence of events that tells them apart.
+    NotEquivalent(Counterexample<E>),
+
+    /// The exploration stopped after the given number of pairs of states, without finding a difference.
+    LimitReached {
+        /// The number of pairs of states explored.
+        explored: usize,
+    },
+}
+
+impl<E> EquivalenceResult<E> {
+    /// Returns `true` if the machines are equivalent.
+    pub fn is_equivalent(&self) -> bool {
+        matches!(self, EquivalenceResult::Equivalent)
+    }
+
+    /// Returns the sequence of events that tells apart the machines, if any.
+    pub fn counterexample(&self) -> Option<&Counterexample<E>> {
+        match self {
+            EquivalenceResult::NotEquivalent(counterexample) => Some(counterexample),
+            _ => None,
+        }
+    }
+}
+
+/// Checks whether two state machines accept the same sequences of events from the given initial states,
+/// and are done after the same ones, even if their states are different.
+///
+/// The machines are compared over their transitions, the actions and the guards are not run.
+/// The guarded transitions for an event are matched by position,
+/// so both machines must have the same number of them, and the ignored events are accepted without leaving the state.
+///
+/// At most 10000 pairs of states are explored, see `equivalent_within` to change the limit.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::analysis::{equivalent, Difference};
+/// use restate::blocking::*;
+///
+/// let old = Machine::new()
+///     .on_next(Builder::new("idle").on('s').go_to("running"))
+///     .on_next(Builder::new("running").on('p').go_to("idle"))
+///     .on_next(Builder::new("running").on('x').go_to("stopped").is_final());
+///
+/// let new = Machine::new()
+///     .on_next(Builder::new(0).on('s').go_to(1))
+///     .on_next(Builder::new(1).on('p').go_to(0))
+///     .on_next(Builder::new(1).on('x').go_to(2).is_final());
+///
+/// assert!(equivalent(&old, &new, &"idle", &0).is_equivalent());
+///
+/// let changed = new.on_next(Builder::new(0).on('x').go_to(2));
+/// let result = equivalent(&old, &changed, &"idle", &0);
+/// let counterexample = result.counterexample().unwrap();
+///
+/// assert_eq!(counterexample.events, ['x']);
+/// assert_eq!(counterexample.difference, Difference::OnlySecondAccepts);
+/// ```
+pub fn equivalent<S, S2, E, Ctx, Ctx2, F, F2, Step, Step2, A, A2>(
+    a: &Machine<'_, S, E, Ctx, F, Step, A>,
+    b: &Machine<'_, S2, E, Ctx2, F2, Step2, A2>,
+    initial_a: &S,
+    initial_b: &S2,
+) -> EquivalenceResult<E>
+where
+    S: PartialEq,
+    S2: PartialEq,
+    E: PartialEq + Clone,
+    A: ?Sized,
+    A2: ?Sized,
+{
+    equivalent_within(a, b, initial_a, initial_b, DEFAULT_MAX_PAIRS)
+}
+
+/// Checks whether two state machines are equivalent like `equivalent`,
+/// exploring at most `max_pairs` pairs of states.
+///
+/// Returns `EquivalenceResult::LimitReached` if there are more reachable pairs of states
+/// and no difference was found in the explored ones.
+pub fn equivalent_within<S, S2, E, Ctx, Ctx2, F, F2, Step, Step2, A, A2>(
+    a: &Machine<'_, S, E, Ctx, F, Step, A>,
+    b: &Machine<'_, S2, E, Ctx2, F2, Step2, A2>,
+    initial_a: &S,
+    initial_b: &S2,
+    max_pairs: usize,
+) -> EquivalenceResult<E>
+where
+    S: PartialEq,
+    S2: PartialEq,
+    E: PartialEq + Clone,
+    A: ?Sized,
+    A2: ?Sized,
+{
+    // The explored pairs of states, in the order they were discovered
+    let mut pairs: Vec<Pair<S, S2, E>> = vec![(initial_a, initial_b, None)];
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(index) = queue.pop_front() {
+        let (from_a, from_b, _) = pairs[index];
+
+        let events_a = a.transitions.outgoing(from_a).map(|(event, _)| event);
+        let events_b = b
+            .transitions
+            .outgoing(from_b)
+            .map(|(event, _)| event)
+            .filter(|event| !a.transitions.contains(event, from_a));
+
+        for event in events_a.chain(events_b).collect::<Vec<_>>() {
+            let (next_a, next_b) = match (
+                a.transitions.get(event, from_a),
+                b.transitions.get(event, from_b),
+            ) {
+                (Some(next_a), Some(next_b)) => (next_a, next_b),
+                (Some(_), None) => {
+                    return counterexample(&pairs, index, event, Difference::OnlyFirstAccepts)
+                }
+                _ => return counterexample(&pairs, index, event, Difference::OnlySecondAccepts),
+            };
+
+            if next_a.candidates().count() != next_b.candidates().count() {
+                return counterexample(&pairs, index, event, Difference::GuardsDiffer);
+            }
+
+            for (next_a, next_b) in next_a.candidates().zip(next_b.candidates()) {
+                let (to_a, final_a) = target(a, from_a, next_a);
+                let (to_b, final_b) = target(b, from_b, next_b);
+
+                if final_a != final_b {
+                    return counterexample(&pairs, index, event, Difference::FinalityDiffers);
+                }
+
+                // Both machines are done, so both reject any other event
+                let visited = pairs.iter().any(|(a, b, _)| *a == to_a && *b == to_b);
+                if final_a || visited {
+                    continue;
+                }
+
+                if pairs.len() == max_pairs {
+                    return EquivalenceResult::LimitReached {
+                        explored: pairs.len(),
+                    };
+                }
+
+                pairs.push((to_a, to_b, Some((index, event))));
+                queue.push_back(pairs.len() - 1);
+            }
+        }
+    }
+
+    EquivalenceResult::Equivalent
+}
+
+// A pair of states of both machines, with the index of the previous pair and the event that leads to it.
+type Pair<'m, S, S2, E> = (&'m S, &'m S2, Option<(usize, &'m E)>);
+
+// Returns the events that lead to the pair followed by the last event.
+fn counterexample<S, S2, E: Clone>(
+    pairs: &[Pair<S, S2, E>],
+    mut index: usize,
+    last: &E,
+    difference: Difference,
+) -> EquivalenceResult<E> {
+    let mut events = vec![last.clone()];
+
+    while let Some((prev, event)) = pairs[index].2 {
+        events.push(event.clone());
+        index = prev;
+    }
+
+    events.reverse();
+    EquivalenceResult::NotEquivalent(Counterexample { events, difference })
+}
+
+// Returns the state after the transition and whether the machine is done on it.
+fn target<'m, S, E, Ctx, F, Step, A>(
+    machine: &'m Machine<'_, S, E, Ctx, F, Step, A>,
+    from: &'m S,
+    next: &'m Next<S, A>,
+) -> (&'m S, bool)
+where
+    S: PartialEq,
+    A: ?Sized,
+{
+    // The ignored events don't complete the machine, even on a tagged state
+    if next.ignored {
+        return (from, false);
+    }
+
+    let tagged = outcome_of(&machine.extensions.outcomes, &next.next).is_some();
+    (&next.next, next.is_final || tagged)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{equivalent, equivalent_within, Counterexample, Difference, EquivalenceResult};
+    use crate::blocking::{Builder, Machine};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Door {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    fn door() -> Machine<'static, Door, char, (), ()> {
+        Machine::new()
+            .on_next(Builder::new(Door::Open).on('c').go_to(Door::Closed))
+            .on_next(Builder::new(Door::Closed).on('o').go_to(Door::Open))
+            .on_next(Builder::new(Door::Closed).on('l').go_to(Door::Locked))
+            .on_next(Builder::new(Door::Locked).on('u').go_to(Door::Closed))
+    }
+
+    fn not_equivalent(events: &str, difference: Difference) -> EquivalenceResult<char> {
+        EquivalenceResult::NotEquivalent(Counterexample {
+            events: events.chars().collect(),
+            difference,
+        })
+    }
+
+    #[test]
+    fn equivalent_renamed_test() {
+        // The closed state is split in two, which accept the same events
+        let renamed = Machine::new()
+            .on_next(Builder::new("open").on('c').go_to("closed"))
+            .on_next(Builder::new("closed").on('o').go_to("open"))
+            .on_next(Builder::new("closed").on('l').go_to("locked"))
+            .on_next(Builder::new("locked").on('u').go_to("unlocked"))
+            .on_next(Builder::new("unlocked").on('o').go_to("open"))
+            .on_next(Builder::new("unlocked").on('l').go_to("locked"));
+
+        assert!(equivalent(&door(), &renamed, &Door::Open, &"open").is_equivalent());
+        assert!(
+            equivalent(&renamed, &door().start(Door::Open), &"open", &Door::Open).is_equivalent()
+        );
+    }
+
+    #[test]
+    fn counterexample_test() {
+        let missing = door().on_next(Builder::new(Door::Locked).on('k').go_to(Door::Locked));
+        assert_eq!(
+            equivalent(&missing, &door(), &Door::Open, &Door::Open),
+            not_equivalent("clk", Difference::OnlyFirstAccepts)
+        );
+
+        let final_lock = Machine::new()
+            .on_next(Builder::new(Door::Open).on('c').go_to(Door::Closed))
+            .on_next(Builder::new(Door::Closed).on('o').go_to(Door::Open))
+            .on_next(
+                Builder::new(Door::Closed)
+                    .on('l')
+                    .go_to(Door::Locked)
+                    .is_final(),
+            );
+
+        assert_eq!(
+            equivalent(&door(), &final_lock, &Door::Open, &Door::Open),
+            not_equivalent("cl", Difference::FinalityDiffers)
+        );
+
+        let tagged = door().success_states([Door::Locked]);
+        assert_eq!(
+            equivalent(&tagged, &final_lock, &Door::Open, &Door::Open),
+            EquivalenceResult::Equivalent
+        );
+    }
+
+    #[test]
+    fn equivalent_guards_test() {
+        let guarded = |guards: &[(u64, Door)]| {
+            guards.iter().fold(
+                Machine::new()
+                    .on_next(Builder::new(Door::Open).on('c').go_to(Door::Closed))
+                    .on_next(Builder::new(Door::Closed).on('l').go_to(Door::Locked)),
+                |sm, (secs, to)| {
+                    let unlock = Builder::new(Door::Locked).on('u').go_to(*to);
+                    sm.on_next(unlock.guard_after(Duration::from_secs(*secs)))
+                },
+            )
+        };
+
+        let once = guarded(&[(5, Door::Closed)]);
+        let twice = guarded(&[(5, Door::Closed), (10, Door::Open)]);
+        assert_eq!(
+            equivalent(&once, &twice, &Door::Open, &Door::Open),
+            not_equivalent("clu", Difference::GuardsDiffer)
+        );
+
+        // The guards are opaque, only the targets of the candidates in the same position are compared
+        let other = guarded(&[(1, Door::Closed), (2, Door::Open)]);
+        assert!(equivalent(&twice, &other, &Door::Open, &Door::Open).is_equivalent());
+
+        let swapped = guarded(&[(5, Door::Open), (10, Door::Closed)]);
+        assert_eq!(
+            equivalent(&twice, &swapped, &Door::Open, &Door::Open),
+            not_equivalent("clul", Difference::OnlyFirstAccepts)
+        );
+    }
+
+    #[test]
+    fn equivalent_limit_test() {
+        let counter = |n: u32| {
+            (0..n).fold(Machine::new(), |sm, i| {
+                sm.on_next(Builder::new(i).on('+').go_to((i + 1) % n))
+            })
+        };
+
+        assert_eq!(
+            equivalent_within(&counter(100), &counter(100), &0, &0, 10),
+            EquivalenceResult::LimitReached { explored: 10 }
+        );
+        assert!(equivalent_within(&counter(4), &counter(8), &0, &0, 10).is_equivalent());
+        assert!(equivalent(&counter(3), &counter(4), &0, &0).is_equivalent());
+    }
+}
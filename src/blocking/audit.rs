@@ -0,0 +1,137 @@
+use super::{Build, Machine};
+
+/// The context of a transition with the context of the machine before and after it, see `Machine::on_audit`.
+#[derive(Debug)]
+pub struct AuditContext<'a, S, E, Ctx> {
+    /// The state where this transition starts.
+    pub from: &'a S,
+
+    /// The state where this transition ends.
+    pub to: &'a S,
+
+    /// The event that triggers this transition.
+    pub event: &'a E,
+
+    /// A copy of the context before the actions of the transition ran.
+    pub before: &'a Ctx,
+
+    /// The context after the actions of the transition ran.
+    pub after: &'a Ctx,
+}
+
+// Copies the context before each transition, and calls the audit callback with the copy after it.
+pub(crate) trait Auditor<S, E, Ctx>: Send + Sync {
+    // Copies the context before the actions of a transition can change it, replacing the previous copy.
+    fn copy(&mut self, context: &Ctx);
+
+    // Calls the audit callback with the copy and the context after the transition.
+    fn report(&mut self, from: &S, to: &S, event: &E, after: &Ctx);
+}
+
+pub(crate) type Audit<'a, S, E, Ctx> = Box<dyn Auditor<S, E, Ctx> + 'a>;
+
+// The callback of `on_audit` and the copy of the context before the current transition.
+struct AuditHook<H, Ctx> {
+    hook: H,
+    before: Option<Ctx>,
+}
+
+impl<S, E, Ctx, H> Auditor<S, E, Ctx> for AuditHook<H, Ctx>
+where
+    Ctx: Clone + Send + Sync,
+    H: FnMut(AuditContext<S, E, Ctx>) + Send + Sync,
+{
+    fn copy(&mut self, context: &Ctx) {
+        match self.before.as_mut() {
+            Some(before) => before.clone_from(context),
+            None => self.before = Some(context.clone()),
+        }
+    }
+
+    fn report(&mut self, from: &S, to: &S, event: &E, after: &Ctx) {
+        if let Some(before) = self.before.as_ref() {
+            (self.hook)(AuditContext {
+                from,
+                to,
+                event,
+                before,
+                after,
+            })
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C>
+where
+    Ctx: Clone + Send + Sync + 'a,
+{
+    /// Sets a callback called after each transition with the context before and after the actions ran,
+    /// after `on_transition`.
+    ///
+    /// The context is only cloned before each transition if this callback is set,
+    /// the copy is kept by the machine so it must be `Send` and `Sync` like the callback.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context(100)
+    ///     .on_next(Builder::self_transition("open", "withdraw").action(
+    ///         |cx: ContextMut<&str, &str, i32>| *cx.context -= 30,
+    ///     ))
+    ///     .on_audit(|cx: AuditContext<&str, &str, i32>| {
+    ///         println!("{}: {} -> {}", cx.event, cx.before, cx.after);
+    ///     })
+    ///     .start("open");
+    ///
+    /// sm.send("withdraw").unwrap();
+    /// assert_eq!(*sm.context(), 70);
+    /// ```
+    pub fn on_audit<H>(mut self, hook: H) -> Self
+    where
+        H: FnMut(AuditContext<S, E, Ctx>) + Send + Sync + 'a,
+    {
+        self.extensions.audit = Some(Box::new(AuditHook { hook, before: None }));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AuditContext;
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Account {
+        balance: u32,
+    }
+
+    #[test]
+    fn on_audit_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let entries = log.clone();
+
+        let mut sm = Machine::with_context(Account { balance: 100 })
+            .on_next(
+                Builder::self_transition('o', 30)
+                    .action(|cx: ContextMut<char, u32, Account>| cx.context.balance -= *cx.event),
+            )
+            .on_next(Builder::new('o').on(0).go_to('c').is_final())
+            .on_audit(move |cx: AuditContext<char, u32, Account>| {
+                let entry = (*cx.from, *cx.to, cx.before.balance, cx.after.balance);
+                entries.lock().unwrap().push(entry);
+            })
+            .start('o');
+
+        sm.send(30).unwrap();
+        sm.send(1).unwrap_err();
+        sm.send(0).unwrap();
+
+        assert_eq!(
+            *log.lock().unwrap(),
+            [('o', 'o', 100, 70), ('o', 'c', 70, 70)]
+        );
+    }
+}
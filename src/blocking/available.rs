@@ -0,0 +1,140 @@
+use super::machine::Next;
+use super::{Machine, Ready};
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    S: PartialEq,
+{
+    /// Returns the events that trigger a transition from the current state.
+    ///
+    /// The transitions rejected by their guards or limits at this moment, the expired scoped transitions
+    /// and the ignored events are skipped,
+    /// there are no events if the machine is done.
+    pub fn available_events(&self) -> impl Iterator<Item = &E> {
+        self.available().map(|(event, _)| event)
+    }
+
+    /// Returns the transitions from the current state as `(event, to, is_final)`,
+    /// with the same rules as `available_events`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("coin").go_to("paid"))
+    ///     .on_next(Builder::new("paid").on("soda").go_to("dispensing").is_final())
+    ///     .on_next(Builder::new("paid").on("refund").go_to("idle"))
+    ///     .start("idle");
+    ///
+    /// sm.send("coin").unwrap();
+    ///
+    /// let menu = sm
+    ///     .available_transitions()
+    ///     .enumerate()
+    ///     .map(|(i, (event, to, is_final))| {
+    ///         let end = if is_final { " (end)" } else { "" };
+    ///         format!("{}. {event} -> {to}{end}", i + 1)
+    ///     })
+    ///     .collect::<Vec<_>>();
+    ///
+    /// assert_eq!(menu, ["1. soda -> dispensing (end)", "2. refund -> idle"]);
+    /// ```
+    pub fn available_transitions(&self) -> impl Iterator<Item = (&E, &S, bool)> {
+        self.available()
+            .map(|(event, next)| (event, &next.next, next.is_final))
+    }
+
+    // Returns the transitions that would be taken from the current state for each event.
+    fn available(&self) -> impl Iterator<Item = (&E, &Next<S, A>)> {
+        let extensions = &self.extensions;
+        let elapsed = extensions
+            .clock
+            .now()
+            .saturating_duration_since(extensions.entered_at);
+        let now = || extensions.clock.now();
+
+        let outgoing = match self.done {
+            true => None,
+            false => self.current.as_ref().map(|s| self.transitions.outgoing(s)),
+        };
+
+        outgoing
+            .into_iter()
+            .flatten()
+            .filter_map(move |(event, first)| {
+                let next = first
+                    .candidates()
+                    .find(|next| next.guard.is_none_or(|guard| guard.allows(elapsed)))?;
+
+                let allowed = next.limit.as_ref().is_none_or(|limit| {
+                    let now = limit.cooldown.map(|_| now());
+                    limit.check(now).is_ok()
+                });
+
+                // The next send expires the scoped transitions before looking for the transition
+                let expired = next
+                    .scope
+                    .as_ref()
+                    .is_some_and(|scope| scope.is_expired(extensions.sends + 1, now));
+
+                (allowed && !expired && !next.ignored).then_some((event, next))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine, Scope};
+    use crate::clock::MockClock;
+    use std::time::Duration;
+
+    #[test]
+    fn available_transitions_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_next(
+                Builder::new('a')
+                    .on(1)
+                    .go_to('c')
+                    .guard_after(Duration::from_secs(5)),
+            )
+            .on_next(Builder::new('a').on(2).go_to('d').max_fires(1))
+            .on_next(Builder::new('b').on(3).go_to('e').is_final())
+            .ignore('a', 4)
+            .with_clock(clock.clone())
+            .start('a');
+
+        assert_eq!(sm.available_events().collect::<Vec<_>>(), [&0, &2]);
+
+        clock.advance(Duration::from_secs(5));
+        sm.send(2).unwrap();
+        assert_eq!(sm.available_events().count(), 0);
+
+        let mut sm = sm.into_builder().start('a');
+        sm.send(0).unwrap();
+        assert_eq!(
+            sm.available_transitions().collect::<Vec<_>>(),
+            [(&3, &'e', true)]
+        );
+
+        sm.send(3).unwrap();
+        assert_eq!(sm.available_transitions().count(), 0);
+    }
+
+    #[test]
+    fn available_scoped_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition('a', 0))
+            .start('a');
+
+        sm.add_scoped_transition(Builder::self_transition('a', 1), Scope::Sends(1))
+            .unwrap();
+        assert_eq!(sm.available_events().collect::<Vec<_>>(), [&0, &1]);
+
+        sm.send(0).unwrap();
+        assert_eq!(sm.available_events().collect::<Vec<_>>(), [&0]);
+    }
+}
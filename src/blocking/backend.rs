@@ -0,0 +1,110 @@
+use super::{Build, Machine};
+use crate::common::backend::StateLookup;
+use std::hash::Hash;
+
+pub use crate::common::backend::MapBackend;
+
+impl<'a, S, E> Machine<'a, S, E, (), (), Build> {
+    /// Returns a new `StateMachine` that finds the transitions of each state using a `BTreeMap`.
+    pub fn new_ordered() -> Machine<'a, S, E, (), (), Build>
+    where
+        S: Ord + Clone,
+    {
+        Machine::new().ordered()
+    }
+
+    /// Returns a new `StateMachine` that finds the transitions of each state using a `HashMap`.
+    pub fn new_hashed() -> Machine<'a, S, E, (), (), Build>
+    where
+        S: Hash + Eq + Clone,
+    {
+        Machine::new().hashed()
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Build, A, C> {
+    /// Finds the transitions of each state using a `BTreeMap`.
+    pub fn ordered(mut self) -> Self
+    where
+        S: Ord + Clone,
+    {
+        self.transitions.set_index(StateLookup::ordered());
+        self
+    }
+
+    /// Finds the transitions of each state using a `HashMap`.
+    pub fn hashed(mut self) -> Self
+    where
+        S: Hash + Eq + Clone,
+    {
+        self.transitions.set_index(StateLookup::hashed());
+        self
+    }
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C> {
+    /// Returns the backend used to find the transitions of each state.
+    pub fn backend(&self) -> MapBackend {
+        self.transitions.backend()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MapBackend;
+    use crate::blocking::{Build, Builder, Machine};
+    use crate::error::TransitionError;
+
+    type Def = Machine<'static, u32, char, (), (), Build>;
+
+    // Runs the same definition and events on a machine, and returns the observed results.
+    fn observe(sm: Def) -> Vec<String> {
+        let sm = sm
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('b').go_to(2))
+            .on_next(Builder::new(1).on('a').go_to(0))
+            .on_next(Builder::new(2).on('c').go_to(3).is_final());
+
+        let mut sm = sm.start(0);
+        let mut observed = vec![
+            format!("{:?}", sm.states().collect::<Vec<_>>()),
+            format!("{:?}", sm.events().collect::<Vec<_>>()),
+        ];
+
+        sm.add_transition(Builder::new(3).on('d').go_to(0)).unwrap();
+        assert!(sm.remove_transition(&1, &'a'));
+
+        for event in "aabxcd".chars() {
+            observed.push(format!("{:?} {:?}", sm.send(event), sm.current()));
+        }
+
+        observed
+    }
+
+    #[test]
+    fn backends_test() {
+        assert_eq!(Def::new().backend(), MapBackend::Scan);
+        assert_eq!(Def::new_ordered().backend(), MapBackend::Ordered);
+        assert_eq!(Def::new_hashed().backend(), MapBackend::Hashed);
+
+        let expected = observe(Machine::new());
+        assert_eq!(observe(Machine::new_ordered()), expected);
+        assert_eq!(observe(Machine::new_hashed()), expected);
+        assert!(expected.contains(&format!("{:?} 3", Err::<u32, _>(TransitionError::Done))));
+    }
+
+    #[test]
+    fn index_after_transitions_test() {
+        // The index can be added after the transitions
+        let mut sm = Machine::new()
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('a').go_to(2))
+            .hashed()
+            .ordered()
+            .start(0);
+
+        sm.send('a').unwrap();
+        sm.send('a').unwrap();
+        assert_eq!(*sm.current(), 2);
+    }
+}
@@ -0,0 +1,289 @@
+use super::transition::Marker;
+use super::{
+    BoxedAction, Build, Builder, IntoTransitions, Machine, OnAction, SendAction, SharedAction,
+    Transition,
+};
+use std::marker::PhantomData;
+
+/// A sequence of transitions all triggered by the same event,
+/// created with `Builder::chain`, `Builder::cycle` or `Builder::toggle`.
+pub struct Chain<'a, S, E, Ctx, A: ?Sized = SendAction<'a, S, E, Ctx>> {
+    event: E,
+    states: Vec<S>,
+    last_is_final: bool,
+    actions: Vec<Option<Box<A>>>,
+    _marker: Marker<'a, Ctx, ()>,
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, Build, A> {
+    /// Constructs the transitions from each state to the next one when the given event happens.
+    ///
+    /// # Panics
+    /// If there is less than 2 states.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_chain(Builder::chain("next", [1, 2, 3, 4]).last_is_final())
+    ///     .start(1);
+    ///
+    /// sm.send("next").unwrap();
+    /// sm.send("next").unwrap();
+    /// sm.send("next").unwrap();
+    ///
+    /// assert_eq!(*sm.current(), 4);
+    /// assert!(sm.is_done());
+    /// ```
+    pub fn chain(event: E, states: impl IntoIterator<Item = S>) -> Chain<'a, S, E, Ctx, A> {
+        let states = states.into_iter().collect::<Vec<_>>();
+        assert!(states.len() >= 2, "a chain requires at least 2 states");
+
+        Chain {
+            event,
+            states,
+            last_is_final: false,
+            actions: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S: Clone, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, Build, A> {
+    /// Constructs the transitions from each state to the next one when the given event happens,
+    /// and from the last state back to the first one.
+    ///
+    /// # Panics
+    /// If there is less than 2 states.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::cycle("tick", ["red", "green", "yellow"]))
+    ///     .start("red");
+    ///
+    /// for _ in 0..4 {
+    ///     sm.send("tick").unwrap();
+    /// }
+    ///
+    /// assert_eq!(*sm.current(), "green");
+    /// ```
+    pub fn cycle(event: E, states: impl IntoIterator<Item = S>) -> Chain<'a, S, E, Ctx, A> {
+        let mut states = states.into_iter().collect::<Vec<_>>();
+        assert!(states.len() >= 2, "a cycle requires at least 2 states");
+
+        states.push(states[0].clone());
+        Builder::chain(event, states)
+    }
+
+    /// Constructs the transitions from `a` to `b` and from `b` to `a` when the given event happens.
+    pub fn toggle(a: S, b: S, event: E) -> Chain<'a, S, E, Ctx, A> {
+        Builder::cycle(event, [a, b])
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> Chain<'a, S, E, Ctx, A> {
+    /// Ensure the last transition of the chain completes the state machine.
+    pub fn last_is_final(mut self) -> Self {
+        self.last_is_final = true;
+        self
+    }
+
+    /// Sets an action to execute on every transition of the chain.
+    pub fn action<F>(self, f: F) -> Self
+    where
+        F: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, SharedAction<F>, S, E, Ctx>,
+    {
+        let shared = SharedAction::new(f);
+        self.hop_actions(|_| Some(shared.clone()))
+    }
+
+    /// Sets the action of each transition of the chain,
+    /// the function receives the index of the transition and returns its action, if any.
+    pub fn hop_actions<F>(mut self, mut f: impl FnMut(usize) -> Option<F>) -> Self
+    where
+        F: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, F, S, E, Ctx>,
+    {
+        let hops = self.states.len() - 1;
+        self.actions = (0..hops).map(|i| f(i).map(A::boxed)).collect();
+        self
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> IntoTransitions<'a, S, E, Ctx, A> for Chain<'a, S, E, Ctx, A>
+where
+    S: Clone,
+    E: Clone,
+{
+    type IntoIter = std::vec::IntoIter<Transition<'a, S, E, Ctx, A>>;
+
+    fn into_transitions(self) -> Self::IntoIter {
+        let Chain {
+            event,
+            states,
+            last_is_final,
+            actions,
+            ..
+        } = self;
+
+        let hops = states.len() - 1;
+        let mut actions = actions.into_iter();
+
+        states
+            .windows(2)
+            .enumerate()
+            .map(|(i, w)| Transition {
+                from: w[0].clone(),
+                to: w[1].clone(),
+                event: event.clone(),
+                is_final: last_is_final && i == hops - 1,
+                action: actions.next().flatten(),
+                before: None,
+                guard: None,
+                limit: None,
+                priority: 0,
+                label: None,
+                _marker: PhantomData,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized, C> Machine<'a, S, E, Ctx, (), Build, A, C>
+where
+    E: PartialEq + Clone,
+    S: PartialEq + Clone,
+{
+    /// Adds all the transitions of the given chain.
+    ///
+    /// # Panics
+    /// If any of the transitions already exists.
+    pub fn on_chain(self, chain: Chain<'a, S, E, Ctx, A>) -> Self {
+        self.on_next(chain)
+    }
+
+    /// Adds the transitions from each state to the next one when the given event happens,
+    /// and from the last state back to the first one, see `Builder::cycle`.
+    ///
+    /// # Panics
+    /// If there is less than 2 states or any of the transitions already exists.
+    pub fn on_cycle(self, event: E, states: impl IntoIterator<Item = S>) -> Self {
+        self.on_next(Builder::cycle(event, states))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Step {
+        One,
+        Two,
+        Three,
+        Done,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Next;
+
+    #[test]
+    fn chain_test() {
+        let mut sm = Machine::with_context(0)
+            .on_chain(
+                Builder::chain(Next, [Step::One, Step::Two, Step::Three, Step::Done])
+                    .last_is_final()
+                    .action(|cx: ContextMut<Step, Next, i32>| {
+                        *cx.context += 1;
+                    }),
+            )
+            .start(Step::One);
+
+        assert_eq!(sm.send(Next).unwrap(), Step::One);
+        assert_eq!(sm.send(Next).unwrap(), Step::Two);
+        assert!(!sm.is_done());
+        assert_eq!(sm.send(Next).unwrap(), Step::Three);
+
+        assert_eq!(*sm.current(), Step::Done);
+        assert_eq!(*sm.context(), 3);
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn chain_hop_actions_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_chain(
+                Builder::chain(Next, [Step::One, Step::Two, Step::Three]).hop_actions(|i| {
+                    (i != 0).then_some(move |cx: ContextMut<Step, Next, Vec<usize>>| {
+                        cx.context.push(i);
+                    })
+                }),
+            )
+            .start(Step::One);
+
+        sm.send(Next).unwrap();
+        sm.send(Next).unwrap();
+
+        assert_eq!(*sm.context(), vec![1]);
+        assert!(!sm.is_done());
+    }
+
+    #[test]
+    fn toggle_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(
+                Builder::toggle(false, true, ()).action(|cx: ContextMut<bool, (), i32>| {
+                    *cx.context += 1;
+                }),
+            )
+            .start(false);
+
+        assert_eq!(sm.send(()), Ok(false));
+        assert_eq!(sm.send(()), Ok(true));
+        assert_eq!(sm.send(()), Ok(false));
+        assert_eq!(*sm.context(), 3);
+    }
+
+    #[test]
+    fn cycle_test() {
+        let mut sm = Machine::new()
+            .on_cycle(Next, [Step::One, Step::Two, Step::Three])
+            .start(Step::One);
+
+        let visited = (0..4).map(|_| sm.send(Next).unwrap()).collect::<Vec<_>>();
+
+        assert_eq!(visited, [Step::One, Step::Two, Step::Three, Step::One]);
+        assert_eq!(*sm.current(), Step::Two);
+        assert_eq!(sm.transitions().count(), 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cycle_overlap_test() {
+        let _ = Machine::new()
+            .on_next(Builder::new(Step::Three).on(Next).go_to(Step::Done))
+            .on_cycle(Next, [Step::One, Step::Two, Step::Three]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn chain_too_short_test() {
+        let _ = Machine::<Step, Next, (), ()>::new().on_chain(Builder::chain(Next, [Step::One]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn chain_overlap_test() {
+        let _ = Machine::new()
+            .on_chain(Builder::chain(Next, [Step::One, Step::Two, Step::Three]))
+            .on_chain(Builder::chain(Next, [Step::Two, Step::Done]));
+    }
+}
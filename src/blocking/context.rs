@@ -1,3 +1,9 @@
+use super::sender::EventSender;
+use super::state_data::StateData;
+use crate::error::TransitionError;
+use std::cell::Cell;
+use std::fmt::Debug;
+
 /// An immutable context.
 #[derive(Debug)]
 pub struct Context<'a, S, E, Ctx> {
@@ -14,8 +20,23 @@ pub struct Context<'a, S, E, Ctx> {
     pub context: &'a Ctx,
 }
 
+// The reason an action stopped the transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Abort {
+    Cancelled,
+    ActionSpent,
+}
+
+impl From<Abort> for TransitionError {
+    fn from(value: Abort) -> Self {
+        match value {
+            Abort::Cancelled => TransitionError::Cancelled,
+            Abort::ActionSpent => TransitionError::ActionSpent,
+        }
+    }
+}
+
 /// A mutable context.
-#[derive(Debug)]
 pub struct ContextMut<'a, S, E, Ctx> {
     /// The state where this transition starts.
     pub from: &'a S,
@@ -28,4 +49,75 @@ pub struct ContextMut<'a, S, E, Ctx> {
 
     /// The mutable data associated to the state machine.
     pub context: &'a mut Ctx,
+
+    // The data associated to each state, if the machine supports it.
+    pub(crate) state_data: Option<&'a mut StateData<S>>,
+
+    // Set when the action cancels the transition.
+    pub(crate) abort: &'a Cell<Option<Abort>>,
+
+    // Whether the machine is done after this transition.
+    pub(crate) is_final: &'a Cell<bool>,
+
+    // The sender to queue events to the machine, if the machine supports it.
+    pub(crate) sender: Option<&'a EventSender<E>>,
+}
+
+impl<S, E, Ctx> ContextMut<'_, S, E, Ctx> {
+    /// Cancels this transition, the machine stays in the state where the transition starts.
+    ///
+    /// `send` returns `TransitionError::Cancelled` and `on_transition` is not called,
+    /// the changes made to the context by the action are kept.
+    pub fn cancel(&self) {
+        self.abort.set(Some(Abort::Cancelled));
+    }
+
+    /// Returns `true` if this transition was cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.abort.get().is_some()
+    }
+
+    /// Returns `true` if the machine will be done after this transition,
+    /// initially whether the transition was marked as final.
+    pub fn is_final(&self) -> bool {
+        self.is_final.get()
+    }
+
+    /// Overrides whether the machine is done after this transition,
+    /// only affects this transition and not the stored one.
+    pub fn set_final(&self, is_final: bool) {
+        self.is_final.set(is_final);
+    }
+}
+
+impl<S, E, Ctx> Debug for ContextMut<'_, S, E, Ctx>
+where
+    S: Debug,
+    E: Debug,
+    Ctx: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ContextMut")
+            .field("from", &self.from)
+            .field("to", &self.to)
+            .field("event", &self.event)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+/// The context of an event that didn't trigger any transition.
+#[derive(Debug)]
+pub struct UnhandledContext<'a, S, E, Ctx> {
+    /// The current state of the state machine.
+    pub state: &'a S,
+
+    /// The event that was rejected.
+    pub event: &'a E,
+
+    /// The mutable data associated to the state machine.
+    pub context: &'a mut Ctx,
+
+    /// Whether the event was rejected because the state machine is done.
+    pub is_done: bool,
 }
@@ -0,0 +1,282 @@
+use super::{Build, Machine, Ready};
+use std::collections::{HashSet, VecDeque};
+use std::hash::Hash;
+
+// The number of keys remembered if `dedupe_window` is not called.
+const DEFAULT_WINDOW: usize = 1024;
+
+// The idempotency keys of the events already handled, with the type of the keys erased.
+pub(crate) trait SeenKeys<E> {
+    // Returns `true` if the key of the event was seen.
+    fn contains(&self, event: &E) -> bool;
+
+    // Returns `true` if the key of the event was seen, and marks it as the most recently seen.
+    fn check(&mut self, event: &E) -> bool;
+
+    // Remembers the key of the event, forgetting the least recently seen keys out of the window.
+    fn insert(&mut self, event: &E, window: usize);
+
+    fn len(&self) -> usize;
+
+    fn clear(&mut self);
+}
+
+struct KeySet<K, F> {
+    key: F,
+    order: VecDeque<K>,
+    keys: HashSet<K>,
+}
+
+impl<E, K, F> SeenKeys<E> for KeySet<K, F>
+where
+    K: Hash + Eq + Clone,
+    F: Fn(&E) -> Option<K>,
+{
+    fn contains(&self, event: &E) -> bool {
+        (self.key)(event).is_some_and(|key| self.keys.contains(&key))
+    }
+
+    fn check(&mut self, event: &E) -> bool {
+        let Some(key) = (self.key)(event) else {
+            return false;
+        };
+
+        if !self.keys.contains(&key) {
+            return false;
+        }
+
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+            self.order.push_back(key);
+        }
+
+        true
+    }
+
+    fn insert(&mut self, event: &E, window: usize) {
+        let Some(key) = (self.key)(event) else {
+            return;
+        };
+
+        if window == 0 || !self.keys.insert(key.clone()) {
+            return;
+        }
+
+        self.order.push_back(key);
+
+        while self.order.len() > window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.keys.remove(&oldest);
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.order.len()
+    }
+
+    fn clear(&mut self) {
+        self.order.clear();
+        self.keys.clear();
+    }
+}
+
+// Drops the events which idempotency key was already seen, see `Machine::dedupe_by`.
+pub(crate) struct Dedupe<'a, E> {
+    window: usize,
+    seen: Option<Box<dyn SeenKeys<E> + Send + Sync + 'a>>,
+}
+
+impl<E> Dedupe<'_, E> {
+    pub(crate) fn new() -> Self {
+        Dedupe {
+            window: DEFAULT_WINDOW,
+            seen: None,
+        }
+    }
+
+    // Returns `true` if the event is a duplicate of an event already handled.
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.seen.is_some()
+    }
+
+    pub(crate) fn is_duplicate(&mut self, event: &E) -> bool {
+        self.seen.as_mut().is_some_and(|seen| seen.check(event))
+    }
+
+    // Called after the event was handled.
+    pub(crate) fn handled(&mut self, event: &E) {
+        if let Some(seen) = self.seen.as_mut() {
+            seen.insert(event, self.window);
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Drops the events which idempotency key was already seen, the key of each event is returned by `key`,
+    /// and the events with a `None` key are never dropped.
+    ///
+    /// The dropped events don't run any action nor hook, `send` returns the current state as with the ignored events
+    /// and the `TransitionOutcome` of `step` has `was_duplicate`.
+    /// Only the keys of the last events handled are remembered, see `dedupe_window`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(PartialEq)]
+    /// enum Payment {
+    ///     Charge { id: u32, amount: u32 },
+    ///     Refresh,
+    /// }
+    ///
+    /// let mut sm = Machine::with_context(0)
+    ///     .on_next(Builder::self_transition("open", Payment::Charge { id: 1, amount: 10 }).action(
+    ///         |cx: ContextMut<&str, Payment, u32>| {
+    ///             if let Payment::Charge { amount, .. } = cx.event {
+    ///                 *cx.context += amount;
+    ///             }
+    ///         },
+    ///     ))
+    ///     .on_next(Builder::self_transition("open", Payment::Refresh))
+    ///     .dedupe_by(|event: &Payment| match event {
+    ///         Payment::Charge { id, .. } => Some(*id),
+    ///         Payment::Refresh => None,
+    ///     })
+    ///     .start("open");
+    ///
+    /// let charge = || Payment::Charge { id: 1, amount: 10 };
+    /// assert!(!sm.step(charge()).unwrap().was_duplicate);
+    /// assert!(sm.step(charge()).unwrap().was_duplicate);
+    /// assert!(!sm.step(Payment::Refresh).unwrap().was_duplicate);
+    /// assert!(!sm.step(Payment::Refresh).unwrap().was_duplicate);
+    /// assert_eq!(*sm.context(), 10);
+    /// ```
+    pub fn dedupe_by<K>(mut self, key: impl Fn(&E) -> Option<K> + Send + Sync + 'a) -> Self
+    where
+        E: 'a,
+        K: Hash + Eq + Clone + Send + Sync + 'a,
+    {
+        self.extensions.dedupe.seen = Some(Box::new(KeySet {
+            key,
+            order: VecDeque::new(),
+            keys: HashSet::new(),
+        }));
+
+        self
+    }
+
+    /// Sets the number of idempotency keys remembered by `dedupe_by`, by default `1024`.
+    ///
+    /// When the window is full the least recently seen key is forgotten,
+    /// so an event with that key is handled again.
+    pub fn dedupe_window(mut self, window: usize) -> Self {
+        self.extensions.dedupe.window = window;
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C> {
+    /// Returns `true` if the idempotency key of the event was seen, so the event would be dropped.
+    pub fn is_duplicate(&self, event: &E) -> bool {
+        // Checking doesn't refresh the key, only a dropped event does
+        let seen = self.extensions.dedupe.seen.as_ref();
+        seen.is_some_and(|seen| seen.contains(event))
+    }
+
+    /// Returns the number of idempotency keys remembered.
+    pub fn seen_len(&self) -> usize {
+        self.extensions
+            .dedupe
+            .seen
+            .as_ref()
+            .map_or(0, |seen| seen.len())
+    }
+
+    /// Forgets all the idempotency keys seen, so the events with those keys are handled again.
+    pub fn clear_seen(&mut self) {
+        if let Some(seen) = self.extensions.dedupe.seen.as_mut() {
+            seen.clear();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine, Ready};
+    use std::mem;
+
+    #[derive(Debug, Clone, Copy)]
+    enum Message {
+        Order(u32),
+        Refund(u32),
+    }
+
+    // The transitions match any id, only the idempotency keys tell the messages apart
+    impl PartialEq for Message {
+        fn eq(&self, other: &Self) -> bool {
+            mem::discriminant(self) == mem::discriminant(other)
+        }
+    }
+
+    fn ids(sm: &Machine<'static, char, Message, Vec<Message>, (), Ready>) -> Vec<u32> {
+        let ids = sm.context().iter().map(|message| match message {
+            Message::Order(id) | Message::Refund(id) => *id,
+        });
+
+        ids.collect()
+    }
+
+    fn count(cx: ContextMut<char, Message, Vec<Message>>) {
+        cx.context.push(*cx.event);
+    }
+
+    fn machine(window: usize) -> Machine<'static, char, Message, Vec<Message>, (), Ready> {
+        let order = Builder::new('a').on(Message::Order(0)).go_to('a');
+
+        Machine::with_context(Vec::new())
+            .on_next(order.action(count))
+            .on_next(Builder::self_transition('a', Message::Refund(0)).action(count))
+            .dedupe_by(|event: &Message| match event {
+                Message::Order(id) => Some(("order", *id)),
+                Message::Refund(id) => Some(("refund", *id)),
+            })
+            .dedupe_window(window)
+            .start('a')
+    }
+
+    #[test]
+    fn dedupe_test() {
+        let mut sm = machine(8);
+
+        assert_eq!(sm.send(Message::Order(1)), Ok('a'));
+        assert!(sm.is_duplicate(&Message::Order(1)));
+        assert_eq!(sm.send(Message::Order(1)), Ok('a'));
+        assert!(sm.step(Message::Order(1)).unwrap().was_duplicate);
+
+        // The extractor namespaces the keys of each kind of message
+        assert!(!sm.step(Message::Refund(1)).unwrap().was_duplicate);
+        assert_eq!(ids(&sm), [1, 1]);
+        assert!(matches!(sm.context()[1], Message::Refund(1)));
+        assert_eq!(sm.seen_len(), 2);
+
+        sm.clear_seen();
+        assert_eq!(sm.seen_len(), 0);
+        sm.send(Message::Order(1)).unwrap();
+        assert_eq!(sm.context().len(), 3);
+    }
+
+    #[test]
+    fn dedupe_window_test() {
+        let mut sm = machine(2);
+
+        for id in [1, 2, 1, 3, 2] {
+            sm.send(Message::Order(id)).unwrap();
+        }
+
+        // Sending 1 again refreshed it, so 2 was the least recently seen when 3 arrived
+        assert_eq!(ids(&sm), [1, 2, 3, 2]);
+        assert_eq!(sm.seen_len(), 2);
+    }
+}
@@ -0,0 +1,439 @@
+use super::time_guard::TimeGuard;
+use super::{Build, Context, ContextMut, Machine, OnTransition, StatelessAction};
+use crate::clock::Clock;
+use crate::error::{BuildError, TransitionError};
+use crate::map::TransitionMap;
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Instant;
+
+// A transition of a definition, the action is stateless so the instances can run it at the same time.
+struct SharedNext<'a, S, E, Ctx> {
+    next: S,
+    is_final: bool,
+    action: Option<Box<StatelessAction<'a, S, E, Ctx>>>,
+    guard: Option<TimeGuard>,
+}
+
+struct Definition<'a, S, E, Ctx, F> {
+    transitions: TransitionMap<S, E, SharedNext<'a, S, E, Ctx>>,
+    on_transition: Option<Mutex<F>>,
+    clock: Box<dyn Clock + Send + Sync + 'a>,
+}
+
+/// The transitions and hooks of a state machine, built once and shared by many `MachineInstance`s.
+///
+/// Cloning a definition returns a handle to the same transitions.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// let definition = Machine::with_context_stateless(0)
+///     .on_next(Builder::new("pending").on("pay").go_to("paid").action(
+///         |cx: ContextMut<&str, &str, i32>| {
+///             *cx.context += 1;
+///         },
+///     ))
+///     .on_next(Builder::new("paid").on("ship").go_to("shipped").is_final())
+///     .into_definition()
+///     .unwrap();
+///
+/// let mut first = definition.instantiate("pending", 0);
+/// let mut second = definition.instantiate("pending", 10);
+///
+/// first.send("pay").unwrap();
+/// first.send("ship").unwrap();
+/// second.send("pay").unwrap();
+///
+/// assert!(first.is_done());
+/// assert_eq!(*second.current(), "paid");
+/// assert_eq!(*second.context(), 11);
+/// ```
+pub struct MachineDefinition<'a, S, E, Ctx, F = ()> {
+    inner: Arc<Definition<'a, S, E, Ctx, F>>,
+}
+
+impl<S, E, Ctx, F> Clone for MachineDefinition<'_, S, E, Ctx, F> {
+    fn clone(&self) -> Self {
+        MachineDefinition {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<S, E, Ctx, F> Debug for MachineDefinition<'_, S, E, Ctx, F>
+where
+    S: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MachineDefinition")
+            .field(
+                "states",
+                &self.inner.transitions.states().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl<'a, S, E, Ctx, F> Machine<'a, S, E, Ctx, F, Build, StatelessAction<'a, S, E, Ctx>> {
+    /// Converts the transitions, the `on_transition` hook and the clock of this machine into a definition
+    /// that can be instantiated many times without rebuilding them.
+    ///
+    /// The actions of a `StatelessMachine` don't keep state, so the instances run them at the same time
+    /// and a transition behaves the same for all of them.
+    /// Other configurations of the machine like timers or state data are not part of the definition.
+    ///
+    /// # Returns
+    /// - Err(BuildError::Unsupported): If a transition has a before action, a fire limit,
+    ///   guarded candidates or is an ignored event, these cannot be shared by the instances.
+    ///   Or if the machine has a configuration that changes how the instances handle the events,
+    ///   like `match_by_discriminant`, outcome states, a journal or the `on_done` and `on_error` hooks.
+    pub fn into_definition(self) -> Result<MachineDefinition<'a, S, E, Ctx, F>, BuildError> {
+        let extensions = &self.extensions;
+        let unsupported = [
+            (
+                extensions.match_by_discriminant,
+                "states matched by discriminant",
+            ),
+            (!extensions.outcomes.is_empty(), "outcome states"),
+            (extensions.catch_panics, "caught panics"),
+            (extensions.on_start.is_some(), "on_start hooks"),
+            (extensions.on_done.is_some(), "on_done hooks"),
+            (extensions.on_error.is_some(), "on_error hooks"),
+            (extensions.on_unhandled.is_some(), "on_unhandled hooks"),
+            (extensions.audit.is_some(), "on_audit hooks"),
+            (extensions.journal.is_some(), "journals"),
+            (extensions.dedupe.is_enabled(), "idempotency keys"),
+        ];
+
+        if let Some((_, feature)) = unsupported.into_iter().find(|(used, _)| *used) {
+            return Err(BuildError::Unsupported { feature });
+        }
+
+        for (_, _, next) in self.transitions.iter() {
+            let feature = if next.before.is_some() {
+                "before actions"
+            } else if next.limit.is_some() {
+                "fire limits"
+            } else if !next.candidates.is_empty() {
+                "guarded candidate transitions"
+            } else if next.ignored {
+                "ignored events"
+            } else {
+                continue;
+            };
+
+            return Err(BuildError::Unsupported { feature });
+        }
+
+        let transitions = self.transitions.map_values(|next| SharedNext {
+            next: next.next,
+            is_final: next.is_final,
+            action: next.action,
+            guard: next.guard,
+        });
+
+        Ok(MachineDefinition {
+            inner: Arc::new(Definition {
+                transitions,
+                on_transition: self.on_transition.map(Mutex::new),
+                clock: self.extensions.clock,
+            }),
+        })
+    }
+}
+
+impl<'a, S, E, Ctx, F> MachineDefinition<'a, S, E, Ctx, F> {
+    /// Returns a new instance of this definition in the given state.
+    pub fn instantiate(&self, initial_state: S, context: Ctx) -> MachineInstance<'a, S, E, Ctx, F> {
+        MachineInstance {
+            definition: self.clone(),
+            current: initial_state,
+            done: false,
+            context,
+            entered_at: self.inner.clock.now(),
+        }
+    }
+}
+
+/// A state machine that only holds its current state and context, created with `MachineDefinition::instantiate`.
+pub struct MachineInstance<'a, S, E, Ctx, F = ()> {
+    definition: MachineDefinition<'a, S, E, Ctx, F>,
+    current: S,
+    done: bool,
+    context: Ctx,
+    entered_at: Instant,
+}
+
+impl<S, E, Ctx, F> Debug for MachineInstance<'_, S, E, Ctx, F>
+where
+    S: Debug,
+    Ctx: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MachineInstance")
+            .field("current", &self.current)
+            .field("done", &self.done)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+impl<'a, S, E, Ctx, F> MachineInstance<'a, S, E, Ctx, F> {
+    /// Returns the definition of this instance.
+    pub fn definition(&self) -> &MachineDefinition<'a, S, E, Ctx, F> {
+        &self.definition
+    }
+
+    /// Returns the current state.
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// Returns the context used for this instance.
+    pub fn context(&self) -> &Ctx {
+        &self.context
+    }
+
+    /// Returns `true` if this instance had done executing.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<S, E, Ctx, F> MachineInstance<'_, S, E, Ctx, F>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+{
+    /// Triggers a transition and returns the previous state.
+    pub fn send(&mut self, event: E) -> Result<S, TransitionError> {
+        if self.done {
+            return Err(TransitionError::Done);
+        }
+
+        let definition = &*self.definition.inner;
+        let Some(SharedNext {
+            next,
+            is_final,
+            action,
+            guard,
+        }) = definition.transitions.get(&event, &self.current)
+        else {
+            return Err(TransitionError::InvalidTransition);
+        };
+
+        let elapsed = || {
+            definition
+                .clock
+                .now()
+                .saturating_duration_since(self.entered_at)
+        };
+
+        if guard.is_some_and(|guard| !guard.allows(elapsed())) {
+            return Err(TransitionError::GuardRejected);
+        }
+
+        let abort = Cell::new(None);
+        let finality = Cell::new(*is_final);
+
+        if let Some(f) = action.as_ref() {
+            f(ContextMut {
+                from: &self.current,
+                to: next,
+                event: &event,
+                context: &mut self.context,
+                state_data: None,
+                abort: &abort,
+                is_final: &finality,
+                sender: None,
+            });
+        }
+
+        // If the action cancelled the transition, the instance stays in the previous state
+        if let Some(abort) = abort.get() {
+            return Err(abort.into());
+        }
+
+        if let Some(f) = definition.on_transition.as_ref() {
+            lock(f).call(Context {
+                from: &self.current,
+                to: next,
+                event: &event,
+                context: &self.context,
+            });
+        }
+
+        if finality.get() {
+            self.done = true;
+        }
+
+        if self.current != *next {
+            self.entered_at = definition.clock.now();
+        }
+
+        Ok(std::mem::replace(&mut self.current, next.clone()))
+    }
+}
+
+// The hook is locked while running, a panic in other instance cannot corrupt the definition.
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{
+        Builder, Context, ContextMut, ErrorDecision, Machine, MachineDefinition,
+    };
+    use crate::clock::MockClock;
+    use crate::error::{BuildError, TransitionError};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Order {
+        Pending,
+        Paid,
+        Shipped,
+    }
+
+    type OnOrder = fn(Context<Order, &str, Vec<Order>>);
+
+    fn definition() -> MachineDefinition<'static, Order, &'static str, Vec<Order>, OnOrder> {
+        fn on_transition(_: Context<Order, &str, Vec<Order>>) {}
+
+        Machine::with_context_stateless(vec![])
+            .on_next(
+                Builder::new(Order::Pending)
+                    .on("pay")
+                    .go_to(Order::Paid)
+                    .action(|cx: ContextMut<Order, &str, Vec<Order>>| {
+                        cx.context.push(cx.from.clone());
+                    }),
+            )
+            .on_next(
+                Builder::new(Order::Paid)
+                    .on("ship")
+                    .go_to(Order::Shipped)
+                    .is_final(),
+            )
+            .on_transition(on_transition as OnOrder)
+            .into_definition()
+            .unwrap()
+    }
+
+    #[test]
+    fn definition_instances_test() {
+        let definition = definition();
+        let mut first = definition.instantiate(Order::Pending, vec![]);
+        let mut second = definition.instantiate(Order::Pending, vec![]);
+
+        assert_eq!(first.send("pay"), Ok(Order::Pending));
+        assert_eq!(first.send("ship"), Ok(Order::Paid));
+        assert_eq!(first.send("pay"), Err(TransitionError::Done));
+
+        // The other instance is not affected
+        assert_eq!(*second.current(), Order::Pending);
+        assert!(second.context().is_empty());
+        assert_eq!(second.send("ship"), Err(TransitionError::InvalidTransition));
+
+        second.send("pay").unwrap();
+        assert_eq!(second.context(), &[Order::Pending]);
+        assert!(!second.is_done());
+    }
+
+    #[test]
+    fn definition_threads_test() {
+        let definition = definition();
+
+        let handles = (0..4)
+            .map(|_| {
+                let mut instance = definition.instantiate(Order::Pending, vec![]);
+                std::thread::spawn(move || {
+                    instance.send("pay").unwrap();
+                    instance.send("ship").unwrap();
+                    instance.is_done()
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+    }
+
+    #[test]
+    fn definition_clock_test() {
+        let clock = MockClock::new();
+        let definition = Machine::new_stateless()
+            .on_next(
+                Builder::new(Order::Pending)
+                    .on("pay")
+                    .go_to(Order::Paid)
+                    .guard_after(Duration::from_secs(2)),
+            )
+            .with_clock(clock.clone())
+            .into_definition()
+            .unwrap();
+
+        let mut first = definition.instantiate(Order::Pending, ());
+        clock.advance(Duration::from_secs(2));
+        let mut second = definition.instantiate(Order::Pending, ());
+
+        // Each instance measures the time since its own entry with the clock of the machine
+        assert_eq!(first.send("pay"), Ok(Order::Pending));
+        assert_eq!(second.send("pay"), Err(TransitionError::GuardRejected));
+    }
+
+    #[test]
+    fn definition_unsupported_test() {
+        let result = Machine::new_stateless()
+            .on_next(
+                Builder::new(Order::Pending)
+                    .on("pay")
+                    .go_to(Order::Paid)
+                    .before(|_: ContextMut<Order, &str, ()>| {}),
+            )
+            .into_definition();
+
+        assert_eq!(
+            result.unwrap_err(),
+            BuildError::Unsupported {
+                feature: "before actions"
+            }
+        );
+    }
+
+    #[test]
+    fn definition_unsupported_config_test() {
+        let machine = || Machine::new_stateless().on_next(Builder::new(0).on('a').go_to(1));
+        let unsupported = |feature| Err(BuildError::Unsupported { feature });
+
+        let definition = machine().match_by_discriminant().into_definition();
+        assert_eq!(
+            definition.map(|_| ()),
+            unsupported("states matched by discriminant")
+        );
+
+        let definition = machine().success_states([1]).into_definition();
+        assert_eq!(definition.map(|_| ()), unsupported("outcome states"));
+
+        let definition = machine().on_done(|_, _| {}).into_definition();
+        assert_eq!(definition.map(|_| ()), unsupported("on_done hooks"));
+
+        let definition = machine()
+            .on_error(|_| ErrorDecision::Propagate)
+            .into_definition();
+        assert_eq!(definition.map(|_| ()), unsupported("on_error hooks"));
+
+        let definition = machine().on_unhandled(|_| {}).into_definition();
+        assert_eq!(definition.map(|_| ()), unsupported("on_unhandled hooks"));
+
+        let definition = machine().dedupe_by(|e: &char| Some(*e)).into_definition();
+        assert_eq!(definition.map(|_| ()), unsupported("idempotency keys"));
+    }
+}
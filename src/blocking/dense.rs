@@ -0,0 +1,464 @@
+use super::{Build, Context, ContextMut, IntoTransition, Machine, Ready, SendAction};
+use super::{OnTransition, Transition};
+use crate::error::TransitionError;
+use std::cell::Cell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+/// A type with a fixed number of values, each one mapped to an index in `0..COUNT`.
+///
+/// Usually implemented for fieldless enums.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::StateIndex;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq)]
+/// enum Light {
+///     Red,
+///     Green,
+/// }
+///
+/// impl StateIndex for Light {
+///     const COUNT: usize = 2;
+///
+///     fn index(&self) -> usize {
+///         *self as usize
+///     }
+///
+///     fn from_index(index: usize) -> Self {
+///         [Light::Red, Light::Green][index]
+///     }
+/// }
+/// ```
+pub trait StateIndex: Sized {
+    /// The number of values of this type.
+    const COUNT: usize;
+
+    /// Returns the index of this value, must be lower than `COUNT`.
+    fn index(&self) -> usize;
+
+    /// Returns the value for the given index.
+    fn from_index(index: usize) -> Self;
+}
+
+// A transition in the table of a `DenseMachine`.
+struct DenseNext<'a, S, E, Ctx> {
+    next: usize,
+    is_final: bool,
+    action: Option<Box<SendAction<'a, S, E, Ctx>>>,
+}
+
+/// A state machine which transitions are stored in a table indexed by state and event.
+///
+/// Finding a transition is a constant time operation, and sending an event doesn't allocate.
+/// The states are not required to be `Clone`, they are recreated from their index.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// #[derive(Debug, PartialEq)]
+/// enum Light {
+///     Red,
+///     Green,
+/// }
+///
+/// impl StateIndex for Light {
+///     const COUNT: usize = 2;
+///
+///     fn index(&self) -> usize {
+///         match self {
+///             Light::Red => 0,
+///             Light::Green => 1,
+///         }
+///     }
+///
+///     fn from_index(index: usize) -> Self {
+///         match index {
+///             0 => Light::Red,
+///             _ => Light::Green,
+///         }
+///     }
+/// }
+///
+/// struct Switch;
+///
+/// impl StateIndex for Switch {
+///     const COUNT: usize = 1;
+///
+///     fn index(&self) -> usize {
+///         0
+///     }
+///
+///     fn from_index(_: usize) -> Self {
+///         Switch
+///     }
+/// }
+///
+/// let mut sm = DenseMachine::new()
+///     .on_next(Builder::new(Light::Red).on(Switch).go_to(Light::Green))
+///     .on_next(Builder::new(Light::Green).on(Switch).go_to(Light::Red))
+///     .start(Light::Red);
+///
+/// assert_eq!(sm.send(Switch), Ok(Light::Red));
+/// assert_eq!(sm.current(), Light::Green);
+/// ```
+pub struct DenseMachine<'a, S, E, Ctx = (), F = (), Step = Build> {
+    table: Vec<Option<DenseNext<'a, S, E, Ctx>>>,
+    current: usize,
+    done: bool,
+    context: Ctx,
+    on_transition: Option<F>,
+    _marker: PhantomData<Step>,
+}
+
+impl<S, E, Ctx, F, Step> Debug for DenseMachine<'_, S, E, Ctx, F, Step>
+where
+    S: StateIndex + Debug,
+    Ctx: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DenseMachine")
+            .field("current", &S::from_index(self.current))
+            .field("done", &self.done)
+            .field("context", &self.context)
+            .finish()
+    }
+}
+
+impl<'a, S, E> Machine<'a, S, E, (), (), Build>
+where
+    S: StateIndex,
+    E: StateIndex,
+{
+    /// Returns a new `DenseMachine`.
+    pub fn new_dense() -> DenseMachine<'a, S, E> {
+        DenseMachine::new()
+    }
+}
+
+impl<'a, S, E> DenseMachine<'a, S, E>
+where
+    S: StateIndex,
+    E: StateIndex,
+{
+    /// Returns a new `DenseMachine`.
+    pub fn new() -> Self {
+        DenseMachine::with_context(())
+    }
+}
+
+impl<'a, S, E> Default for DenseMachine<'a, S, E>
+where
+    S: StateIndex,
+    E: StateIndex,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, S, E, Ctx> DenseMachine<'a, S, E, Ctx>
+where
+    S: StateIndex,
+    E: StateIndex,
+{
+    /// Returns a new `DenseMachine` with the given context.
+    pub fn with_context(context: Ctx) -> Self {
+        let mut table = Vec::with_capacity(S::COUNT * E::COUNT);
+        table.resize_with(S::COUNT * E::COUNT, || None);
+
+        DenseMachine {
+            table,
+            current: 0,
+            done: false,
+            context,
+            on_transition: None,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Adds a transition from a state to other based on an event.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state, or the transition has a before action, a time guard or a fire limit.
+    pub fn on_next(mut self, transition: impl IntoTransition<'a, S, E, Ctx>) -> Self {
+        let Transition {
+            from,
+            to,
+            event,
+            action,
+            before,
+            is_final,
+            guard,
+            limit,
+            ..
+        } = transition.into_transition();
+
+        assert!(
+            before.is_none(),
+            "before actions are not supported by `DenseMachine`"
+        );
+        assert!(
+            guard.is_none(),
+            "time guards are not supported by `DenseMachine`"
+        );
+        assert!(
+            limit.is_none(),
+            "fire limits are not supported by `DenseMachine`"
+        );
+
+        let state = from.index();
+        assert!(state < S::COUNT, "state index out of `StateIndex::COUNT`");
+
+        let entry = &mut self.table[slot(state, &event)];
+
+        if entry.is_some() {
+            panic!("a transition already exists for the event");
+        }
+
+        *entry = Some(DenseNext {
+            next: to.index(),
+            is_final,
+            action,
+        });
+
+        self
+    }
+
+    /// Adds a function that is called when a transition occurs.
+    pub fn on_transition<F>(self, on_transition: F) -> DenseMachine<'a, S, E, Ctx, F>
+    where
+        F: FnMut(Context<S, E, Ctx>),
+    {
+        DenseMachine {
+            table: self.table,
+            current: self.current,
+            done: self.done,
+            context: self.context,
+            on_transition: Some(on_transition),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, F> DenseMachine<'a, S, E, Ctx, F, Build>
+where
+    S: StateIndex,
+{
+    /// Starts this state machine with the given state.
+    pub fn start(self, initial_state: S) -> DenseMachine<'a, S, E, Ctx, F, Ready> {
+        DenseMachine {
+            table: self.table,
+            current: initial_state.index(),
+            done: false,
+            context: self.context,
+            on_transition: self.on_transition,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<S, E, Ctx, F> DenseMachine<'_, S, E, Ctx, F, Ready>
+where
+    S: StateIndex,
+    E: StateIndex,
+    F: OnTransition<S, E, Ctx>,
+{
+    /// Returns the current state.
+    pub fn current(&self) -> S {
+        S::from_index(self.current)
+    }
+
+    /// Returns the context of this state machine.
+    pub fn context(&self) -> &Ctx {
+        &self.context
+    }
+
+    /// Returns `true` if the state machine reached a final state.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Sends an event to this state machine and returns the previous state.
+    pub fn send(&mut self, event: E) -> Result<S, TransitionError> {
+        if self.done {
+            return Err(TransitionError::Done);
+        }
+
+        let slot = slot(self.current, &event);
+        let Some(DenseNext {
+            next,
+            is_final,
+            action,
+        }) = self.table[slot].as_mut()
+        else {
+            return Err(TransitionError::InvalidTransition);
+        };
+
+        let prev = std::mem::replace(&mut self.current, *next);
+        let finality = Cell::new(*is_final);
+
+        let from = S::from_index(prev);
+        let to = S::from_index(*next);
+
+        if let Some(f) = action.as_mut() {
+            let abort = Cell::new(None);
+            f.call(ContextMut {
+                from: &from,
+                to: &to,
+                event: &event,
+                context: &mut self.context,
+                state_data: None,
+                abort: &abort,
+                is_final: &finality,
+                sender: None,
+            });
+
+            if let Some(abort) = abort.get() {
+                self.current = prev;
+                return Err(abort.into());
+            }
+        }
+
+        if finality.get() {
+            self.done = true;
+        }
+
+        if let Some(f) = self.on_transition.as_mut() {
+            f.call(Context {
+                from: &from,
+                to: &to,
+                event: &event,
+                context: &self.context,
+            });
+        }
+
+        Ok(from)
+    }
+}
+
+// Returns the position in the table of the transition for the event from the state with the given index.
+fn slot<E: StateIndex>(state: usize, event: &E) -> usize {
+    let event = event.index();
+    assert!(event < E::COUNT, "event index out of `StateIndex::COUNT`");
+    state * E::COUNT + event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StateIndex;
+    use crate::blocking::{Builder, ContextMut, DenseMachine, Machine};
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running,
+        Paused,
+        Stopped,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Event {
+        Start,
+        Pause,
+        Stop,
+    }
+
+    impl StateIndex for State {
+        const COUNT: usize = 4;
+
+        fn index(&self) -> usize {
+            *self as usize
+        }
+
+        fn from_index(index: usize) -> Self {
+            [State::Idle, State::Running, State::Paused, State::Stopped][index]
+        }
+    }
+
+    impl StateIndex for Event {
+        const COUNT: usize = 3;
+
+        fn index(&self) -> usize {
+            *self as usize
+        }
+
+        fn from_index(index: usize) -> Self {
+            [Event::Start, Event::Pause, Event::Stop][index]
+        }
+    }
+
+    fn count(cx: ContextMut<State, Event, usize>) {
+        *cx.context += 1;
+    }
+
+    fn definition() -> Vec<(State, Event, State, bool)> {
+        vec![
+            (State::Idle, Event::Start, State::Running, false),
+            (State::Running, Event::Pause, State::Paused, false),
+            (State::Paused, Event::Start, State::Running, false),
+            (State::Running, Event::Stop, State::Stopped, true),
+            (State::Paused, Event::Stop, State::Stopped, true),
+        ]
+    }
+
+    #[test]
+    fn dense_same_behavior_test() {
+        let mut machine = Machine::with_context(0);
+        let mut dense = DenseMachine::with_context(0);
+
+        for (from, event, to, is_final) in definition() {
+            let mut builder = Builder::new(from).on(event).go_to(to).action(count);
+            if is_final {
+                builder = builder.is_final();
+            }
+            machine = machine.on_next(builder);
+
+            let mut builder = Builder::new(from).on(event).go_to(to).action(count);
+            if is_final {
+                builder = builder.is_final();
+            }
+            dense = dense.on_next(builder);
+        }
+
+        let mut machine = machine.start(State::Idle);
+        let mut dense = dense.start(State::Idle);
+
+        let events = [
+            Event::Pause,
+            Event::Start,
+            Event::Pause,
+            Event::Start,
+            Event::Stop,
+            Event::Start,
+        ];
+
+        for event in events {
+            assert_eq!(machine.send(event), dense.send(event));
+            assert_eq!(*machine.current(), dense.current());
+            assert_eq!(machine.is_done(), dense.is_done());
+        }
+
+        assert_eq!(machine.context(), dense.context());
+    }
+
+    #[test]
+    #[should_panic]
+    fn dense_duplicate_test() {
+        let _ = DenseMachine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Running),
+            )
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Paused),
+            );
+    }
+}
@@ -0,0 +1,207 @@
+use super::{Machine, Next};
+use crate::map::TransitionMap;
+
+/// A transition as compared by `Machine::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffTransition<S, E> {
+    /// The state where the transition starts.
+    pub from: S,
+
+    /// The event that triggers the transition.
+    pub event: E,
+
+    /// The state where the transition ends.
+    pub to: S,
+
+    /// Whether the transition completes the state machine.
+    pub is_final: bool,
+
+    /// Whether the transition has an action.
+    pub has_action: bool,
+}
+
+/// The structural differences between two state machines, returned by `Machine::diff`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachineDiff<S, E> {
+    /// The transitions only in the other machine.
+    pub added_transitions: Vec<DiffTransition<S, E>>,
+
+    /// The transitions only in this machine.
+    pub removed_transitions: Vec<DiffTransition<S, E>>,
+
+    /// The transitions from the same state and event that changed, as `(old, new)`.
+    pub retargeted_transitions: Vec<(DiffTransition<S, E>, DiffTransition<S, E>)>,
+
+    /// The states only in the other machine.
+    pub added_states: Vec<S>,
+
+    /// The states only in this machine.
+    pub removed_states: Vec<S>,
+}
+
+impl<S, E> MachineDiff<S, E> {
+    /// Returns `true` if both machines have the same structure.
+    pub fn is_empty(&self) -> bool {
+        self.added_transitions.is_empty()
+            && self.removed_transitions.is_empty()
+            && self.retargeted_transitions.is_empty()
+            && self.added_states.is_empty()
+            && self.removed_states.is_empty()
+    }
+}
+
+fn transitions<S, E, A: ?Sized>(map: &TransitionMap<S, E, Next<S, A>>) -> Vec<DiffTransition<S, E>>
+where
+    S: Clone,
+    E: Clone,
+{
+    map.iter()
+        .flat_map(|(from, event, first)| {
+            first.candidates().map(|next| DiffTransition {
+                from: from.clone(),
+                event: event.clone(),
+                to: next.next.clone(),
+                is_final: next.is_final,
+                has_action: next.action.is_some(),
+            })
+        })
+        .collect()
+}
+
+fn states<S: PartialEq + Clone, E>(transitions: &[DiffTransition<S, E>]) -> Vec<S> {
+    let mut states = Vec::new();
+
+    for state in transitions.iter().flat_map(|t| [&t.from, &t.to]) {
+        if !states.contains(state) {
+            states.push(state.clone());
+        }
+    }
+
+    states
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: PartialEq + Clone,
+    E: PartialEq + Clone,
+{
+    /// Compares the transitions of this machine with the ones of other machine,
+    /// the actions are only compared by whether the transitions have one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let old = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"));
+    ///
+    /// let new = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_next(Builder::new("running").on("stop").go_to("idle"));
+    ///
+    /// let diff = old.diff(&new);
+    /// assert_eq!(diff.added_transitions.len(), 1);
+    /// assert!(diff.removed_transitions.is_empty());
+    /// assert!(old.diff(&old).is_empty());
+    /// ```
+    pub fn diff<Ctx2, F2, Step2, A2: ?Sized>(
+        &self,
+        other: &Machine<'_, S, E, Ctx2, F2, Step2, A2>,
+    ) -> MachineDiff<S, E> {
+        let old = transitions(&self.transitions);
+        let new = transitions(&other.transitions);
+
+        let find = |list: &[DiffTransition<S, E>], t: &DiffTransition<S, E>| {
+            list.iter()
+                .find(|x| x.from == t.from && x.event == t.event)
+                .cloned()
+        };
+
+        let mut removed_transitions = Vec::new();
+        let mut retargeted_transitions = Vec::new();
+
+        for t in &old {
+            match find(&new, t) {
+                None => removed_transitions.push(t.clone()),
+                Some(n) if n != *t => retargeted_transitions.push((t.clone(), n)),
+                Some(_) => {}
+            }
+        }
+
+        let added_transitions = new
+            .iter()
+            .filter(|t| find(&old, t).is_none())
+            .cloned()
+            .collect();
+
+        let (old_states, new_states) = (states(&old), states(&new));
+
+        MachineDiff {
+            added_transitions,
+            removed_transitions,
+            retargeted_transitions,
+            added_states: only_in(&new_states, &old_states),
+            removed_states: only_in(&old_states, &new_states),
+        }
+    }
+}
+
+fn only_in<S: PartialEq + Clone>(states: &[S], other: &[S]) -> Vec<S> {
+    states
+        .iter()
+        .filter(|s| !other.contains(s))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DiffTransition;
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    fn noop(_: ContextMut<char, u8, ()>) {}
+
+    #[test]
+    fn diff_test() {
+        let old = Machine::new()
+            .on_next(Builder::new('a').on(1).go_to('b'))
+            .on_next(Builder::new('b').on(2).go_to('c'))
+            .on_next(Builder::new('c').on(3).go_to('a'))
+            .start('a');
+
+        let new = Machine::new()
+            .on_next(Builder::new('a').on(1).go_to('b'))
+            .on_next(Builder::new('b').on(2).go_to('d').is_final())
+            .on_next(Builder::new('a').on(4).go_to('a').action(noop));
+
+        let diff = old.diff(&new);
+        let transition = |from, event, to, is_final, has_action| DiffTransition {
+            from,
+            event,
+            to,
+            is_final,
+            has_action,
+        };
+
+        assert_eq!(
+            diff.added_transitions,
+            [transition('a', 4, 'a', false, true)]
+        );
+        assert_eq!(
+            diff.removed_transitions,
+            [transition('c', 3, 'a', false, false)]
+        );
+        assert_eq!(
+            diff.retargeted_transitions,
+            [(
+                transition('b', 2, 'c', false, false),
+                transition('b', 2, 'd', true, false)
+            )]
+        );
+        assert_eq!(diff.added_states, ['d']);
+        assert_eq!(diff.removed_states, ['c']);
+        assert!(!diff.is_empty());
+        assert!(new.diff(&new).is_empty());
+    }
+}
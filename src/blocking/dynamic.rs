@@ -0,0 +1,110 @@
+use super::machine::{insert_next, split};
+use super::{IntoTransition, Machine, Ready};
+use crate::error::BuildError;
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Ready, A, C>
+where
+    S: PartialEq,
+    E: PartialEq,
+{
+    /// Adds a transition to this running state machine.
+    ///
+    /// # Returns
+    /// - Ok(()): If the transition was added.
+    /// - Err(BuildError::DuplicateTransition): If a transition already exists for the event from the state,
+    ///   and any of them is not guarded.
+    /// - Err(BuildError::LimitExceeded): If adding the transition exceeds the `Machine::limits`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new().start("idle");
+    ///
+    /// sm.add_transition(Builder::new("idle").on("start").go_to("running"))
+    ///     .unwrap();
+    ///
+    /// assert!(sm.send("start").is_ok());
+    /// ```
+    pub fn add_transition(
+        &mut self,
+        transition: impl IntoTransition<'a, S, E, Ctx, A>,
+    ) -> Result<(), BuildError> {
+        let (event, from, next) = split(transition);
+
+        let added = self.extensions.check_limits(&event, &from, &next.next)?;
+
+        insert_next(&mut self.transitions, event, from, next)
+            .map_err(|_| BuildError::DuplicateTransition)?;
+
+        self.extensions.count_added(added);
+        Ok(())
+    }
+
+    /// Removes the transition for the event from the given state,
+    /// returns `true` if the transition existed.
+    ///
+    /// Removing a transition out of the current state is allowed, the next `send` of the event just fails.
+    pub fn remove_transition(&mut self, from: &S, event: &E) -> bool {
+        match self.transitions.remove(event, from) {
+            Some(next) => {
+                self.extensions.count_removed(next.candidates().count());
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::clock::MockClock;
+    use crate::error::{BuildError, TransitionError};
+    use std::time::Duration;
+
+    #[test]
+    fn add_and_remove_transition_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new(1).on('a').go_to(2))
+            .start(1);
+
+        sm.add_transition(Builder::new(2).on('b').go_to(1)).unwrap();
+        assert_eq!(
+            sm.add_transition(Builder::new(2).on('b').go_to(3)),
+            Err(BuildError::DuplicateTransition)
+        );
+
+        sm.send('a').unwrap();
+        sm.send('b').unwrap();
+        assert_eq!(*sm.current(), 1);
+
+        assert!(sm.remove_transition(&1, &'a'));
+        assert!(!sm.remove_transition(&1, &'a'));
+        assert_eq!(sm.send('a'), Err(TransitionError::InvalidTransition));
+        assert_eq!(*sm.current(), 1);
+    }
+
+    #[test]
+    fn add_guarded_transition_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(Builder::new(1).on('a').go_to(2))
+            .with_clock(clock.clone())
+            .start(1);
+
+        sm.send('a').unwrap();
+        clock.advance(Duration::from_secs(3));
+
+        // The guard measures the time since entering the state, not since adding the transition
+        sm.add_transition(
+            Builder::new(2)
+                .on('b')
+                .go_to(1)
+                .guard_after(Duration::from_secs(2)),
+        )
+        .unwrap();
+        assert_eq!(sm.send('b'), Ok(2));
+    }
+}
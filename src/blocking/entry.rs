@@ -0,0 +1,75 @@
+use super::{Build, Machine, Ready};
+
+/// The context of the initial state when a machine starts, used by `Machine::start_with_entry`.
+#[derive(Debug)]
+pub struct EntryContext<'a, S, Ctx> {
+    /// The state the machine starts in.
+    pub state: &'a S,
+
+    /// The mutable data associated to the state machine.
+    pub context: &'a mut Ctx,
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized> Machine<'a, S, E, Ctx, F, Build, A> {
+    /// Starts this state machine with the given state, and calls `entry` once for it.
+    ///
+    /// The machine doesn't transition to start, so no action runs and `on_transition` is not called,
+    /// `entry` can do the setup of the initial state that a transition into the state would do.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::with_context(Vec::new())
+    ///     .on_next(Builder::new("connecting").on("connected").go_to("online"))
+    ///     .start_with_entry("connecting", |cx: EntryContext<&str, Vec<String>>| {
+    ///         cx.context.push(format!("entered {}", cx.state));
+    ///     });
+    ///
+    /// assert_eq!(*sm.context(), ["entered connecting"]);
+    /// ```
+    pub fn start_with_entry(
+        self,
+        initial_state: S,
+        entry: impl FnOnce(EntryContext<S, Ctx>),
+    ) -> Machine<'a, S, E, Ctx, F, Ready, A> {
+        let mut machine = self.start(initial_state);
+
+        if let Some(state) = &machine.current {
+            entry(EntryContext {
+                state,
+                context: &mut machine.context,
+            });
+        }
+
+        machine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EntryContext;
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[test]
+    fn start_with_entry_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::new('a').on(0).go_to('b').action(
+                |cx: ContextMut<char, u8, Vec<String>>| {
+                    cx.context.push(format!("action {}", cx.to))
+                },
+            ))
+            .on_transition(|cx| assert!(!cx.context.is_empty()))
+            .start_with_entry('a', |cx: EntryContext<char, Vec<String>>| {
+                assert!(cx.context.is_empty());
+                cx.context.push(format!("entry {}", cx.state));
+            });
+
+        assert_eq!(*sm.current(), 'a');
+        assert_eq!(*sm.context(), ["entry a"]);
+
+        sm.send(0).unwrap();
+        assert_eq!(*sm.context(), ["entry a", "action b"]);
+    }
+}
@@ -0,0 +1,202 @@
+use super::Machine;
+use std::collections::VecDeque;
+
+/// A transition visited by `Machine::explore`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExploredEdge<S, E> {
+    /// The state where the transition starts.
+    pub from: S,
+
+    /// The event that triggers the transition.
+    pub event: E,
+
+    /// The state where the transition ends.
+    pub to: S,
+
+    /// Whether the transition completes the state machine.
+    pub is_final: bool,
+}
+
+/// The states and transitions reachable from an initial state, returned by `Machine::explore`.
+#[derive(Debug, Clone)]
+pub struct Exploration<S, E> {
+    // The reachable states, in the order they were discovered.
+    states: Vec<S>,
+
+    // The shortest distance from the initial state of each state in `states`.
+    distances: Vec<usize>,
+
+    // The visited transitions.
+    edges: Vec<ExploredEdge<S, E>>,
+}
+
+impl<S, E> Exploration<S, E>
+where
+    S: PartialEq,
+{
+    /// Returns `true` if the given state is reachable.
+    pub fn contains(&self, state: &S) -> bool {
+        self.states.contains(state)
+    }
+
+    /// Returns an iterator over the reachable states, in the order they were discovered.
+    pub fn states(&self) -> impl Iterator<Item = &S> {
+        self.states.iter()
+    }
+
+    /// Returns an iterator over the visited transitions.
+    pub fn edges(&self) -> impl Iterator<Item = &ExploredEdge<S, E>> {
+        self.edges.iter()
+    }
+
+    /// Returns the min number of transitions to reach the given state from the initial state.
+    pub fn distance(&self, state: &S) -> Option<usize> {
+        let index = self.states.iter().position(|s| s == state)?;
+        Some(self.distances[index])
+    }
+
+    /// Returns the reachable states that have no path to the given state.
+    pub fn states_without_path_to(&self, target: &S) -> Vec<&S> {
+        let mut can_reach = self.states.iter().map(|s| s == target).collect::<Vec<_>>();
+
+        let mut queue = self
+            .states
+            .iter()
+            .enumerate()
+            .filter(|(_, s)| *s == target)
+            .map(|(i, _)| i)
+            .collect::<VecDeque<_>>();
+
+        while let Some(index) = queue.pop_front() {
+            let state = &self.states[index];
+
+            for edge in self.edges.iter().filter(|e| &e.to == state) {
+                let from = self.states.iter().position(|s| s == &edge.from).unwrap();
+                if !can_reach[from] {
+                    can_reach[from] = true;
+                    queue.push_back(from);
+                }
+            }
+        }
+
+        self.states
+            .iter()
+            .zip(can_reach)
+            .filter(|(_, reach)| !reach)
+            .map(|(s, _)| s)
+            .collect()
+    }
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: PartialEq + Clone,
+    E: Clone,
+{
+    /// Returns all the states and transitions reachable from the given state.
+    ///
+    /// The transitions from the targets of final transitions are not followed, because the machine is done on those.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on(1).go_to("working"))
+    ///     .on_next(Builder::new("working").on(2).go_to("idle"))
+    ///     .on_next(Builder::new("working").on(3).go_to("stuck"))
+    ///     .on_next(Builder::new("working").on(4).go_to("done").is_final());
+    ///
+    /// let exploration = sm.explore(&"idle");
+    ///
+    /// assert!(exploration.contains(&"done"));
+    /// assert_eq!(exploration.distance(&"done"), Some(2));
+    /// assert_eq!(exploration.states_without_path_to(&"done"), vec![&"stuck"]);
+    /// ```
+    pub fn explore(&self, initial: &S) -> Exploration<S, E> {
+        let mut states = vec![initial.clone()];
+        let mut distances = vec![0];
+        let mut finished = vec![false];
+        let mut edges = Vec::new();
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(index) = queue.pop_front() {
+            if finished[index] {
+                continue;
+            }
+
+            for (event, next) in self.transitions.outgoing(&states[index]) {
+                edges.push(ExploredEdge {
+                    from: states[index].clone(),
+                    event: event.clone(),
+                    to: next.next.clone(),
+                    is_final: next.is_final,
+                });
+
+                if !states.contains(&next.next) {
+                    states.push(next.next.clone());
+                    distances.push(distances[index] + 1);
+                    finished.push(next.is_final);
+                    queue.push_back(states.len() - 1);
+                }
+            }
+        }
+
+        Exploration {
+            states,
+            distances,
+            edges,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Draft,
+        Review,
+        Published,
+        Archived,
+        Orphan,
+    }
+
+    #[test]
+    fn explore_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new(State::Draft).on('s').go_to(State::Review))
+            .on_next(Builder::new(State::Review).on('r').go_to(State::Draft))
+            .on_next(
+                Builder::new(State::Review)
+                    .on('p')
+                    .go_to(State::Published)
+                    .is_final(),
+            )
+            .on_next(Builder::new(State::Review).on('a').go_to(State::Archived))
+            .on_next(Builder::new(State::Orphan).on('s').go_to(State::Draft))
+            .start(State::Draft);
+
+        let exploration = sm.explore(sm.current());
+
+        assert_eq!(exploration.states().count(), 4);
+        assert_eq!(exploration.edges().count(), 4);
+        assert!(!exploration.contains(&State::Orphan));
+
+        assert_eq!(exploration.distance(&State::Draft), Some(0));
+        assert_eq!(exploration.distance(&State::Review), Some(1));
+        assert_eq!(exploration.distance(&State::Archived), Some(2));
+        assert_eq!(exploration.distance(&State::Orphan), None);
+
+        assert_eq!(
+            exploration.states_without_path_to(&State::Published),
+            vec![&State::Archived]
+        );
+        assert_eq!(
+            exploration.states_without_path_to(&State::Draft),
+            vec![&State::Published, &State::Archived]
+        );
+    }
+}
@@ -0,0 +1,142 @@
+use super::audit::Audit;
+use super::dedupe::Dedupe;
+use super::journal::Journal;
+use super::lifecycle::LifecycleHook;
+use super::on_error::OnError;
+use super::sender::EventSender;
+use super::size_limits::Sizes;
+use super::state_data::StateData;
+use super::timer::Timers;
+use super::undo::UndoHistory;
+use super::validate::Invariants;
+use super::{Context, FinishInfo, Outcome, UnhandledContext};
+use crate::clock::{Clock, SystemClock};
+use std::time::Instant;
+
+// A callback called with the events that don't trigger any transition.
+pub(crate) type OnUnhandled<'a, S, E, Ctx> =
+    Box<dyn FnMut(UnhandledContext<S, E, Ctx>) + Send + Sync + 'a>;
+
+// A transition hook set after the machine started, in place of the `on_transition` of the builder.
+pub(crate) type OnTransitionHook<'a, S, E, Ctx> =
+    Box<dyn FnMut(Context<S, E, Ctx>) + Send + Sync + 'a>;
+
+// The optional features of a machine, grouped so they move together between the machine steps.
+pub(crate) struct Extensions<'a, S, E, Ctx> {
+    // The source of time used for timers.
+    pub(crate) clock: Box<dyn Clock + Send + Sync + 'a>,
+
+    // The events scheduled after dwelling in a state.
+    pub(crate) timers: Timers<S, E>,
+
+    // Called when an event is rejected.
+    pub(crate) on_unhandled: Option<OnUnhandled<'a, S, E, Ctx>>,
+
+    // Called when an event fails, decides whether the error is returned.
+    pub(crate) on_error: Option<OnError<'a, S, E, Ctx>>,
+
+    // Called when a transition occurs, replaces the `on_transition` of the machine.
+    pub(crate) on_transition: Option<OnTransitionHook<'a, S, E, Ctx>>,
+
+    // Whether the transitions match the current state by its enum variant.
+    pub(crate) match_by_discriminant: bool,
+
+    // The data associated to each state.
+    pub(crate) state_data: StateData<S>,
+
+    // The last known position of the current state in the transition map.
+    pub(crate) node_hint: Option<usize>,
+
+    // Whether the panics of the callbacks are caught.
+    pub(crate) catch_panics: bool,
+
+    // Whether a callback panicked.
+    pub(crate) poisoned: bool,
+
+    // Whether the machine rejects the events until it is resumed.
+    pub(crate) paused: bool,
+
+    // The events posted, queued from actions or from other threads, and not processed yet.
+    pub(crate) sender: EventSender<E>,
+
+    // Records the transitions before their effects are observable.
+    pub(crate) journal: Option<Journal<'a, S, E>>,
+
+    // When the current state was entered, read by the timers and the time guards.
+    pub(crate) entered_at: Instant,
+
+    // The states before the last transitions, if they can be undone.
+    pub(crate) undo: Option<UndoHistory<S, Ctx>>,
+
+    // The number of scoped transitions not removed yet.
+    pub(crate) scoped: usize,
+
+    // The events sent while there are scoped transitions, to expire them by count.
+    pub(crate) sends: u64,
+
+    // The checks of the states added to the machine, if enabled with `validate_invariants`.
+    pub(crate) invariants: Option<Invariants<S>>,
+
+    // Called with the context before and after each transition.
+    pub(crate) audit: Option<Audit<'a, S, E, Ctx>>,
+
+    // The transition that completed the machine, if it is done.
+    pub(crate) finished: Option<FinishInfo<S, E>>,
+
+    // The states tagged with an outcome, entering any of them completes the machine.
+    pub(crate) outcomes: Vec<(S, Outcome)>,
+
+    // The human readable name of the machine, used in diagnostics.
+    pub(crate) name: Option<String>,
+
+    // Called when the machine starts.
+    pub(crate) on_start: Option<LifecycleHook<'a, S, Ctx>>,
+
+    // Called when the machine is done.
+    pub(crate) on_done: Option<LifecycleHook<'a, S, Ctx>>,
+
+    // The maximum size of the machine and its current size, if it is limited.
+    pub(crate) sizes: Option<Sizes<S, E>>,
+
+    // The idempotency keys of the events already handled, if enabled with `dedupe_by`.
+    pub(crate) dedupe: Dedupe<'a, E>,
+}
+
+impl<S, E, Ctx> Extensions<'_, S, E, Ctx> {
+    pub(crate) fn new() -> Self {
+        Extensions {
+            clock: Box::new(SystemClock),
+            timers: Timers::new(),
+            on_unhandled: None,
+            on_error: None,
+            on_transition: None,
+            match_by_discriminant: false,
+            state_data: StateData::new(),
+            node_hint: None,
+            catch_panics: false,
+            poisoned: false,
+            paused: false,
+            sender: EventSender::new(),
+            journal: None,
+            entered_at: Instant::now(),
+            undo: None,
+            scoped: 0,
+            sends: 0,
+            invariants: None,
+            audit: None,
+            finished: None,
+            outcomes: Vec::new(),
+            name: None,
+            on_start: None,
+            on_done: None,
+            sizes: None,
+            dedupe: Dedupe::new(),
+        }
+    }
+
+    // Called each time the machine enters a state other than the current one.
+    pub(crate) fn enter(&mut self) {
+        self.entered_at = self.clock.now();
+        self.timers.enter();
+    }
+}
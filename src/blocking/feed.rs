@@ -0,0 +1,176 @@
+use super::{Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+
+/// The result of sending a sequence of events with `Machine::feed`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FeedSummary<S, E> {
+    /// The number of events sent successfully.
+    pub consumed: usize,
+
+    /// The state of the machine after the events.
+    pub state: S,
+
+    /// Whether the machine is done.
+    pub is_done: bool,
+
+    /// The event that failed and its error, if any.
+    pub error: Option<(E, TransitionError)>,
+}
+
+impl<S, E> FeedSummary<S, E> {
+    /// Returns `true` if no event failed.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// An iterator over the results of sending each event of other iterator, returned by `Machine::iter_on`.
+pub struct IterOn<'m, M, I> {
+    machine: &'m mut M,
+    events: I,
+}
+
+impl<'a, 'm, S, E, Ctx, F, A, I> Iterator for IterOn<'m, Machine<'a, S, E, Ctx, F, Ready, A>, I>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+    I: Iterator<Item = E>,
+{
+    type Item = Result<S, TransitionError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.events.next()?;
+        Some(self.machine.send(event))
+    }
+}
+
+impl<'a, S, E, Ctx, F, A> Machine<'a, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends each event, stopping at the first error or when the machine is done.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::TransitionError;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new(0).on('a').go_to(1))
+    ///     .on_next(Builder::new(1).on('b').go_to(2))
+    ///     .start(0);
+    ///
+    /// let summary = sm.feed("abc".chars());
+    ///
+    /// assert_eq!(summary.consumed, 2);
+    /// assert_eq!(summary.state, 2);
+    /// assert_eq!(summary.error, Some(('c', TransitionError::InvalidTransition)));
+    /// ```
+    pub fn feed<I>(&mut self, events: I) -> FeedSummary<S, E>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        let mut consumed = 0;
+        let mut error = None;
+
+        let mut events = events.into_iter();
+
+        while !self.done {
+            let Some(event) = events.next() else {
+                break;
+            };
+
+            match self.send_or_return(event) {
+                Ok(_) => consumed += 1,
+                Err((err, event)) => {
+                    error = Some((event, err));
+                    break;
+                }
+            }
+        }
+
+        FeedSummary {
+            consumed,
+            state: self.current.clone().unwrap(),
+            is_done: self.done,
+            error,
+        }
+    }
+
+    /// Returns an iterator that sends each event lazily and yields the results.
+    pub fn iter_on<I>(&mut self, events: I) -> IterOn<'_, Self, I::IntoIter>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        IterOn {
+            machine: self,
+            events: events.into_iter(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FeedSummary;
+    use crate::blocking::{Builder, Machine};
+    use crate::error::TransitionError;
+
+    fn machine() -> Machine<'static, i32, char, (), (), crate::blocking::Ready> {
+        Machine::new()
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('b').go_to(2).is_final())
+            .start(0)
+    }
+
+    #[test]
+    fn feed_done_test() {
+        let mut sm = machine();
+        let mut events = "abab".chars();
+
+        assert_eq!(
+            sm.feed(events.by_ref()),
+            FeedSummary {
+                consumed: 2,
+                state: 2,
+                is_done: true,
+                error: None
+            }
+        );
+
+        // The events after done are not consumed
+        assert_eq!(events.as_str(), "ab");
+    }
+
+    #[test]
+    fn feed_error_test() {
+        let mut sm = machine();
+        let summary = sm.feed(vec!['a', 'a', 'b']);
+
+        assert!(!summary.is_ok());
+        assert_eq!(summary.consumed, 1);
+        assert_eq!(summary.state, 1);
+        assert!(!summary.is_done);
+        assert_eq!(
+            summary.error,
+            Some(('a', TransitionError::InvalidTransition))
+        );
+    }
+
+    #[test]
+    fn iter_on_test() {
+        let mut sm = machine();
+        let results = sm.iter_on("aab".chars()).collect::<Vec<_>>();
+
+        assert_eq!(
+            results,
+            vec![Ok(0), Err(TransitionError::InvalidTransition), Ok(1)]
+        );
+        assert!(sm.is_done());
+    }
+}
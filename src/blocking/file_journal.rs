@@ -0,0 +1,343 @@
+use super::{JournalEntry, Machine, OnAction, OnTransition, Persist, Ready};
+use crate::error::RestoreError;
+use std::fmt::Display;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// A journal that writes each transition as a length-prefixed record.
+///
+/// A record is the length of its content followed by the `from`, `event` and `to` fields,
+/// each one the length of the field followed by its text, all the lengths are `u32` little endian.
+/// An empty record revokes the record before it, it is written when a recorded transition is rolled back.
+/// The states and events are written with `Display` and read back with `FromStr`.
+#[derive(Debug)]
+pub struct FileJournal<W = File> {
+    writer: W,
+}
+
+impl FileJournal {
+    /// Opens the journal at the given path, the new records are appended to the existing ones.
+    ///
+    /// A record truncated by a crash at the end of the journal is removed,
+    /// so the new records follow the last complete one.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .read(true)
+            .append(true)
+            .open(path)?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+
+        let complete = complete_len(&bytes);
+        if complete < bytes.len() {
+            file.set_len(complete as u64)?;
+        }
+
+        Ok(FileJournal::new(file))
+    }
+}
+
+impl<W: Write> FileJournal<W> {
+    /// Returns a journal writing to the given writer.
+    pub fn new(writer: W) -> Self {
+        FileJournal { writer }
+    }
+
+    /// Returns the writer of this journal.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<S, E, W> Persist<S, E> for FileJournal<W>
+where
+    S: Display,
+    E: Display,
+    W: Write,
+{
+    fn record(&mut self, entry: &JournalEntry<S, E>) -> io::Result<()> {
+        let mut content = Vec::new();
+        for field in [
+            entry.from.to_string(),
+            entry.event.to_string(),
+            entry.to.to_string(),
+        ] {
+            content.extend_from_slice(&len_prefix(field.len())?);
+            content.extend_from_slice(field.as_bytes());
+        }
+
+        // The record is written at once, so a crash can only truncate the last record
+        let mut record = len_prefix(content.len())?.to_vec();
+        record.extend_from_slice(&content);
+
+        self.writer.write_all(&record)?;
+        self.writer.flush()
+    }
+
+    fn revoke(&mut self, _: &JournalEntry<S, E>) -> io::Result<()> {
+        self.writer.write_all(&len_prefix(0)?)?;
+        self.writer.flush()
+    }
+}
+
+fn len_prefix(len: usize) -> io::Result<[u8; 4]> {
+    u32::try_from(len)
+        .map(u32::to_le_bytes)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "journal record is too long"))
+}
+
+// Reads the next `len` bytes, returns `None` if there are not enough.
+fn take<'b>(bytes: &mut &'b [u8], len: usize) -> Option<&'b [u8]> {
+    if bytes.len() < len {
+        return None;
+    }
+
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Some(head)
+}
+
+fn take_len(bytes: &mut &[u8]) -> Option<usize> {
+    let prefix = take(bytes, 4)?;
+    let len = u32::from_le_bytes(prefix.try_into().unwrap());
+    Some(len as usize)
+}
+
+// Returns the length of the complete records at the start of the journal.
+fn complete_len(bytes: &[u8]) -> usize {
+    let mut rest = bytes;
+    let mut complete = 0;
+
+    while take_len(&mut rest)
+        .and_then(|len| take(&mut rest, len))
+        .is_some()
+    {
+        complete = bytes.len() - rest.len();
+    }
+
+    complete
+}
+
+fn decode<S: FromStr, E: FromStr>(mut content: &[u8]) -> Option<(S, E, S)> {
+    let mut field = || {
+        let len = take_len(&mut content)?;
+        std::str::from_utf8(take(&mut content, len)?).ok()
+    };
+
+    let from = field()?.parse().ok()?;
+    let event = field()?.parse().ok()?;
+    let to = field()?.parse().ok()?;
+    Some((from, event, to))
+}
+
+impl<S, E, F, Ctx, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq + FromStr,
+    S: PartialEq + Clone + FromStr,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Replays the events of a journal written by `FileJournal` and returns the number of replayed records.
+    ///
+    /// Each replayed transition must match the recorded one, a truncated record at the end of the journal is ignored.
+    /// The revoked records are not replayed nor counted in the indexes of the errors.
+    /// The replayed transitions are not recorded again in the journal of this machine.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let build = || {
+    ///     Machine::new()
+    ///         .on_next(Builder::new(0).on('a').go_to(1))
+    ///         .on_next(Builder::new(1).on('b').go_to(2))
+    /// };
+    ///
+    /// let mut journal = Vec::new();
+    /// let mut sm = build().with_journal(FileJournal::new(&mut journal)).start(0);
+    /// sm.send('a').unwrap();
+    /// sm.send('b').unwrap();
+    /// drop(sm);
+    ///
+    /// let mut restored = build().start(0);
+    /// assert_eq!(restored.restore_from_journal(journal.as_slice()), Ok(2));
+    /// assert_eq!(*restored.current(), 2);
+    /// ```
+    pub fn restore_from_journal(
+        &mut self,
+        mut reader: impl Read,
+    ) -> Result<usize, RestoreError<S>> {
+        let mut bytes = Vec::new();
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|err| RestoreError::Io(err.to_string()))?;
+
+        let journal = self.extensions.journal.take();
+        let result = self.replay(&bytes);
+        self.extensions.journal = journal;
+        result
+    }
+
+    fn replay(&mut self, mut bytes: &[u8]) -> Result<usize, RestoreError<S>> {
+        let mut records = Vec::new();
+
+        while let Some(content) = take_len(&mut bytes).and_then(|len| take(&mut bytes, len)) {
+            // An empty record revokes the previous one
+            match content.is_empty() {
+                true => {
+                    records.pop();
+                }
+                false => records.push(content),
+            }
+        }
+
+        for (index, content) in records.iter().enumerate() {
+            let (from, event, to) =
+                decode::<S, E>(content).ok_or(RestoreError::Corrupted { index })?;
+
+            let prev = self
+                .send(event)
+                .map_err(|error| RestoreError::Rejected { index, error })?;
+
+            if prev != from || *self.current() != to {
+                return Err(RestoreError::Diverged {
+                    index,
+                    expected: (from, to),
+                    actual: (prev, self.current().clone()),
+                });
+            }
+        }
+
+        Ok(records.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, FileJournal, Machine};
+    use crate::error::{RestoreError, TransitionError};
+
+    fn machine() -> Machine<'static, u32, char, (), ()> {
+        Machine::new()
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('b').go_to(2))
+            .on_next(Builder::new(2).on('a').go_to(0))
+    }
+
+    #[test]
+    fn restore_test() {
+        let path = std::env::temp_dir().join(format!("restate-journal-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut sm = machine()
+            .with_journal(FileJournal::open(&path).unwrap())
+            .start(0);
+        for event in "aba".chars() {
+            sm.send(event).unwrap();
+        }
+        assert!(sm.send('b').is_err());
+
+        let mut restored = machine().start(0);
+        let file = std::fs::File::open(&path).unwrap();
+        assert_eq!(restored.restore_from_journal(file), Ok(3));
+        assert_eq!(*restored.current(), 0);
+
+        // A record truncated by a crash is ignored
+        let mut bytes = std::fs::read(&path).unwrap();
+        bytes.truncate(bytes.len() - 3);
+
+        let mut restored = machine().start(0);
+        assert_eq!(restored.restore_from_journal(bytes.as_slice()), Ok(2));
+        assert_eq!(*restored.current(), 2);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn reopen_truncated_test() {
+        let path =
+            std::env::temp_dir().join(format!("restate-torn-journal-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut sm = machine()
+            .with_journal(FileJournal::open(&path).unwrap())
+            .start(0);
+        sm.send('a').unwrap();
+        sm.send('b').unwrap();
+        drop(sm);
+
+        // A crash truncated the last record
+        let bytes = std::fs::read(&path).unwrap();
+        std::fs::write(&path, &bytes[..bytes.len() - 3]).unwrap();
+
+        let mut sm = machine()
+            .with_journal(FileJournal::open(&path).unwrap())
+            .start(0);
+        let file = std::fs::File::open(&path).unwrap();
+        assert_eq!(sm.restore_from_journal(file), Ok(1));
+        sm.send('b').unwrap();
+        sm.send('a').unwrap();
+        drop(sm);
+
+        // The new records are not read as part of the truncated one
+        let mut restored = machine().start(0);
+        let file = std::fs::File::open(&path).unwrap();
+        assert_eq!(restored.restore_from_journal(file), Ok(3));
+        assert_eq!(*restored.current(), 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn restore_cancelled_test() {
+        let cancel_first = |cx: ContextMut<u32, char, u32>| {
+            *cx.context += 1;
+            if *cx.context == 1 {
+                cx.cancel();
+            }
+        };
+
+        let mut journal = Vec::new();
+        let mut sm = Machine::with_context(0)
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('b').go_to(2).action(cancel_first))
+            .with_journal(FileJournal::new(&mut journal))
+            .start(0);
+
+        sm.send('a').unwrap();
+        assert_eq!(sm.send('b'), Err(TransitionError::Cancelled));
+        sm.send('b').unwrap();
+        drop(sm);
+
+        // The cancelled transition was revoked, so it is not replayed
+        let mut restored = machine().start(0);
+        assert_eq!(restored.restore_from_journal(journal.as_slice()), Ok(2));
+        assert_eq!(*restored.current(), 2);
+    }
+
+    #[test]
+    fn restore_diverged_test() {
+        let mut journal = Vec::new();
+        let mut sm = machine()
+            .with_journal(FileJournal::new(&mut journal))
+            .start(0);
+        sm.send('a').unwrap();
+        drop(sm);
+
+        // The restored machine starts from other state
+        let mut restored = machine().start(2);
+        assert_eq!(
+            restored.restore_from_journal(journal.as_slice()),
+            Err(RestoreError::Diverged {
+                index: 0,
+                expected: (0, 1),
+                actual: (2, 0),
+            })
+        );
+    }
+}
@@ -0,0 +1,92 @@
+use super::{Machine, Ready};
+
+/// The transition that completed a state machine, returned by `Machine::finished_by`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FinishInfo<S, E> {
+    /// The state where the final transition started.
+    pub from: S,
+
+    /// The state the machine finished in.
+    pub to: S,
+
+    /// The event of the final transition, only kept if it was sent by value with `send`.
+    pub event: Option<E>,
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C> {
+    /// Returns the transition that completed this state machine, or `None` if it is not done.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::TransitionError;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("open").on("close").go_to("closed").is_final())
+    ///     .start("open");
+    ///
+    /// sm.send("close").unwrap();
+    /// assert_eq!(sm.send("close"), Err(TransitionError::Done));
+    ///
+    /// let info = sm.finished_by().unwrap();
+    /// assert_eq!((info.from, info.event, info.to), ("open", Some("close"), "closed"));
+    /// ```
+    pub fn finished_by(&self) -> Option<&FinishInfo<S, E>> {
+        self.extensions.finished.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FinishInfo;
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[test]
+    fn finished_by_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_next(Builder::new('b').on(1).go_to('c').is_final())
+            .on_next(Builder::new('a').on(2).go_to('d').is_final())
+            .start('a');
+
+        sm.send(0).unwrap();
+        assert_eq!(sm.finished_by(), None);
+
+        sm.send(1).unwrap();
+        let info = FinishInfo {
+            from: 'b',
+            to: 'c',
+            event: Some(1),
+        };
+        assert_eq!(sm.finished_by(), Some(&info));
+
+        let mut sm = sm.into_builder().start('a');
+        assert_eq!(sm.finished_by(), None);
+
+        // A borrowed event is not kept
+        sm.send_ref(&2).unwrap();
+        let info = sm.finished_by().unwrap();
+        assert_eq!((info.from, info.to, info.event), ('a', 'd', None));
+    }
+
+    #[test]
+    fn finished_by_undo_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(
+                Builder::new('a')
+                    .on(0)
+                    .go_to('b')
+                    .is_final()
+                    .action(|cx: ContextMut<char, u8, i32>| *cx.context += 1),
+            )
+            .with_undo(1)
+            .start('a');
+
+        sm.send(0).unwrap();
+        assert!(sm.finished_by().is_some());
+
+        sm.undo().unwrap();
+        assert_eq!(sm.finished_by(), None);
+    }
+}
@@ -0,0 +1,250 @@
+use super::transition::private::{CanBuild, HasEvent, HasFrom};
+use super::transition::Marker;
+use super::Transition;
+use super::{BoxedAction, Build, Builder, IntoTransition, OnAction, SendAction, SharedAction};
+use std::marker::PhantomData;
+
+/// Allows a type to be converted into many `Transition`s.
+pub trait IntoTransitions<'a, S, E, Ctx, A: ?Sized = SendAction<'a, S, E, Ctx>> {
+    /// The iterator over the transitions.
+    type IntoIter: Iterator<Item = Transition<'a, S, E, Ctx, A>>;
+
+    /// Converts this type into `Transition`s.
+    fn into_transitions(self) -> Self::IntoIter;
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> IntoTransitions<'a, S, E, Ctx, A> for Transition<'a, S, E, Ctx, A> {
+    type IntoIter = std::iter::Once<Transition<'a, S, E, Ctx, A>>;
+
+    fn into_transitions(self) -> Self::IntoIter {
+        std::iter::once(self)
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> IntoTransitions<'a, S, E, Ctx, A>
+    for Builder<'a, S, E, Ctx, CanBuild, A>
+{
+    type IntoIter = std::iter::Once<Transition<'a, S, E, Ctx, A>>;
+
+    fn into_transitions(self) -> Self::IntoIter {
+        std::iter::once(self.into_transition())
+    }
+}
+
+/// A builder of the transitions from many states to the same state, created with `Builder::from_states`.
+pub struct FromStates<'a, S, E, Ctx, TStep = HasFrom, A: ?Sized = SendAction<'a, S, E, Ctx>> {
+    from: Vec<S>,
+    event: Option<E>,
+    to: Option<S>,
+    is_final: bool,
+    actions: Vec<Option<Box<A>>>,
+    _marker: Marker<'a, Ctx, TStep>,
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, Build, A> {
+    /// Constructs the transitions from each of the given states to the same state when an event happens.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Player {
+    ///     Running,
+    ///     Paused,
+    ///     Buffering,
+    ///     Suspended,
+    /// }
+    ///
+    /// let mut sm = Machine::with_context(vec![])
+    ///     .on_next(
+    ///         Builder::from_states([Player::Running, Player::Paused, Player::Buffering])
+    ///             .on("suspend")
+    ///             .go_to(Player::Suspended)
+    ///             .action(|cx: ContextMut<Player, &str, Vec<Player>>| {
+    ///                 cx.context.push(cx.from.clone());
+    ///             }),
+    ///     )
+    ///     .on_next(Builder::new(Player::Suspended).on("resume").go_to(Player::Paused))
+    ///     .start(Player::Running);
+    ///
+    /// sm.send("suspend").unwrap();
+    /// sm.send("resume").unwrap();
+    /// sm.send("suspend").unwrap();
+    ///
+    /// assert_eq!(*sm.context(), vec![Player::Running, Player::Paused]);
+    /// ```
+    pub fn from_states(
+        states: impl IntoIterator<Item = S>,
+    ) -> FromStates<'a, S, E, Ctx, HasFrom, A> {
+        FromStates {
+            from: states.into_iter().collect(),
+            event: None,
+            to: None,
+            is_final: false,
+            actions: Vec::new(),
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> FromStates<'a, S, E, Ctx, HasFrom, A> {
+    /// Sets the event that trigger the transitions.
+    pub fn on(self, event: E) -> FromStates<'a, S, E, Ctx, HasEvent, A> {
+        FromStates {
+            from: self.from,
+            event: Some(event),
+            to: None,
+            is_final: self.is_final,
+            actions: self.actions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> FromStates<'a, S, E, Ctx, HasEvent, A> {
+    /// Sets the state where the transitions go to.
+    pub fn go_to(self, state: S) -> FromStates<'a, S, E, Ctx, CanBuild, A> {
+        FromStates {
+            from: self.from,
+            event: self.event,
+            to: Some(state),
+            is_final: self.is_final,
+            actions: self.actions,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> FromStates<'a, S, E, Ctx, CanBuild, A> {
+    /// Ensure the transitions complete the state machine.
+    pub fn is_final(mut self) -> Self {
+        self.is_final = true;
+        self
+    }
+
+    /// Sets an action shared by all the transitions.
+    pub fn action<F>(mut self, f: F) -> Self
+    where
+        F: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, SharedAction<F>, S, E, Ctx>,
+    {
+        let shared = SharedAction::new(f);
+        self.actions = self
+            .from
+            .iter()
+            .map(|_| Some(A::boxed(shared.clone())))
+            .collect();
+        self
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> IntoTransitions<'a, S, E, Ctx, A>
+    for FromStates<'a, S, E, Ctx, CanBuild, A>
+where
+    S: Clone,
+    E: Clone,
+{
+    type IntoIter = std::vec::IntoIter<Transition<'a, S, E, Ctx, A>>;
+
+    fn into_transitions(self) -> Self::IntoIter {
+        let FromStates {
+            from,
+            event,
+            to,
+            is_final,
+            actions,
+            ..
+        } = self;
+
+        let (event, to) = (event.unwrap(), to.unwrap());
+        let mut actions = actions.into_iter();
+
+        from.into_iter()
+            .map(|from| Transition {
+                from,
+                to: to.clone(),
+                event: event.clone(),
+                is_final,
+                action: actions.next().flatten(),
+                before: None,
+                guard: None,
+                limit: None,
+                priority: 0,
+                label: None,
+                _marker: PhantomData,
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Context, ContextMut, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Running,
+        Paused,
+        Buffering,
+        Suspended,
+    }
+
+    #[test]
+    fn from_states_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(
+                Builder::from_states([State::Running, State::Paused, State::Buffering])
+                    .on('s')
+                    .go_to(State::Suspended)
+                    .action(|cx: ContextMut<State, char, i32>| *cx.context += 1),
+            )
+            .on_next(
+                Builder::new(State::Suspended)
+                    .on('b')
+                    .go_to(State::Buffering),
+            )
+            .start(State::Buffering);
+
+        assert_eq!(sm.states().count(), 4);
+        assert_eq!(sm.send('s'), Ok(State::Buffering));
+        sm.send('b').unwrap();
+        assert_eq!(sm.send('s'), Ok(State::Buffering));
+        assert_eq!(*sm.context(), 2);
+    }
+
+    #[test]
+    fn from_is_source_state_test() {
+        let mut sm = Machine::with_context(vec![])
+            .on_next(
+                Builder::new(State::Running)
+                    .on('p')
+                    .go_to(State::Paused)
+                    .action(|cx: ContextMut<State, char, Vec<(State, State)>>| {
+                        cx.context.push((cx.from.clone(), cx.to.clone()));
+                    }),
+            )
+            .on_transition(|cx: Context<State, char, Vec<(State, State)>>| {
+                assert_eq!(*cx.from, State::Running);
+                assert_eq!(*cx.to, State::Paused);
+            })
+            .start(State::Running);
+
+        sm.send('p').unwrap();
+        assert_eq!(*sm.context(), vec![(State::Running, State::Paused)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "source state at index 1")]
+    fn from_states_duplicate_test() {
+        let _ = Machine::new()
+            .on_next(Builder::new(State::Paused).on('s').go_to(State::Running))
+            .on_next(
+                Builder::from_states([State::Running, State::Paused])
+                    .on('s')
+                    .go_to(State::Suspended),
+            );
+    }
+}
@@ -0,0 +1,114 @@
+use super::machine::{insert_next, Next};
+use super::{Build, Machine};
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Build, A, C>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+{
+    /// Drops the event in the given state without a transition.
+    ///
+    /// Sending the event returns the current state as a self transition would,
+    /// but no action nor hook runs, see `TransitionOutcome::was_ignored` to tell them apart.
+    /// The exporters show the ignored events as self loops with a distinct style.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("click").go_to("loading"))
+    ///     .ignore("loading", "click")
+    ///     .on_transition(|cx: Context<&str, &str, ()>| assert_ne!(cx.from, cx.to))
+    ///     .start("idle");
+    ///
+    /// sm.send("click").unwrap();
+    /// assert_eq!(sm.send("click"), Ok("loading"));
+    /// assert_eq!(*sm.current(), "loading");
+    /// ```
+    pub fn ignore(mut self, state: S, event: E) -> Self {
+        let next = Next {
+            next: state.clone(),
+            action: None,
+            before: None,
+            is_final: false,
+            guard: None,
+            limit: None,
+            priority: 0,
+            label: None,
+            candidates: Vec::new(),
+            ignored: true,
+            scope: None,
+            #[cfg(feature = "rand")]
+            weight: 1,
+        };
+
+        let added = self.extensions.unchecked_limits(&event, &state, &next.next);
+
+        if insert_next(&mut self.transitions, event, state, next).is_err() {
+            panic!("a transition already exists for the event");
+        }
+
+        self.extensions.count_added(added);
+
+        self
+    }
+
+    /// Drops each of the events in the given state without a transition, see `Machine::ignore`.
+    ///
+    /// # Panics
+    /// If a transition already exists for any of the events from the state.
+    pub fn ignore_many(self, state: S, events: impl IntoIterator<Item = E>) -> Self {
+        events
+            .into_iter()
+            .fold(self, |machine, event| machine.ignore(state.clone(), event))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use std::cell::Cell;
+
+    fn count(cx: ContextMut<char, u8, i32>) {
+        *cx.context += 1;
+    }
+
+    #[test]
+    fn ignore_test() {
+        let transitions = Cell::new(0);
+        let mut sm = Machine::with_context(0)
+            .on_next(Builder::new('a').on(0).go_to('b').action(count))
+            .on_next(Builder::new('b').on(0).go_to('a').action(count))
+            .ignore_many('b', [1, 2])
+            .on_transition(|_| transitions.set(transitions.get() + 1))
+            .on_unhandled(|cx| *cx.context = -1)
+            .start('a');
+
+        sm.send(0).unwrap();
+        assert_eq!(sm.send(1), Ok('b'));
+        assert_eq!(sm.step(2).map(|outcome| outcome.was_ignored), Ok(true));
+        assert_eq!(*sm.current(), 'b');
+        assert_eq!(*sm.context(), 1);
+        assert_eq!(transitions.get(), 1);
+
+        let ignored = sm
+            .transitions()
+            .filter(|t| t.is_ignored())
+            .map(|t| (*t.from(), *t.event(), *t.to()))
+            .collect::<Vec<_>>();
+        assert_eq!(ignored, [('b', 1, 'b'), ('b', 2, 'b')]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ignore_duplicate_test() {
+        let _ = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .ignore('a', 0);
+    }
+}
@@ -0,0 +1,365 @@
+use super::{Build, Builder, Context, ContextMut, IntoTransition, Machine, OnTransition, Ready};
+use super::{SendAction, Transition, TransitionTable};
+use crate::error::TransitionError;
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+// Maps each distinct name to a symbol, the index of the name in `names`.
+#[derive(Default)]
+struct Interner {
+    symbols: HashMap<String, u32>,
+    names: Arc<Vec<String>>,
+}
+
+impl Interner {
+    // Returns the symbol of the name, only allocates if the name was not interned before.
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&symbol) = self.symbols.get(name) {
+            return symbol;
+        }
+
+        let symbol = u32::try_from(self.names.len()).expect("too many interned names");
+        self.symbols.insert(name.to_owned(), symbol);
+        Arc::make_mut(&mut self.names).push(name.to_owned());
+        symbol
+    }
+
+    fn get(&self, name: &str) -> Option<u32> {
+        self.symbols.get(name).copied()
+    }
+
+    fn resolve(&self, symbol: u32) -> &String {
+        &self.names[symbol as usize]
+    }
+}
+
+// Wraps an action over names so it runs in the machine over symbols, it receives the names of its transition.
+fn named_action<'a, Ctx: 'a>(
+    mut action: Box<SendAction<'a, String, String, Ctx>>,
+    from: String,
+    event: String,
+    to: String,
+) -> Box<SendAction<'a, u32, u32, Ctx>> {
+    Box::new(move |cx: ContextMut<u32, u32, Ctx>| {
+        action.call(ContextMut {
+            from: &from,
+            to: &to,
+            event: &event,
+            context: cx.context,
+            state_data: None,
+            abort: cx.abort,
+            is_final: cx.is_final,
+            sender: None,
+        })
+    })
+}
+
+/// A state machine for `String` states and events, which are interned to symbols when the machine is built.
+///
+/// The transitions are stored in a `Machine` over the symbols, so sending an event compares integers instead of strings
+/// and doesn't clone the states, the names are only resolved to pass them to the actions and hooks.
+/// Sending an event that was never interned fails with `TransitionError::InvalidTransition` without allocating.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// let table = ["idle,start,running", "running,stop,idle"]
+///     .iter()
+///     .map(|row| {
+///         let parts = row.split(',').map(String::from).collect::<Vec<_>>();
+///         (parts[0].clone(), parts[1].clone(), parts[2].clone(), false)
+///     })
+///     .collect::<TransitionTable<_, _>>();
+///
+/// let mut sm = InternedMachine::from_table(table).start("idle");
+///
+/// assert_eq!(sm.send("start"), Ok("idle"));
+/// assert_eq!(sm.current(), "running");
+/// assert!(sm.send("unknown").is_err());
+/// ```
+pub struct InternedMachine<'a, Ctx = (), F = (), Step = Build> {
+    interner: Interner,
+    machine: Machine<'a, u32, u32, Ctx, F, Step>,
+}
+
+impl<Ctx, F, Step> Debug for InternedMachine<'_, Ctx, F, Step>
+where
+    Ctx: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let current = self
+            .machine
+            .current
+            .map(|symbol| self.interner.resolve(symbol));
+
+        f.debug_struct("InternedMachine")
+            .field("current", &current)
+            .field("done", &self.machine.done)
+            .field("context", &self.machine.context)
+            .finish()
+    }
+}
+
+impl<'a> InternedMachine<'a> {
+    /// Returns a new `InternedMachine`.
+    pub fn new() -> Self {
+        InternedMachine::with_context(())
+    }
+
+    /// Returns a new `InternedMachine` with the transitions of the table and no actions.
+    ///
+    /// # Panics
+    /// If the table has many rows for the same event from the same state.
+    pub fn from_table(table: TransitionTable<String, String>) -> Self {
+        InternedMachine::new().on_table(table)
+    }
+}
+
+impl<'a> Default for InternedMachine<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, Ctx: 'a> InternedMachine<'a, Ctx> {
+    /// Returns a new `InternedMachine` with the given context.
+    pub fn with_context(context: Ctx) -> Self {
+        InternedMachine {
+            interner: Interner::default(),
+            machine: Machine::with_context(context),
+        }
+    }
+
+    /// Adds a transition from a state to other based on an event.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state, and any of them is not guarded.
+    pub fn on_next(mut self, transition: impl IntoTransition<'a, String, String, Ctx>) -> Self {
+        let Transition {
+            from,
+            to,
+            event,
+            is_final,
+            action,
+            before,
+            guard,
+            limit,
+            priority,
+            label,
+            _marker,
+        } = transition.into_transition();
+
+        let symbols = Transition {
+            from: self.interner.intern(&from),
+            to: self.interner.intern(&to),
+            event: self.interner.intern(&event),
+            is_final,
+            action: None,
+            before: None,
+            guard,
+            limit,
+            priority,
+            label,
+            _marker: PhantomData,
+        };
+
+        let names = |f| named_action(f, from.clone(), event.clone(), to.clone());
+        let symbols = Transition {
+            action: action.map(names),
+            before: before.map(names),
+            ..symbols
+        };
+
+        self.machine = self.machine.on_next(symbols);
+        self
+    }
+
+    /// Adds the transitions of the table without actions.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state.
+    pub fn on_table(self, table: TransitionTable<String, String>) -> Self {
+        table
+            .into_iter()
+            .fold(self, |machine, (from, event, to, is_final)| {
+                let builder = Builder::new(from).on(event).go_to(to);
+                match is_final {
+                    true => machine.on_next(builder.is_final()),
+                    false => machine.on_next(builder),
+                }
+            })
+    }
+
+    /// Adds a function that is called when a transition occurs.
+    pub fn on_transition<F>(
+        self,
+        mut on_transition: F,
+    ) -> InternedMachine<'a, Ctx, impl OnTransition<u32, u32, Ctx> + 'a>
+    where
+        F: FnMut(Context<String, String, Ctx>) + 'a,
+    {
+        let names = self.interner.names.clone();
+
+        InternedMachine {
+            interner: self.interner,
+            machine: self
+                .machine
+                .on_transition(move |cx: Context<u32, u32, Ctx>| {
+                    on_transition(Context {
+                        from: &names[*cx.from as usize],
+                        to: &names[*cx.to as usize],
+                        event: &names[*cx.event as usize],
+                        context: cx.context,
+                    })
+                }),
+        }
+    }
+}
+
+impl<'a, Ctx, F> InternedMachine<'a, Ctx, F, Build> {
+    /// Starts this state machine with the given state.
+    pub fn start(mut self, initial_state: &str) -> InternedMachine<'a, Ctx, F, Ready> {
+        let current = self.interner.intern(initial_state);
+
+        InternedMachine {
+            interner: self.interner,
+            machine: self.machine.start(current),
+        }
+    }
+}
+
+impl<Ctx, F> InternedMachine<'_, Ctx, F, Ready>
+where
+    F: OnTransition<u32, u32, Ctx>,
+{
+    /// Returns the current state.
+    pub fn current(&self) -> &str {
+        self.interner.resolve(*self.machine.current())
+    }
+
+    /// Returns the context of this state machine.
+    pub fn context(&self) -> &Ctx {
+        self.machine.context()
+    }
+
+    /// Returns `true` if the state machine reached a final state.
+    pub fn is_done(&self) -> bool {
+        self.machine.is_done()
+    }
+
+    /// Sends an event to this state machine and returns the previous state.
+    pub fn send(&mut self, event: impl AsRef<str>) -> Result<&str, TransitionError> {
+        let Some(event) = self.interner.get(event.as_ref()) else {
+            // An event that was never interned has no transition
+            return Err(match self.machine.is_done() {
+                true => TransitionError::Done,
+                false => TransitionError::InvalidTransition,
+            });
+        };
+
+        let prev = self.machine.send(event)?;
+        Ok(self.interner.resolve(prev))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, InternedMachine, Machine, TransitionTable};
+    use crate::error::TransitionError;
+
+    fn push(cx: ContextMut<String, String, Vec<String>>) {
+        cx.context
+            .push(format!("{}:{}:{}", cx.from, cx.event, cx.to));
+    }
+
+    fn table() -> TransitionTable<String, String> {
+        [
+            ("idle", "start", "running", false),
+            ("running", "pause", "paused", false),
+            ("paused", "start", "running", false),
+            ("running", "stop", "stopped", true),
+        ]
+        .into_iter()
+        .map(|(from, event, to, is_final)| (from.into(), event.into(), to.into(), is_final))
+        .collect()
+    }
+
+    #[test]
+    fn interned_same_behavior_test() {
+        let mut machine = Machine::from_table(table()).start("idle".to_string());
+        let mut interned = InternedMachine::from_table(table()).start("idle");
+
+        for event in [
+            "pause", "start", "pause", "unknown", "start", "stop", "start",
+        ] {
+            let expected = machine.send(event.to_string());
+            assert_eq!(interned.send(event).map(String::from), expected);
+            assert_eq!(interned.current(), machine.current());
+            assert_eq!(interned.is_done(), machine.is_done());
+        }
+
+        assert_eq!(interned.send("stop"), Err(TransitionError::Done));
+    }
+
+    #[test]
+    fn interned_action_test() {
+        let mut sm = InternedMachine::with_context(Vec::new())
+            .on_next(
+                Builder::new("a".to_string())
+                    .on("go".into())
+                    .go_to("b".into())
+                    .action(push),
+            )
+            .on_next(
+                Builder::new("b".to_string())
+                    .on("go".into())
+                    .go_to("a".into())
+                    .action(|cx: ContextMut<String, String, Vec<String>>| cx.cancel()),
+            )
+            .on_transition(|cx| assert_eq!(cx.context.len(), 1))
+            .start("a");
+
+        assert_eq!(sm.send(String::from("go")), Ok("a"));
+        assert_eq!(sm.send("go"), Err(TransitionError::Cancelled));
+        assert_eq!(sm.current(), "b");
+        assert_eq!(*sm.context(), ["a:go:b"]);
+    }
+
+    #[test]
+    fn interned_before_test() {
+        let mut sm = InternedMachine::with_context(Vec::new())
+            .on_next(
+                Builder::new("a".to_string())
+                    .on("go".into())
+                    .go_to("b".into())
+                    .before(|cx: ContextMut<String, String, Vec<String>>| {
+                        cx.context.push(format!("leaving {}", cx.from))
+                    })
+                    .after(push),
+            )
+            .start("a");
+
+        sm.send("go").unwrap();
+        assert_eq!(*sm.context(), ["leaving a", "a:go:b"]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn interned_duplicate_test() {
+        let _ = InternedMachine::new()
+            .on_next(
+                Builder::new("a".to_string())
+                    .on("go".into())
+                    .go_to("b".into()),
+            )
+            .on_next(
+                Builder::new("a".to_string())
+                    .on("go".into())
+                    .go_to("c".into()),
+            );
+    }
+}
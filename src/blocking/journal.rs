@@ -0,0 +1,235 @@
+use super::{Build, Machine};
+use std::io;
+
+/// A transition accepted by a state machine, as recorded in a journal.
+#[derive(Debug, PartialEq, Eq)]
+pub struct JournalEntry<'a, S, E> {
+    /// The state before the transition.
+    pub from: &'a S,
+
+    /// The state after the transition.
+    pub to: &'a S,
+
+    /// The event that triggered the transition.
+    pub event: &'a E,
+}
+
+impl<S, E> Clone for JournalEntry<'_, S, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, E> Copy for JournalEntry<'_, S, E> {}
+
+/// A durable storage of the transitions of a state machine.
+pub trait Persist<S, E> {
+    /// Records a transition, if this fails the transition is rolled back.
+    fn record(&mut self, entry: &JournalEntry<S, E>) -> io::Result<()>;
+
+    /// Revokes the last recorded transition, called when the transition is rolled back after being recorded,
+    /// like when the action of a transition recorded with `JournalOrder::BeforeActions` cancels it,
+    /// or when a hook called after recording it panics with `Machine::catch_panics`.
+    ///
+    /// By default nothing is done and the rolled back transition remains recorded.
+    fn revoke(&mut self, entry: &JournalEntry<S, E>) -> io::Result<()> {
+        let _ = entry;
+        Ok(())
+    }
+}
+
+/// Determines when the transitions are recorded in the journal of a state machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JournalOrder {
+    /// The transition is recorded before running its action,
+    /// if the action cancels the transition or panics the record is revoked with `Persist::revoke`.
+    #[default]
+    BeforeActions,
+
+    /// The transition is recorded after running its action and before calling `on_transition`,
+    /// the changes made by the action to the context are kept if the record fails.
+    /// If a hook called after the record panics the record is revoked with `Persist::revoke`.
+    AfterActions,
+}
+
+pub(crate) struct Journal<'a, S, E> {
+    pub(crate) persist: Box<dyn Persist<S, E> + Send + Sync + 'a>,
+    pub(crate) order: JournalOrder,
+}
+
+impl<S, E> Journal<'_, S, E> {
+    // Records the transition if the journal uses the given order.
+    pub(crate) fn record(
+        &mut self,
+        order: JournalOrder,
+        entry: JournalEntry<S, E>,
+    ) -> Result<(), String> {
+        if self.order != order {
+            return Ok(());
+        }
+
+        self.persist.record(&entry).map_err(|err| err.to_string())
+    }
+
+    // Revokes the recorded transition, after it was rolled back.
+    pub(crate) fn revoke(&mut self, entry: JournalEntry<S, E>) -> Result<(), String> {
+        self.persist.revoke(&entry).map_err(|err| err.to_string())
+    }
+}
+
+impl<'a, S, E, Ctx, F> Machine<'a, S, E, Ctx, F, Build> {
+    /// Records each accepted transition in the given journal before running its action.
+    ///
+    /// If recording fails `send` returns `TransitionError::Journal` and the machine stays in the previous state.
+    pub fn with_journal(self, persist: impl Persist<S, E> + Send + Sync + 'a) -> Self {
+        self.with_journal_ordered(persist, JournalOrder::BeforeActions)
+    }
+
+    /// Records each accepted transition in the given journal, at the given point of the transition.
+    pub fn with_journal_ordered(
+        mut self,
+        persist: impl Persist<S, E> + Send + Sync + 'a,
+        order: JournalOrder,
+    ) -> Self {
+        self.extensions.journal = Some(Journal {
+            persist: Box::new(persist),
+            order,
+        });
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{JournalEntry, JournalOrder, Persist};
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::TransitionError;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Recorder {
+        entries: Arc<Mutex<Vec<(char, char)>>>,
+        revoked: Arc<Mutex<Vec<(char, char)>>>,
+        fail: bool,
+    }
+
+    impl Persist<char, u8> for Recorder {
+        fn record(&mut self, entry: &JournalEntry<char, u8>) -> io::Result<()> {
+            if self.fail {
+                return Err(io::Error::other("disk full"));
+            }
+
+            self.entries.lock().unwrap().push((*entry.from, *entry.to));
+            Ok(())
+        }
+
+        fn revoke(&mut self, entry: &JournalEntry<char, u8>) -> io::Result<()> {
+            self.revoked.lock().unwrap().push((*entry.from, *entry.to));
+            Ok(())
+        }
+    }
+
+    fn cancel_on_b(cx: ContextMut<char, u8, ()>) {
+        if *cx.to == 'b' {
+            cx.cancel();
+        }
+    }
+
+    #[test]
+    fn journal_records_test() {
+        let recorder = Recorder::default();
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_next(Builder::new('b').on(1).go_to('c'))
+            .with_journal(recorder.clone())
+            .start('a');
+
+        sm.send(0).unwrap();
+        assert!(sm.send(0).is_err());
+        sm.send(1).unwrap();
+
+        assert_eq!(*recorder.entries.lock().unwrap(), [('a', 'b'), ('b', 'c')]);
+    }
+
+    #[test]
+    fn journal_failure_test() {
+        let recorder = Recorder {
+            fail: true,
+            ..Default::default()
+        };
+
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .with_journal(recorder)
+            .start('a');
+
+        assert!(matches!(sm.send(0), Err(TransitionError::Journal(_))));
+        assert_eq!(*sm.current(), 'a');
+    }
+
+    #[test]
+    fn journal_order_test() {
+        let before = Recorder::default();
+        let after = Recorder::default();
+
+        let build = |recorder: Recorder, order| {
+            Machine::new()
+                .on_next(Builder::new('a').on(0).go_to('b').action(cancel_on_b))
+                .with_journal_ordered(recorder, order)
+                .start('a')
+        };
+
+        let mut sm = build(before.clone(), JournalOrder::BeforeActions);
+        assert_eq!(sm.send(0), Err(TransitionError::Cancelled));
+
+        let mut sm = build(after.clone(), JournalOrder::AfterActions);
+        assert_eq!(sm.send(0), Err(TransitionError::Cancelled));
+
+        // Only the journal recording before the action sees the cancelled transition, and revokes it
+        assert_eq!(*before.entries.lock().unwrap(), [('a', 'b')]);
+        assert_eq!(*before.revoked.lock().unwrap(), [('a', 'b')]);
+        assert!(after.entries.lock().unwrap().is_empty());
+        assert!(after.revoked.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn journal_revoke_on_panic_test() {
+        let recorder = Recorder::default();
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new('a')
+                    .on(0)
+                    .go_to('b')
+                    .action(|_: ContextMut<char, u8, ()>| panic!("failed")),
+            )
+            .with_journal(recorder.clone())
+            .catch_panics()
+            .start('a');
+
+        assert!(matches!(
+            sm.send(0),
+            Err(TransitionError::ActionPanicked(_))
+        ));
+        assert_eq!(*recorder.revoked.lock().unwrap(), [('a', 'b')]);
+    }
+
+    #[test]
+    fn journal_after_actions_revoke_on_panic_test() {
+        let recorder = Recorder::default();
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_transition(|_| panic!("failed"))
+            .with_journal_ordered(recorder.clone(), JournalOrder::AfterActions)
+            .catch_panics()
+            .start('a');
+
+        assert!(matches!(
+            sm.send(0),
+            Err(TransitionError::ActionPanicked(_))
+        ));
+        assert_eq!(*sm.current(), 'a');
+        assert_eq!(*recorder.entries.lock().unwrap(), [('a', 'b')]);
+        assert_eq!(*recorder.revoked.lock().unwrap(), [('a', 'b')]);
+    }
+}
@@ -0,0 +1,147 @@
+use super::{Build, Lent, LentMachine, Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+use crate::map::TransitionMap;
+use std::marker::PhantomData;
+
+impl<'a, S, E> Machine<'a, S, E, (), (), Build> {
+    /// Returns a new `LentMachine` that doesn't store a context,
+    /// the context is lent to the machine on each transition with `send_with`.
+    ///
+    /// The actions and hooks receive the lent context,
+    /// the methods using the context of the machine like `send` or `context` don't exist on a `LentMachine`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(Default)]
+    /// struct AppState {
+    ///     clicks: u32,
+    /// }
+    ///
+    /// struct App {
+    ///     state: AppState,
+    ///     button: LentMachine<'static, &'static str, &'static str, AppState, (), Ready>,
+    /// }
+    ///
+    /// let button = Machine::with_lent_context()
+    ///     .on_next(Builder::new("up").on("press").go_to("down").action(
+    ///         |cx: ContextMut<&str, &str, AppState>| {
+    ///             cx.context.clicks += 1;
+    ///         },
+    ///     ))
+    ///     .on_next(Builder::new("down").on("release").go_to("up"))
+    ///     .start("up");
+    ///
+    /// let mut app = App {
+    ///     state: AppState::default(),
+    ///     button,
+    /// };
+    ///
+    /// app.button.send_with("press", &mut app.state).unwrap();
+    /// app.button.send_with("release", &mut app.state).unwrap();
+    /// assert_eq!(app.state.clicks, 1);
+    /// ```
+    pub fn with_lent_context<Ctx>() -> LentMachine<'a, S, E, Ctx> {
+        Machine::from_parts(TransitionMap::new(), Lent(PhantomData))
+    }
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A, Lent<Ctx>>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Triggers a transition with the given context, which is lent to the machine for this transition.
+    ///
+    /// A machine storing its context uses `send` instead,
+    /// so its undo history and hooks never see a context other than its own:
+    ///
+    /// ```rust,compile_fail
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context("own")
+    ///     .on_next(Builder::self_transition('a', 0))
+    ///     .with_undo(4)
+    ///     .start('a');
+    ///
+    /// sm.send_with(0, &mut "lent").unwrap();
+    /// ```
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(TransitionError): If the transition was not successful
+    pub fn send_with(&mut self, event: E, context: &mut Ctx) -> Result<S, TransitionError> {
+        match self.try_dispatch(&event, |_| &mut *context) {
+            Err(error) if self.extensions.on_error.is_some() => {
+                self.recover(error, &event, |_| context)
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, ErrorDecision, Machine};
+    use crate::error::TransitionError;
+
+    fn push(cx: ContextMut<char, u8, Vec<u8>>) {
+        cx.context.push(*cx.event);
+    }
+
+    #[test]
+    fn send_with_test() {
+        let mut sm = Machine::with_lent_context()
+            .on_next(Builder::new('a').on(0).go_to('b').action(push))
+            .on_next(Builder::new('b').on(1).go_to('a').action(push))
+            .on_transition(|cx| assert!(!cx.context.is_empty()))
+            .on_unhandled(|cx| cx.context.clear())
+            .start('a');
+
+        let mut first = Vec::new();
+        let mut second = vec![9];
+
+        sm.send_with(0, &mut first).unwrap();
+        sm.send_with(1, &mut second).unwrap();
+        assert_eq!(first, [0]);
+        assert_eq!(second, [9, 1]);
+
+        assert_eq!(
+            sm.send_with(1, &mut first),
+            Err(TransitionError::InvalidTransition)
+        );
+        assert!(first.is_empty());
+    }
+
+    #[test]
+    fn send_with_on_error_test() {
+        let mut sm = Machine::with_lent_context()
+            .on_next(Builder::self_transition('a', 0).action(push))
+            .on_error(|cx| {
+                cx.context.push(u8::MAX);
+                ErrorDecision::Ignore
+            })
+            .start('a');
+
+        let mut lent = Vec::new();
+        assert_eq!(sm.send_with(1, &mut lent), Ok('a'));
+        sm.send_with(0, &mut lent).unwrap();
+        assert_eq!(lent, [u8::MAX, 0]);
+    }
+
+    #[test]
+    fn send_with_no_sender_test() {
+        let mut sm = Machine::with_lent_context()
+            .on_next(
+                Builder::self_transition('a', 0)
+                    .action(|cx: ContextMut<char, u8, ()>| assert!(cx.sender().is_none())),
+            )
+            .start('a');
+
+        sm.send_with(0, &mut ()).unwrap();
+    }
+}
@@ -0,0 +1,115 @@
+use super::{Build, Machine};
+
+// A callback called with a state and the context when the machine starts or is done.
+pub(crate) type LifecycleHook<'a, S, Ctx> = Box<dyn FnMut(&S, &mut Ctx) + Send + Sync + 'a>;
+
+impl<'a, S, E, Ctx, F, A: ?Sized> Machine<'a, S, E, Ctx, F, Build, A> {
+    /// Adds a function that is called once with the initial state and the context when the machine starts,
+    /// it replaces the previous one.
+    ///
+    /// It is called again each time the machine is started after `into_builder`,
+    /// a `LentMachine` has no context when it starts so it has no `on_start`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::with_context(Vec::new())
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_start(|state, cx: &mut Vec<String>| cx.push(format!("started in {state}")))
+    ///     .start("idle");
+    ///
+    /// assert_eq!(*sm.context(), ["started in idle"]);
+    /// ```
+    pub fn on_start(mut self, f: impl FnMut(&S, &mut Ctx) + Send + Sync + 'a) -> Self {
+        self.extensions.on_start = Some(Box::new(f));
+        self
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Adds a function that is called once with the final state and the context when the machine is done,
+    /// it replaces the previous one.
+    ///
+    /// It is called after the action and the `on_transition` of the transition that completes the machine,
+    /// and again if the machine is done after being started again with `into_builder`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context(0)
+    ///     .on_next(Builder::new("open").on("close").go_to("closed").is_final())
+    ///     .on_done(|state, cx: &mut i32| {
+    ///         assert_eq!(*state, "closed");
+    ///         *cx += 1;
+    ///     })
+    ///     .start("open");
+    ///
+    /// sm.send("close").unwrap();
+    /// assert!(sm.send("close").is_err());
+    /// assert_eq!(*sm.context(), 1);
+    /// ```
+    pub fn on_done(mut self, f: impl FnMut(&S, &mut Ctx) + Send + Sync + 'a) -> Self {
+        self.extensions.on_done = Some(Box::new(f));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine, Ready};
+    use crate::error::TransitionError;
+
+    fn push(cx: ContextMut<char, u8, Vec<String>>) {
+        cx.context.push(format!("action {}", cx.to));
+    }
+
+    fn machine() -> Machine<'static, char, u8, Vec<String>, (), Ready> {
+        Machine::with_context(Vec::new())
+            .on_next(Builder::new('a').on(0).go_to('b').action(push))
+            .on_next(Builder::new('b').on(1).go_to('c').is_final().action(push))
+            .on_start(|state, cx| cx.push(format!("start {state}")))
+            .on_done(|state, cx| cx.push(format!("done {state}")))
+            .start('a')
+    }
+
+    #[test]
+    fn lifecycle_test() {
+        let mut sm = machine();
+        sm.send(0).unwrap();
+        sm.send(1).unwrap();
+        assert_eq!(sm.send(1), Err(TransitionError::Done));
+
+        assert_eq!(*sm.context(), ["start a", "action b", "action c", "done c"]);
+    }
+
+    #[test]
+    fn lifecycle_order_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::new('a').on(0).go_to('b').is_final().action(push))
+            .on_done(|_, cx: &mut Vec<String>| cx.push(String::from("done")))
+            .start('a');
+
+        sm.set_on_transition(|cx| assert_eq!(*cx.context, ["action b"]));
+        sm.send(0).unwrap();
+        assert_eq!(*sm.context(), ["action b", "done"]);
+    }
+
+    #[test]
+    fn lifecycle_restart_test() {
+        let mut sm = machine();
+        sm.send(0).unwrap();
+        sm.send(1).unwrap();
+
+        let mut sm = sm.into_builder().start('b');
+        sm.send(1).unwrap();
+
+        assert_eq!(
+            *sm.context(),
+            ["start a", "action b", "action c", "done c", "start b", "action c", "done c"]
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use super::{Machine, Ready};
+use crate::error::TransitionError;
+use std::time::{Duration, Instant};
+
+// How many times and how often a transition can happen, and its usage so far.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct FireLimit {
+    pub(crate) max_fires: Option<u32>,
+    pub(crate) cooldown: Option<Duration>,
+    fires: u32,
+    last_fired: Option<Instant>,
+}
+
+impl FireLimit {
+    // Returns an error if the transition cannot happen, `now` is only required if there is a cooldown.
+    pub(crate) fn check(&self, now: Option<Instant>) -> Result<(), TransitionError> {
+        if self.remaining() == Some(0) {
+            return Err(TransitionError::TransitionExhausted);
+        }
+
+        match (self.next_allowed(), now) {
+            (Some(next_allowed), Some(now)) if now < next_allowed => {
+                Err(TransitionError::CoolingDown)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // Records that the transition happened.
+    pub(crate) fn fire(&mut self, now: Option<Instant>) {
+        self.fires = self.fires.saturating_add(1);
+        self.last_fired = now;
+    }
+
+    fn remaining(&self) -> Option<u32> {
+        self.max_fires.map(|max| max.saturating_sub(self.fires))
+    }
+
+    fn next_allowed(&self) -> Option<Instant> {
+        Some(self.last_fired? + self.cooldown?)
+    }
+}
+
+/// The usage of a transition with a fire limit or a cooldown, returned by `Machine::transition_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransitionStatus {
+    /// The number of times the transition happened.
+    pub fires: u32,
+
+    /// The number of times the transition can still happen, if it has a fire limit.
+    pub remaining: Option<u32>,
+
+    /// The earliest time the transition can happen again, if it is cooling down.
+    pub next_allowed: Option<Instant>,
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    S: PartialEq,
+    E: PartialEq,
+{
+    /// Returns the usage of the transition for the event from the given state,
+    /// or `None` if there is no transition or it doesn't have a fire limit nor a cooldown.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("failed").on("retry").go_to("connecting").max_fires(3))
+    ///     .on_next(Builder::new("connecting").on("fail").go_to("failed"))
+    ///     .start("failed");
+    ///
+    /// sm.send("retry").unwrap();
+    /// sm.send("fail").unwrap();
+    ///
+    /// let status = sm.transition_status(&"failed", &"retry").unwrap();
+    /// assert_eq!(status.fires, 1);
+    /// assert_eq!(status.remaining, Some(2));
+    /// ```
+    pub fn transition_status(&self, from: &S, event: &E) -> Option<TransitionStatus> {
+        let limit = self.transitions.get(event, from)?.limit.as_ref()?;
+
+        let now = self.extensions.clock.now();
+        let next_allowed = limit.next_allowed().filter(|t| *t > now);
+
+        Some(TransitionStatus {
+            fires: limit.fires,
+            remaining: limit.remaining(),
+            next_allowed,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::clock::{Clock, MockClock};
+    use crate::error::TransitionError;
+    use std::time::Duration;
+
+    #[test]
+    fn max_fires_test() {
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new("failed")
+                    .on("retry")
+                    .go_to("connecting")
+                    .max_fires(3),
+            )
+            .on_next(Builder::new("connecting").on("fail").go_to("failed"))
+            .start("failed");
+
+        for _ in 0..3 {
+            sm.send("retry").unwrap();
+            sm.send("fail").unwrap();
+        }
+
+        assert_eq!(sm.send("retry"), Err(TransitionError::TransitionExhausted));
+        assert_eq!(*sm.current(), "failed");
+
+        let status = sm.transition_status(&"failed", &"retry").unwrap();
+        assert_eq!(status.fires, 3);
+        assert_eq!(status.remaining, Some(0));
+        assert!(sm.transition_status(&"connecting", &"fail").is_none());
+    }
+
+    #[test]
+    fn cooldown_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition(0, 'r').cooldown(Duration::from_secs(10)))
+            .with_clock(clock.clone())
+            .start(0);
+
+        sm.send('r').unwrap();
+        assert_eq!(sm.send('r'), Err(TransitionError::CoolingDown));
+
+        let status = sm.transition_status(&0, &'r').unwrap();
+        assert_eq!(status.remaining, None);
+        assert_eq!(
+            status.next_allowed,
+            Some(clock.now() + Duration::from_secs(10))
+        );
+
+        clock.advance(Duration::from_secs(10));
+        assert_eq!(sm.transition_status(&0, &'r').unwrap().next_allowed, None);
+        sm.send('r').unwrap();
+    }
+}
@@ -0,0 +1,475 @@
+use super::machine::split;
+use super::{Limits, Machine, OnAction, SendAction, SharedAction, Transition, TransitionTable};
+use crate::common::json::{self, Value};
+use crate::error::LoadError;
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+/// A transition of a `MachineDefinitionFile`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct DefinitionEntry {
+    /// The name of the state where the transition starts.
+    pub from: String,
+
+    /// The name of the event that triggers the transition.
+    pub event: String,
+
+    /// The name of the state where the transition ends.
+    pub to: String,
+
+    /// Whether the transition is final.
+    pub is_final: bool,
+
+    /// The name of the registered action to run, if any.
+    pub action: Option<String>,
+}
+
+/// A state machine definition authored outside the code.
+///
+/// The JSON format is an object with a `transitions` list of `{ from, event, to, final, action }` entries,
+/// where `final` and `action` are optional, and an optional `states` list declaring all the states.
+///
+/// ```json
+/// {
+///   "states": ["Idle", "Running"],
+///   "transitions": [
+///     { "from": "Idle", "event": "start", "to": "Running", "action": "log_entry" },
+///     { "from": "Running", "event": "stop", "to": "Idle", "final": true }
+///   ]
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MachineDefinitionFile {
+    /// The declared states, if any.
+    pub states: Option<Vec<String>>,
+
+    /// The transitions of the machine.
+    pub transitions: Vec<DefinitionEntry>,
+}
+
+impl MachineDefinitionFile {
+    /// Parses a definition from JSON.
+    pub fn from_json(input: &str) -> Result<Self, LoadError> {
+        let value = json::parse(input).map_err(LoadError::Parse)?;
+
+        let states = match value.get("states") {
+            None | Some(Value::Null) => None,
+            Some(states) => Some(
+                states
+                    .as_array()
+                    .ok_or_else(|| parse_error("`states` must be a list"))?
+                    .iter()
+                    .map(|s| s.as_str().map(String::from))
+                    .collect::<Option<Vec<_>>>()
+                    .ok_or_else(|| parse_error("`states` must be a list of strings"))?,
+            ),
+        };
+
+        let transitions = value
+            .get("transitions")
+            .and_then(|t| t.as_array())
+            .ok_or_else(|| parse_error("`transitions` must be a list"))?
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| parse_entry(index, entry))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(MachineDefinitionFile {
+            states,
+            transitions,
+        })
+    }
+
+    /// Returns the names of the states and events of the transitions as a table, without the actions.
+    pub fn to_table(&self) -> TransitionTable<String, String> {
+        self.transitions
+            .iter()
+            .map(|t| (t.from.clone(), t.event.clone(), t.to.clone(), t.is_final))
+            .collect()
+    }
+}
+
+fn parse_error(reason: &str) -> LoadError {
+    LoadError::Parse(reason.to_owned())
+}
+
+fn parse_entry(index: usize, entry: &Value) -> Result<DefinitionEntry, LoadError> {
+    let field = |name: &str| {
+        entry
+            .get(name)
+            .and_then(|v| v.as_str())
+            .map(String::from)
+            .ok_or_else(|| {
+                LoadError::Parse(format!("transition {index} must have a `{name}` string"))
+            })
+    };
+
+    let is_final = match entry.get("final") {
+        None => false,
+        Some(value) => value.as_bool().ok_or_else(|| {
+            LoadError::Parse(format!("`final` of transition {index} must be a boolean"))
+        })?,
+    };
+
+    let action = match entry.get("action") {
+        None | Some(Value::Null) => None,
+        Some(_) => Some(field("action")?),
+    };
+
+    Ok(DefinitionEntry {
+        from: field("from")?,
+        event: field("event")?,
+        to: field("to")?,
+        is_final,
+        action,
+    })
+}
+
+type ActionFactory<'a, S, E, Ctx> = Box<dyn Fn() -> Box<SendAction<'a, S, E, Ctx>> + 'a>;
+
+/// Builds state machines from a `MachineDefinitionFile`, resolving the actions by name.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// let definition = MachineDefinitionFile::from_json(r#"{
+///     "transitions": [
+///         { "from": "idle", "event": "start", "to": "running", "action": "count" },
+///         { "from": "running", "event": "stop", "to": "stopped", "final": true, "action": "count" }
+///     ]
+/// }"#).unwrap();
+///
+/// let mut sm = DefinitionLoader::new()
+///     .register_action("count", |cx: ContextMut<String, String, i32>| *cx.context += 1)
+///     .build(&definition, 0)
+///     .unwrap()
+///     .start(String::from("idle"));
+///
+/// sm.send(String::from("start")).unwrap();
+/// sm.send(String::from("stop")).unwrap();
+/// assert_eq!(*sm.context(), 2);
+/// ```
+pub struct DefinitionLoader<'a, S, E, Ctx> {
+    actions: Vec<(String, ActionFactory<'a, S, E, Ctx>)>,
+    limits: Option<Limits>,
+}
+
+impl<'a, S, E, Ctx> DefinitionLoader<'a, S, E, Ctx> {
+    /// Returns a loader without actions.
+    pub fn new() -> Self {
+        DefinitionLoader {
+            actions: Vec::new(),
+            limits: None,
+        }
+    }
+
+    /// Limits the size of the machines built by this loader, see `Machine::limits`.
+    ///
+    /// The definitions exceeding the limits fail to build with `LoadError::Build`.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        self.limits = Some(limits);
+        self
+    }
+
+    /// Registers an action with the given name, the same action is shared by all the transitions using it.
+    pub fn register_action<F>(mut self, name: impl Into<String>, action: F) -> Self
+    where
+        F: OnAction<S, E, Ctx> + Send + 'a,
+    {
+        let action = SharedAction::new(action);
+        let factory = move || Box::new(action.clone()) as Box<SendAction<'a, S, E, Ctx>>;
+        self.actions.push((name.into(), Box::new(factory)));
+        self
+    }
+
+    /// Builds a machine from the definition, converting the states and events with `FromStr`.
+    pub fn build(
+        &self,
+        definition: &MachineDefinitionFile,
+        context: Ctx,
+    ) -> Result<Machine<'a, S, E, Ctx, ()>, LoadError>
+    where
+        S: FromStr + PartialEq + Clone,
+        E: FromStr + PartialEq + Clone,
+    {
+        self.build_with(definition, context, |s| s.parse().ok(), |e| e.parse().ok())
+    }
+
+    /// Builds a machine from the definition, converting the states and events with the given functions.
+    pub fn build_with(
+        &self,
+        definition: &MachineDefinitionFile,
+        context: Ctx,
+        parse_state: impl Fn(&str) -> Option<S>,
+        parse_event: impl Fn(&str) -> Option<E>,
+    ) -> Result<Machine<'a, S, E, Ctx, ()>, LoadError>
+    where
+        S: PartialEq + Clone,
+        E: PartialEq + Clone,
+    {
+        self.validate_states(definition)?;
+
+        let mut machine = Machine::with_context(context);
+
+        if let Some(limits) = self.limits {
+            machine = machine.limits(limits);
+        }
+
+        for entry in definition.transitions.iter() {
+            let state = |name: &str| {
+                parse_state(name).ok_or_else(|| LoadError::InvalidState(name.to_owned()))
+            };
+
+            let event = parse_event(&entry.event)
+                .ok_or_else(|| LoadError::InvalidEvent(entry.event.clone()))?;
+
+            let action = match &entry.action {
+                None => None,
+                Some(name) => {
+                    let (_, factory) = self
+                        .actions
+                        .iter()
+                        .find(|(n, _)| n == name)
+                        .ok_or_else(|| LoadError::UnknownAction(name.clone()))?;
+
+                    Some(factory())
+                }
+            };
+
+            let (event, from, next) = split(Transition::<S, E, Ctx> {
+                from: state(&entry.from)?,
+                to: state(&entry.to)?,
+                event,
+                is_final: entry.is_final,
+                action,
+                before: None,
+                guard: None,
+                limit: None,
+                priority: 0,
+                label: None,
+                _marker: PhantomData,
+            });
+
+            let added = machine
+                .extensions
+                .check_limits(&event, &from, &next.next)
+                .map_err(LoadError::Build)?;
+
+            machine
+                .transitions
+                .try_insert(event, from, next)
+                .map_err(|_| LoadError::DuplicateTransition {
+                    from: entry.from.clone(),
+                    event: entry.event.clone(),
+                })?;
+
+            machine.extensions.count_added(added);
+        }
+
+        Ok(machine)
+    }
+
+    // Checks that all the target states are known.
+    //
+    // If the states are declared, all the states must be declared,
+    // otherwise a state only referenced as target must be reached by a final transition.
+    fn validate_states(&self, definition: &MachineDefinitionFile) -> Result<(), LoadError> {
+        let transitions = &definition.transitions;
+
+        for entry in transitions.iter() {
+            let known = match &definition.states {
+                Some(states) => {
+                    if !states.contains(&entry.from) {
+                        return Err(LoadError::UnknownState(entry.from.clone()));
+                    }
+
+                    states.contains(&entry.to)
+                }
+                None => entry.is_final || transitions.iter().any(|t| t.from == entry.to),
+            };
+
+            if !known {
+                return Err(LoadError::UnknownState(entry.to.clone()));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<S, E, Ctx> Default for DefinitionLoader<'_, S, E, Ctx> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DefinitionEntry, DefinitionLoader, MachineDefinitionFile};
+    use crate::blocking::{ContextMut, Limits, Machine};
+    use crate::error::{BuildError, LimitKind, LoadError};
+    use std::str::FromStr;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running,
+        Stopped,
+    }
+
+    impl FromStr for State {
+        type Err = ();
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "Idle" => Ok(State::Idle),
+                "Running" => Ok(State::Running),
+                "Stopped" => Ok(State::Stopped),
+                _ => Err(()),
+            }
+        }
+    }
+
+    const DEFINITION: &str = r#"{
+        "transitions": [
+            { "from": "Idle", "event": "start", "to": "Running", "action": "log_entry" },
+            { "from": "Running", "event": "pause", "to": "Idle" },
+            { "from": "Running", "event": "stop", "to": "Stopped", "final": true, "action": "log_entry" }
+        ]
+    }"#;
+
+    type Loader = DefinitionLoader<'static, State, String, Vec<String>>;
+
+    fn loader() -> Loader {
+        DefinitionLoader::new()
+            .register_action("log_entry", |cx: ContextMut<State, String, Vec<String>>| {
+                cx.context.push(format!("{:?}", cx.to))
+            })
+    }
+
+    #[test]
+    fn from_json_test() {
+        let definition = MachineDefinitionFile::from_json(DEFINITION).unwrap();
+
+        assert_eq!(definition.states, None);
+        assert_eq!(
+            definition.transitions[2],
+            DefinitionEntry {
+                from: "Running".into(),
+                event: "stop".into(),
+                to: "Stopped".into(),
+                is_final: true,
+                action: Some("log_entry".into()),
+            }
+        );
+
+        let table = definition.to_table();
+        assert_eq!(table.len(), definition.transitions.len());
+        assert_eq!(Machine::from_table(table).transitions().count(), 3);
+
+        assert!(matches!(
+            MachineDefinitionFile::from_json(r#"{ "transitions": [{ "from": "Idle" }] }"#),
+            Err(LoadError::Parse(_))
+        ));
+        assert!(matches!(
+            MachineDefinitionFile::from_json(&"[".repeat(200_000)),
+            Err(LoadError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn build_test() {
+        let definition = MachineDefinitionFile::from_json(DEFINITION).unwrap();
+        let mut sm = loader()
+            .build(&definition, Vec::new())
+            .unwrap()
+            .start(State::Idle);
+
+        for event in ["start", "pause", "start", "stop"] {
+            sm.send(event.to_owned()).unwrap();
+        }
+
+        assert!(sm.is_done());
+        assert_eq!(sm.context(), &["Running", "Running", "Stopped"]);
+    }
+
+    #[test]
+    fn build_limits_test() {
+        let definition = MachineDefinitionFile::from_json(DEFINITION).unwrap();
+        let limits = |max_transitions| Limits {
+            max_transitions: Some(max_transitions),
+            ..Limits::default()
+        };
+
+        assert!(loader()
+            .limits(limits(3))
+            .build(&definition, Vec::new())
+            .is_ok());
+        assert_eq!(
+            loader()
+                .limits(limits(2))
+                .build(&definition, Vec::new())
+                .map(|_| ()),
+            Err(LoadError::Build(BuildError::LimitExceeded {
+                which: LimitKind::Transitions,
+                limit: 2,
+                actual: 3
+            }))
+        );
+    }
+
+    #[test]
+    fn build_error_test() {
+        let entry = |from: &str, to: &str, action: Option<&str>| DefinitionEntry {
+            from: from.into(),
+            event: "go".into(),
+            to: to.into(),
+            is_final: false,
+            action: action.map(String::from),
+        };
+
+        let build = |transitions: Vec<DefinitionEntry>, states: Option<Vec<&str>>| {
+            let definition = MachineDefinitionFile {
+                states: states.map(|s| s.into_iter().map(String::from).collect()),
+                transitions,
+            };
+
+            loader().build(&definition, Vec::new()).map(|_| ())
+        };
+
+        assert_eq!(
+            build(vec![entry("Idle", "Idle", Some("missing"))], None),
+            Err(LoadError::UnknownAction("missing".into()))
+        );
+        assert_eq!(
+            build(vec![entry("Idle", "Running", None)], None),
+            Err(LoadError::UnknownState("Running".into()))
+        );
+        assert_eq!(
+            build(
+                vec![entry("Idle", "Running", None)],
+                Some(vec!["Idle", "Running"])
+            ),
+            Ok(())
+        );
+        assert_eq!(
+            build(
+                vec![entry("Idle", "Nope", None)],
+                Some(vec!["Idle", "Nope"])
+            ),
+            Err(LoadError::InvalidState("Nope".into()))
+        );
+        assert_eq!(
+            build(
+                vec![entry("Idle", "Idle", None), entry("Idle", "Idle", None)],
+                None
+            ),
+            Err(LoadError::DuplicateTransition {
+                from: "Idle".into(),
+                event: "go".into()
+            })
+        );
+    }
+}
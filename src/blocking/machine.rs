@@ -1,22 +1,86 @@
-use super::{Context, ContextMut, OnAction};
+use super::extensions::Extensions;
+use super::journal::Journal;
+use super::limit::FireLimit;
+use super::name::duplicate_message;
+use super::outcome::outcome_of;
+use super::panic::invoke;
+use super::scope::Expiry;
+use super::time_guard::TimeGuard;
+use super::{
+    Context, ContextMut, FinishInfo, JournalEntry, JournalOrder, LocalAction, OnAction, SendAction,
+    StatelessAction, SyncAction, UnhandledContext,
+};
 use crate::blocking::OnTransition;
-use crate::blocking::{IntoTransition, Transition};
-use crate::common::map::{Events, States, TransitionMap};
+use crate::blocking::{IntoTransition, IntoTransitions, Transition};
+use crate::clock::Clock;
 use crate::error::TransitionError;
+use crate::export::plantuml;
+use crate::graph::{Edge, Graph};
+use crate::map::{Entry, TransitionMap};
 pub use private::*;
-use std::{fmt::Debug, marker::PhantomData};
+use std::{cell::Cell, fmt::Debug, marker::PhantomData};
 
 #[doc(hidden)]
-pub struct Next<'a, S, E, Ctx> {
-    next: S,
-    is_final: bool,
-    action: Option<Box<dyn OnAction<S, E, Ctx> + Send + 'a>>,
+pub struct Next<S, A: ?Sized> {
+    pub(crate) next: S,
+    pub(crate) is_final: bool,
+    pub(crate) action: Option<Box<A>>,
+    pub(crate) before: Option<Box<A>>,
+    pub(crate) guard: Option<TimeGuard>,
+    pub(crate) limit: Option<FireLimit>,
+    pub(crate) priority: u8,
+    pub(crate) label: Option<Box<str>>,
+    // The other guarded transitions for the same event from the same state, in the order they are tried.
+    pub(crate) candidates: Vec<Next<S, A>>,
+    // Whether the event is dropped in the state without a transition, see `Machine::ignore`.
+    pub(crate) ignored: bool,
+    // When the transition is removed, see `Machine::add_scoped_transition`.
+    pub(crate) scope: Option<Expiry>,
+    #[cfg(feature = "rand")]
+    pub(crate) weight: u32,
 }
 
-impl<S, E, Ctx> Debug for Next<'_, S, E, Ctx>
+impl<S, A: ?Sized> Next<S, A> {
+    // Adds other guarded transition for the same event from the same state,
+    // the transitions are tried by priority and then in the order they were added.
+    pub(crate) fn add_candidate(&mut self, mut next: Next<S, A>) {
+        if next.priority > self.priority {
+            // The previous first transition goes before the candidates, it was added before them
+            std::mem::swap(self, &mut next);
+            self.candidates = std::mem::take(&mut next.candidates);
+            self.candidates.insert(0, next);
+            return;
+        }
+
+        let pos = self
+            .candidates
+            .iter()
+            .position(|c| c.priority < next.priority)
+            .unwrap_or(self.candidates.len());
+
+        self.candidates.insert(pos, next);
+    }
+
+    // Returns the first transition which guard allows it, if any.
+    pub(crate) fn select_mut(&mut self, allows: impl Fn(&TimeGuard) -> bool) -> Option<&mut Self> {
+        if self.guard.as_ref().is_none_or(&allows) {
+            return Some(self);
+        }
+
+        self.candidates
+            .iter_mut()
+            .find(|c| c.guard.as_ref().is_none_or(&allows))
+    }
+
+    // Returns this transition followed by its candidates.
+    pub(crate) fn candidates(&self) -> impl Iterator<Item = &Self> {
+        std::iter::once(self).chain(self.candidates.iter())
+    }
+}
+
+impl<S, A: ?Sized> Debug for Next<S, A>
 where
     S: Debug,
-    Ctx: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Node")
@@ -28,6 +92,14 @@ where
 
 /// Represents a finite state machine that can transition between different states based on events.
 ///
+/// # Thread safety
+///
+/// A machine is `Send` if the states, events, context and `on_transition` are `Send`,
+/// the actions of the default machine are required to be `Send`,
+/// and the other callbacks like `on_unhandled` or the clock are required to be `Send + Sync`.
+/// A machine is only `Sync` if its actions are also `Sync`, which is required by a `SyncMachine`,
+/// otherwise it can still be shared with an `Arc<Mutex<_>>`. A `LocalMachine` is neither.
+///
 /// # Example
 ///
 /// ```rust
@@ -66,33 +138,191 @@ where
 ///
 /// assert_eq!(*sm.context(), 2);
 /// ```
-pub struct Machine<'a, S, E, Ctx, F, Step = Build> {
+pub struct Machine<'a, S, E, Ctx, F, Step = Build, A: ?Sized = SendAction<'a, S, E, Ctx>, C = Ctx> {
     // A map of state and event transitions to the next state and associated action.
-    transitions: TransitionMap<S, E, Next<'a, S, E, Ctx>>,
+    pub(crate) transitions: TransitionMap<S, E, Next<S, A>>,
 
     // The current state of the machine, will be `None` if the machine had not started.
-    current: Option<S>,
+    pub(crate) current: Option<S>,
 
     // Indicates whether the state machine has finished execution.
-    done: bool,
+    pub(crate) done: bool,
 
-    // A context object for storing and passing data between state transitions.
-    context: Ctx,
+    // A context object for storing and passing data between state transitions,
+    // or `Lent` if the context is lent on each `send_with`.
+    pub(crate) context: C,
 
     // An optional callback function to execute when a transition occurs.
-    on_transition: Option<F>,
+    pub(crate) on_transition: Option<F>,
 
-    _marker: PhantomData<Step>,
+    // The optional features used by this state machine.
+    pub(crate) extensions: Extensions<'a, S, E, Ctx>,
+
+    pub(crate) _marker: PhantomData<(&'a (), Step)>,
 }
 
-impl<S, E, Ctx, F, Step> Debug for Machine<'_, S, E, Ctx, F, Step>
+/// A state machine that doesn't borrow from its environment, all its actions must be `'static`.
+///
+/// This type can be stored in a struct, returned from functions or moved across threads.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// #[derive(Debug, Clone, PartialEq, Eq)]
+/// enum Light {
+///     On,
+///     Off,
+/// }
+///
+/// struct App {
+///     light: OwnedMachine<Light, (), i32, (), Ready>,
+/// }
+///
+/// fn create_light() -> OwnedMachine<Light, (), i32, (), Ready> {
+///     Machine::with_context_owned(0)
+///         .on_next(Builder::new(Light::Off).on(()).go_to(Light::On).action(
+///             |cx: ContextMut<Light, (), i32>| {
+///                 *cx.context += 1;
+///             },
+///         ))
+///         .on_next(Builder::new(Light::On).on(()).go_to(Light::Off))
+///         .start(Light::Off)
+/// }
+///
+/// let mut app = App {
+///     light: create_light(),
+/// };
+///
+/// app.light.send(()).unwrap();
+///
+/// let handle = std::thread::spawn(move || {
+///     app.light.send(()).unwrap();
+///     app
+/// });
+///
+/// let app = handle.join().unwrap();
+/// assert_eq!(*app.light.current(), Light::Off);
+/// assert_eq!(*app.light.context(), 1);
+/// ```
+pub type OwnedMachine<S, E, Ctx = (), F = (), Step = Build> = Machine<'static, S, E, Ctx, F, Step>;
+
+/// A state machine which actions are not required to be `Send`,
+/// so they can capture types like `Rc` or `RefCell`.
+///
+/// A `LocalMachine` cannot be sent to other threads.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+/// use std::{cell::RefCell, rc::Rc};
+///
+/// let log = Rc::new(RefCell::new(Vec::new()));
+/// let log_clone = log.clone();
+///
+/// let mut sm = Machine::new_local()
+///     .on_next(
+///         LocalBuilder::self_transition("idle", "ping").action(
+///             move |cx: ContextMut<&'static str, &'static str, ()>| {
+///                 log_clone.borrow_mut().push(*cx.event);
+///             },
+///         ),
+///     )
+///     .start("idle");
+///
+/// sm.send("ping").unwrap();
+/// assert_eq!(*log.borrow(), vec!["ping"]);
+/// ```
+///
+/// ```compile_fail
+/// use restate::blocking::*;
+///
+/// fn assert_send<T: Send>(_: &T) {}
+///
+/// let sm: LocalMachine<(), (), (), (), Ready> = Machine::new_local()
+///     .on_next(Builder::self_transition((), ()))
+///     .start(());
+///
+/// assert_send(&sm);
+/// ```
+pub type LocalMachine<'a, S, E, Ctx, F = (), Step = Build> =
+    Machine<'a, S, E, Ctx, F, Step, LocalAction<'a, S, E, Ctx>>;
+
+/// A state machine which actions are required to be `Send + Sync`,
+/// so the machine is `Sync` and can be shared across threads, for example in an `Arc<RwLock<_>>`.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+/// use std::sync::{Arc, RwLock};
+///
+/// let sm: SyncMachine<&str, &str, u32, (), Ready> = Machine::with_context_sync(0)
+///     .on_next(SyncBuilder::self_transition("idle", "ping").action(
+///         |cx: ContextMut<&str, &str, u32>| {
+///             *cx.context += 1;
+///         },
+///     ))
+///     .start("idle");
+///
+/// let sm = Arc::new(RwLock::new(sm));
+/// let reader = sm.clone();
+///
+/// std::thread::spawn(move || sm.write().unwrap().send("ping").unwrap())
+///     .join()
+///     .unwrap();
+///
+/// assert_eq!(*reader.read().unwrap().context(), 1);
+/// ```
+pub type SyncMachine<'a, S, E, Ctx, F = (), Step = Build> =
+    Machine<'a, S, E, Ctx, F, Step, SyncAction<'a, S, E, Ctx>>;
+
+/// A state machine which actions are `Fn + Send + Sync`, so they don't keep state between calls,
+/// its transitions can be shared by many instances with `Machine::into_definition`.
+///
+/// ```compile_fail
+/// use restate::blocking::*;
+///
+/// // A one-shot action keeps whether it already ran
+/// let sm: StatelessMachine<(), (), ()> = Machine::new_stateless()
+///     .on_next(Builder::self_transition((), ()).action_once(|_: ContextMut<(), (), ()>| {}));
+/// ```
+pub type StatelessMachine<'a, S, E, Ctx, F = (), Step = Build> =
+    Machine<'a, S, E, Ctx, F, Step, StatelessAction<'a, S, E, Ctx>>;
+
+/// A state machine that doesn't store a context, the context is lent to it on each transition with `send_with`,
+/// see `Machine::with_lent_context`.
+///
+/// The methods using the context of the machine like `send`, `context` or `run_to_completion` don't exist on it.
+///
+/// ```compile_fail
+/// use restate::blocking::*;
+///
+/// let mut sm: LentMachine<&str, &str, u32, (), Ready> = Machine::with_lent_context()
+///     .on_next(Builder::self_transition("idle", "ping"))
+///     .start("idle");
+///
+/// sm.send("ping").unwrap();
+/// ```
+pub type LentMachine<'a, S, E, Ctx, F = (), Step = Build> =
+    Machine<'a, S, E, Ctx, F, Step, SendAction<'a, S, E, Ctx>, Lent<Ctx>>;
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Debug for Machine<'_, S, E, Ctx, F, Step, A, C>
 where
     S: Debug,
     E: Debug,
-    Ctx: Debug,
+    C: Debug,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("StateMachine")
+        let mut debug = f.debug_struct("StateMachine");
+
+        if let Some(name) = &self.extensions.name {
+            debug.field("name", name);
+        }
+
+        debug
             .field("current", &self.current)
             .field("done", &self.done)
             .field("context", &self.context)
@@ -104,58 +334,155 @@ where
 impl<'a, S, E> Machine<'a, S, E, (), (), Build> {
     /// Returns a new `StateMachine`.
     pub fn new() -> Machine<'a, S, E, (), (), Build> {
-        Machine {
-            transitions: TransitionMap::new(),
-            current: None,
-            done: false,
-            context: (),
-            on_transition: None,
-            _marker: PhantomData,
-        }
+        Machine::with_context(())
+    }
+
+    /// Returns a new `StateMachine` with space for the given number of states,
+    /// and `transitions_per_state` transitions for each state.
+    pub fn with_capacity(
+        states: usize,
+        transitions_per_state: usize,
+    ) -> Machine<'a, S, E, (), (), Build> {
+        let transitions = TransitionMap::with_capacity(states, transitions_per_state);
+        Machine::from_parts(transitions, ())
+    }
+
+    /// Returns a new `StateMachine` which actions are not required to be `Send`.
+    pub fn new_local() -> LocalMachine<'a, S, E, ()> {
+        Machine::with_context_local(())
+    }
+
+    /// Returns a new `StateMachine` which actions are required to be `Send + Sync`.
+    pub fn new_sync() -> SyncMachine<'a, S, E, ()> {
+        Machine::with_context_sync(())
+    }
+
+    /// Returns a new `StateMachine` which actions are required to be `Fn + Send + Sync`.
+    pub fn new_stateless() -> StatelessMachine<'a, S, E, ()> {
+        Machine::with_context_stateless(())
     }
 
     /// Returns a new `StateMachine` with the given context.
     pub fn with_context<Ctx>(context: Ctx) -> Machine<'a, S, E, Ctx, (), Build> {
+        Machine::from_parts(TransitionMap::new(), context)
+    }
+
+    /// Returns a new `StateMachine` with the given context which actions are not required to be `Send`.
+    pub fn with_context_local<Ctx>(context: Ctx) -> LocalMachine<'a, S, E, Ctx> {
+        Machine::from_parts(TransitionMap::new(), context)
+    }
+
+    /// Returns a new `StateMachine` with the given context which actions are required to be `Send + Sync`.
+    pub fn with_context_sync<Ctx>(context: Ctx) -> SyncMachine<'a, S, E, Ctx> {
+        Machine::from_parts(TransitionMap::new(), context)
+    }
+
+    /// Returns a new `StateMachine` with the given context which actions are required to be `Fn + Send + Sync`.
+    pub fn with_context_stateless<Ctx>(context: Ctx) -> StatelessMachine<'a, S, E, Ctx> {
+        Machine::from_parts(TransitionMap::new(), context)
+    }
+}
+
+impl<S, E, Ctx, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, (), Step, A, C> {
+    pub(crate) fn from_parts(transitions: TransitionMap<S, E, Next<S, A>>, context: C) -> Self {
         Machine {
-            transitions: TransitionMap::new(),
+            transitions,
             current: None,
             done: false,
             context,
             on_transition: None,
+            extensions: Extensions::new(),
             _marker: PhantomData,
         }
     }
 }
 
-impl<'a, S, E, Ctx> Machine<'a, S, E, Ctx, (), Build>
+impl<S, E> Machine<'static, S, E, (), (), Build> {
+    /// Returns a new `StateMachine` that doesn't borrow from its environment.
+    pub fn new_owned() -> OwnedMachine<S, E> {
+        Machine::new()
+    }
+
+    /// Returns a new `StateMachine` with the given context that doesn't borrow from its environment.
+    pub fn with_context_owned<Ctx>(context: Ctx) -> OwnedMachine<S, E, Ctx> {
+        Machine::with_context(context)
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized, C> Machine<'a, S, E, Ctx, (), Build, A, C>
 where
     E: PartialEq,
     S: PartialEq,
 {
-    /// Adds a transition from a state to other based on an event.
-    pub fn on_next(mut self, transition: impl IntoTransition<'a, S, E, Ctx>) -> Self {
-        let Transition {
-            from,
-            to,
-            event,
-            action,
-            is_final,
-        } = transition.into_transition();
+    /// Adds a transition from a state to other based on an event,
+    /// or one transition per source state for `Builder::from_states`.
+    ///
+    /// A guarded transition for the same event from the same state as other guarded transitions
+    /// is added as a candidate, the first one which guard allows it is taken, see `Builder::priority`.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state, and any of them is not guarded.
+    pub fn on_next(mut self, transitions: impl IntoTransitions<'a, S, E, Ctx, A>) -> Self {
+        let transitions = transitions.into_transitions();
+        let single = transitions.size_hint() == (1, Some(1));
+
+        for (index, transition) in transitions.enumerate() {
+            let (event, from, next) = split(transition);
+
+            let added = match self.extensions.check_limits(&event, &from, &next.next) {
+                Ok(added) => added,
+                Err(err) => panic!("{err}"),
+            };
+
+            if let Some(invariants) = self.extensions.invariants.as_mut() {
+                invariants.check_insert(self.transitions.states(), &from);
+            }
+
+            if let Err(rejected) = insert_next(&mut self.transitions, event, from, next) {
+                let message = duplicate_message(
+                    self.extensions.name.as_deref(),
+                    rejected.label.as_deref(),
+                    (!single).then_some(index),
+                );
+
+                panic!("{message}");
+            }
+
+            self.extensions.count_added(added);
+        }
+
+        self
+    }
+
+    /// Adds a transition from a state to other based on an event,
+    /// replacing the existing transition for the event from the state and its action.
+    pub fn on_next_replace(mut self, transition: impl IntoTransition<'a, S, E, Ctx, A>) -> Self {
+        let (event, from, next) = split(transition);
+        let added = self.extensions.unchecked_limits(&event, &from, &next.next);
+
+        if let Some(replaced) = self.transitions.insert_or_replace(event, from, next) {
+            self.extensions.count_removed(replaced.candidates().count());
+        }
+
+        self.extensions.count_added(added);
+        self
+    }
+
+    /// Adds a transition from a state to other based on an event,
+    /// only if there is no transition for the event from the state.
+    pub fn on_next_if_absent(mut self, transition: impl IntoTransition<'a, S, E, Ctx, A>) -> Self {
+        let (event, from, next) = split(transition);
+        let added = self.extensions.unchecked_limits(&event, &from, &next.next);
+
+        if self.transitions.try_insert(event, from, next).is_ok() {
+            self.extensions.count_added(added);
+        }
 
-        self.transitions.insert(
-            event,
-            from,
-            Next {
-                next: to,
-                action,
-                is_final,
-            },
-        );
         self
     }
 
     /// Adds a function that is called when a transition occurs.
-    pub fn on_transition<F>(self, on_transition: F) -> Machine<'a, S, E, Ctx, F, Build>
+    pub fn on_transition<F>(self, on_transition: F) -> Machine<'a, S, E, Ctx, F, Build, A, C>
     where
         F: FnMut(Context<S, E, Ctx>),
     {
@@ -165,38 +492,143 @@ where
             done: false,
             context: self.context,
             on_transition: Some(on_transition),
+            extensions: self.extensions,
             _marker: PhantomData,
         }
     }
 }
 
-impl<'a, S, E, F, Ctx> Machine<'a, S, E, Ctx, F, Build> {
+impl<'a, S, E, F, Ctx, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Sets the clock used by this state machine, by default the system clock is used.
+    pub fn with_clock(mut self, clock: impl Clock + Send + Sync + 'a) -> Self {
+        self.extensions.clock = Box::new(clock);
+        self
+    }
+
     /// Starts this state machine with the given state.
-    pub fn start(self, initial_state: S) -> Machine<'a, S, E, Ctx, F, Ready> {
-        Machine {
+    pub fn start(mut self, initial_state: S) -> Machine<'a, S, E, Ctx, F, Ready, A, C>
+    where
+        C: ContextSlot<Ctx>,
+    {
+        // The transitions don't usually change after start, so they are stored without spare capacity
+        self.transitions.freeze();
+        self.extensions.enter();
+
+        let mut machine = Machine {
             current: Some(initial_state),
             transitions: self.transitions,
             done: false,
             context: self.context,
             on_transition: self.on_transition,
+            extensions: self.extensions,
+            _marker: PhantomData,
+        };
+
+        if let (Some(f), Some(state), Some(context)) = (
+            machine.extensions.on_start.as_mut(),
+            &machine.current,
+            machine.context.stored(),
+        ) {
+            f(state, context);
+        }
+
+        machine
+    }
+}
+
+impl<'a, S, E, F, Ctx, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Ready, A, C> {
+    /// Converts this state machine back into a builder, keeping its transitions, context and hooks.
+    ///
+    /// The current state, the done flag, the posted events and the undo history are dropped,
+    /// the machine must be started again.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// fn door() -> OwnedMachine<&'static str, &'static str, (), (), Ready> {
+    ///     Machine::new()
+    ///         .on_next(Builder::new("closed").on("open").go_to("opened"))
+    ///         .on_next(Builder::new("opened").on("close").go_to("closed"))
+    ///         .start("closed")
+    /// }
+    ///
+    /// let mut sm = door()
+    ///     .into_builder()
+    ///     .on_next(Builder::new("closed").on("lock").go_to("locked"))
+    ///     .start("closed");
+    ///
+    /// sm.send("lock").unwrap();
+    /// assert_eq!(*sm.current(), "locked");
+    /// ```
+    pub fn into_builder(mut self) -> Machine<'a, S, E, Ctx, F, Build, A, C> {
+        let extensions = &mut self.extensions;
+        extensions.sender.lock().clear();
+        extensions.node_hint = None;
+        extensions.poisoned = false;
+        extensions.paused = false;
+        extensions.finished = None;
+
+        if let Some(undo) = extensions.undo.as_mut() {
+            undo.clear();
+        }
+
+        Machine {
+            current: None,
+            transitions: self.transitions,
+            done: false,
+            context: self.context,
+            on_transition: self.on_transition,
+            extensions: self.extensions,
             _marker: PhantomData,
         }
     }
+
+    /// Sets the function that is called when a transition occurs,
+    /// replacing the current one including the one set with `on_transition` before the machine started.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use std::sync::{Arc, Mutex};
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .start("idle");
+    ///
+    /// let log = Arc::new(Mutex::new(Vec::new()));
+    /// let entries = log.clone();
+    /// sm.set_on_transition(move |cx: Context<&str, &str, ()>| {
+    ///     entries.lock().unwrap().push(format!("{} -> {}", cx.from, cx.to));
+    /// });
+    ///
+    /// sm.send("start").unwrap();
+    /// assert_eq!(*log.lock().unwrap(), ["idle -> running"]);
+    /// ```
+    pub fn set_on_transition(
+        &mut self,
+        on_transition: impl FnMut(Context<S, E, Ctx>) + Send + Sync + 'a,
+    ) {
+        self.on_transition = None;
+        self.extensions.on_transition = Some(Box::new(on_transition));
+    }
 }
 
-impl<S, E, F, Ctx> Machine<'_, S, E, Ctx, F, Ready>
+impl<S, E, F, Ctx, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
 where
     E: PartialEq,
     S: PartialEq + Clone,
     F: OnTransition<S, E, Ctx>,
 {
     /// Returns the states of the state machine.
-    pub fn states(&self) -> States<'_, S, E, Next<S, E, Ctx>> {
+    pub fn states(&self) -> impl Iterator<Item = &S> {
         self.transitions.states()
     }
 
     /// Returns the events of the state machine.
-    pub fn events(&self) -> Events<'_, S, E, Next<S, E, Ctx>> {
+    pub fn events(&self) -> impl Iterator<Item = &E> {
         self.transitions.events()
     }
 
@@ -205,76 +637,665 @@ where
         self.current.as_ref().unwrap()
     }
 
-    /// Returns the context used for this state machine.
-    pub fn context(&self) -> &Ctx {
-        &self.context
-    }
-
     /// Returns `true` if this state machine had done executing.
     pub fn is_done(&self) -> bool {
         self.done
     }
 }
-impl<S, E, F, Ctx> Machine<'_, S, E, Ctx, F, Ready>
+
+impl<S, E, F, Ctx, A: ?Sized> Machine<'_, S, E, Ctx, F, Ready, A> {
+    /// Returns the context used for this state machine.
+    pub fn context(&self) -> &Ctx {
+        &self.context
+    }
+}
+
+impl<S, E, F, Ctx, A> Machine<'_, S, E, Ctx, F, Ready, A>
 where
     E: PartialEq,
     S: PartialEq + Clone,
     F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
 {
     /// Triggers a transition.
     ///
+    /// The features that give the event back, like `feed` or `run_to_completion` returning the rejected event,
+    /// need its ownership and use this by-value path, see `send_ref` to keep using the event.
+    /// See `step` to also get the new state.
+    ///
     /// # Returns
     /// - Ok(S): The previous state.
     /// - Err(TransitionError): If the transition was not successful
     pub fn send(&mut self, event: E) -> Result<S, TransitionError> {
-        if self.done {
-            return Err(TransitionError::Done);
+        self.send_or_return(event).map_err(|(err, _)| err)
+    }
+
+    // Triggers a transition, and returns the event back if the transition was not successful.
+    pub(crate) fn send_or_return(&mut self, event: E) -> Result<S, (TransitionError, E)> {
+        let prev = match self.send_ref(&event) {
+            Ok(prev) => prev,
+            Err(err) => return Err((err, event)),
+        };
+
+        // The event is kept only if it completed the machine
+        if let (true, Some(info)) = (self.done, self.extensions.finished.as_mut()) {
+            info.event = Some(event);
+        }
+
+        Ok(prev)
+    }
+
+    /// Triggers a transition with a borrowed event, the event is never cloned nor required to be `Clone`.
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(TransitionError): If the transition was not successful
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on(String::from("start")).go_to("running"))
+    ///     .start("idle");
+    ///
+    /// let event = String::from("start");
+    /// assert_eq!(sm.send_ref(&event), Ok("idle"));
+    /// assert_eq!(event, "start");
+    /// ```
+    pub fn send_ref(&mut self, event: &E) -> Result<S, TransitionError> {
+        match self.try_dispatch(event, |context| context) {
+            Err(error) if self.extensions.on_error.is_some() => {
+                self.recover(error, event, |context| context)
+            }
+            result => result,
+        }
+    }
+}
+
+impl<S, E, F, Ctx, A, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+    C: ContextSlot<Ctx>,
+{
+    // Triggers a transition with the context taken from the machine by `context`,
+    // without calling `on_error` if it fails.
+    pub(crate) fn try_dispatch<'c>(
+        &'c mut self,
+        event: &E,
+        context: impl FnOnce(&'c mut C) -> &'c mut Ctx,
+    ) -> Result<S, TransitionError> {
+        if self.extensions.poisoned {
+            return Err(TransitionError::Poisoned);
+        }
+
+        if self.extensions.paused {
+            return Err(TransitionError::Paused);
         }
 
+        if self.extensions.scoped > 0 {
+            self.extensions.sends += 1;
+            self.remove_expired();
+        }
+
+        let context = context(&mut self.context);
+
         // SAFETY: If this state machine is in step `Ready`,
         // current state cannot be null
         let state = self.current.as_mut().unwrap();
 
+        if self.done {
+            let error = unhandled(&mut self.extensions, state, event, context, true)
+                .err()
+                .unwrap_or(TransitionError::Done);
+            return Err(error);
+        }
+
+        // The duplicated event is consumed without running any action nor hook
+        if self.extensions.dedupe.is_duplicate(event) {
+            return Ok(state.clone());
+        }
+
+        let found = find_next(
+            &mut self.transitions,
+            self.extensions.match_by_discriminant,
+            &mut self.extensions.node_hint,
+            event,
+            state,
+        );
+
+        let Some(found) = found else {
+            let error = unhandled(&mut self.extensions, state, event, context, false)
+                .err()
+                .unwrap_or(TransitionError::InvalidTransition);
+            return Err(error);
+        };
+
+        // The first of the guarded transitions that allows the event is taken
+        let elapsed = self
+            .extensions
+            .clock
+            .now()
+            .saturating_duration_since(self.extensions.entered_at);
+        let selected = found.select_mut(|guard| guard.allows(elapsed));
+
         let Some(Next {
             next,
             action,
+            before,
             is_final,
-        }) = self.transitions.get_mut(&event, state) else {
-            return Err(TransitionError::InvalidTransition);
+            limit,
+            ignored,
+            ..
+        }) = selected
+        else {
+            return Err(TransitionError::GuardRejected);
         };
 
+        // The event is consumed without running any action nor hook
+        if *ignored {
+            self.extensions.dedupe.handled(event);
+            return Ok(state.clone());
+        }
+
+        let now = limit
+            .as_ref()
+            .and_then(|limit| limit.cooldown)
+            .map(|_| self.extensions.clock.now());
+
+        if let Some(limit) = limit.as_ref() {
+            limit.check(now)?;
+        }
+
+        // The context is copied before the action can change it
+        let snapshot = self
+            .extensions
+            .undo
+            .as_ref()
+            .map(|history| history.copy(context));
+        if let Some(audit) = self.extensions.audit.as_mut() {
+            audit.copy(context);
+        }
+
+        let abort = Cell::new(None);
+        let tagged = outcome_of(&self.extensions.outcomes, next).is_some();
+        let finality = Cell::new(*is_final || tagged);
+        // Whether the transition was recorded, so it must be revoked if rolled back
+        let mut recorded = false;
+        // The `before` action runs while the machine is still in the previous state
+        let result = invoke(self.extensions.catch_panics, || {
+            if let Some(journal) = self.extensions.journal.as_mut() {
+                journal.record(
+                    JournalOrder::BeforeActions,
+                    JournalEntry {
+                        from: state,
+                        to: next,
+                        event,
+                    },
+                )?;
+
+                recorded = journal.order == JournalOrder::BeforeActions;
+            }
+
+            if let Some(f) = before.as_mut() {
+                let state_data = &mut self.extensions.state_data;
+                f.call(ContextMut {
+                    from: state,
+                    to: next,
+                    event,
+                    context: &mut *context,
+                    state_data: (!state_data.is_empty()).then_some(state_data),
+                    abort: &abort,
+                    is_final: &finality,
+                    sender: (!C::LENT).then_some(&self.extensions.sender),
+                });
+            }
+
+            Ok(())
+        });
+
+        let journal = &mut self.extensions.journal;
+        let entry = JournalEntry {
+            from: state,
+            to: next,
+            event,
+        };
+
+        match result {
+            Err(message) => {
+                self.extensions.poisoned = true;
+                let error = TransitionError::ActionPanicked(message);
+                return Err(rolled_back(journal, recorded, entry, error));
+            }
+            Ok(Err(reason)) => return Err(TransitionError::Journal(reason)),
+            Ok(Ok(())) => {}
+        }
+
+        if let Some(abort) = abort.get() {
+            return Err(rolled_back(journal, recorded, entry, abort.into()));
+        }
+
         // Set the new state
         // TODO: Check if this `Clone` can be removed
         let prev_state = std::mem::replace(state, next.clone());
 
-        if *is_final {
-            self.done = true;
-        }
+        let result = invoke(self.extensions.catch_panics, || {
+            // Call the action of the transition if any
+            if let Some(f) = action.as_mut() {
+                let state_data = &mut self.extensions.state_data;
+                f.call(ContextMut {
+                    from: &prev_state,
+                    to: next,
+                    event,
+                    context: &mut *context,
+                    state_data: (!state_data.is_empty()).then_some(state_data),
+                    abort: &abort,
+                    is_final: &finality,
+                    sender: (!C::LENT).then_some(&self.extensions.sender),
+                });
+            }
 
-        // Call the action of the transition if any
-        if let Some(f) = action.as_mut() {
-            f.call(ContextMut {
-                from: state,
+            if abort.get().is_some() {
+                return Ok(());
+            }
+
+            if let Some(journal) = self.extensions.journal.as_mut() {
+                journal.record(
+                    JournalOrder::AfterActions,
+                    JournalEntry {
+                        from: &prev_state,
+                        to: next,
+                        event,
+                    },
+                )?;
+
+                recorded = true;
+            }
+
+            // After the transition is done, call the `on_transition`
+            let cx = Context {
+                from: &prev_state,
                 to: next,
-                event: &event,
-                context: &mut self.context,
-            });
+                event,
+                context: &*context,
+            };
+
+            if let Some(f) = self.on_transition.as_mut() {
+                f.call(cx);
+            } else if let Some(f) = self.extensions.on_transition.as_mut() {
+                f(cx);
+            }
+
+            if let Some(audit) = self.extensions.audit.as_mut() {
+                audit.report(&prev_state, next, event, &*context);
+            }
+
+            // The machine rejects the events once it is done, so this only runs once
+            if let (true, Some(f)) = (finality.get(), self.extensions.on_done.as_mut()) {
+                f(next, &mut *context);
+            }
+
+            Ok(())
+        });
+
+        let journal = &mut self.extensions.journal;
+        let result = match result {
+            // If a callback panicked, the machine goes back to the previous state
+            Err(message) => {
+                *state = prev_state;
+                self.extensions.poisoned = true;
+                let entry = JournalEntry {
+                    from: state,
+                    to: next,
+                    event,
+                };
+                let error = TransitionError::ActionPanicked(message);
+                return Err(rolled_back(journal, recorded, entry, error));
+            }
+            Ok(result) => result,
+        };
+
+        // If the transition could not be recorded, the machine stays in the previous state
+        if let Err(reason) = result {
+            *state = prev_state;
+            return Err(TransitionError::Journal(reason));
         }
 
-        // After the transition is done, call the `on_transition`
-        if let Some(f) = self.on_transition.as_mut() {
-            f.call(Context {
+        // If the action cancelled the transition, the machine stays in the previous state
+        if let Some(abort) = abort.get() {
+            *state = prev_state;
+            let entry = JournalEntry {
                 from: state,
                 to: next,
-                event: &event,
-                context: &self.context,
+                event,
+            };
+            return Err(rolled_back(journal, recorded, entry, abort.into()));
+        }
+
+        if let Some(limit) = limit.as_mut() {
+            limit.fire(now);
+        }
+
+        // The action may have changed whether the transition is final
+        if finality.get() {
+            self.done = true;
+            self.extensions.finished = Some(FinishInfo {
+                from: prev_state.clone(),
+                to: next.clone(),
+                event: None,
             });
         }
 
+        if let (Some(history), Some(context)) = (self.extensions.undo.as_mut(), snapshot) {
+            history.push(prev_state.clone(), context);
+        }
+
+        if prev_state != *next {
+            self.extensions.state_data.exit(&prev_state);
+            self.extensions.enter();
+        }
+
+        self.extensions.dedupe.handled(event);
         Ok(prev_state)
     }
 }
 
+// Revokes the record of a transition rolled back after it was recorded and returns the error of the transition,
+// if revoking fails the journal error is returned unless a callback panicked.
+fn rolled_back<S, E>(
+    journal: &mut Option<Journal<S, E>>,
+    recorded: bool,
+    entry: JournalEntry<S, E>,
+    error: TransitionError,
+) -> TransitionError {
+    let Some(journal) = journal.as_mut().filter(|_| recorded) else {
+        return error;
+    };
+
+    match journal.revoke(entry) {
+        Err(reason) if !matches!(error, TransitionError::ActionPanicked(_)) => {
+            TransitionError::Journal(reason)
+        }
+        _ => error,
+    }
+}
+
+// Splits a transition into the event, the source state and the entry of the transition map.
+pub(crate) fn split<'a, S, E, Ctx, A: ?Sized>(
+    transition: impl IntoTransition<'a, S, E, Ctx, A>,
+) -> (E, S, Next<S, A>) {
+    let Transition {
+        from,
+        to,
+        event,
+        action,
+        before,
+        is_final,
+        guard,
+        limit,
+        priority,
+        label,
+        ..
+    } = transition.into_transition();
+
+    let next = Next {
+        next: to,
+        action,
+        before,
+        is_final,
+        guard,
+        limit,
+        priority,
+        label: label.map(String::into_boxed_str),
+        candidates: Vec::new(),
+        ignored: false,
+        scope: None,
+        #[cfg(feature = "rand")]
+        weight: 1,
+    };
+
+    (event, from, next)
+}
+
+// Returns the transitions for the event from the state,
+// an exact match takes precedence over a match by variant.
+pub(crate) fn find_next<'m, S, E, A: ?Sized>(
+    map: &'m mut TransitionMap<S, E, Next<S, A>>,
+    match_by_discriminant: bool,
+    node_hint: &mut Option<usize>,
+    event: &E,
+    state: &S,
+) -> Option<&'m mut Next<S, A>>
+where
+    S: PartialEq,
+    E: PartialEq,
+{
+    if match_by_discriminant && map.get(event, state).is_none() {
+        let variant = std::mem::discriminant(state);
+        map.get_mut_by(event, |s| std::mem::discriminant(s) == variant)
+    } else {
+        map.get_mut_hinted(event, state, node_hint)
+    }
+}
+
+// Adds the transition to the map, or as a candidate if the transitions for the event from the state are guarded.
+// Returns the transition back if it cannot be added.
+pub(crate) fn insert_next<S, E, A: ?Sized>(
+    map: &mut TransitionMap<S, E, Next<S, A>>,
+    event: E,
+    from: S,
+    next: Next<S, A>,
+) -> Result<(), Box<Next<S, A>>>
+where
+    S: PartialEq,
+    E: PartialEq,
+{
+    match map.entry(event, from) {
+        Entry::Vacant(entry) => {
+            entry.insert(next);
+            Ok(())
+        }
+        Entry::Occupied(mut entry) if next.guard.is_some() && entry.get().guard.is_some() => {
+            entry.get_mut().add_candidate(next);
+            Ok(())
+        }
+        Entry::Occupied(_) => Err(Box::new(next)),
+    }
+}
+
+// Calls the `on_unhandled` callback if any, and poisons the machine if the callback panics.
+fn unhandled<S, E, Ctx>(
+    extensions: &mut Extensions<'_, S, E, Ctx>,
+    state: &S,
+    event: &E,
+    context: &mut Ctx,
+    is_done: bool,
+) -> Result<(), TransitionError> {
+    let Some(f) = extensions.on_unhandled.as_mut() else {
+        return Ok(());
+    };
+
+    invoke(extensions.catch_panics, || {
+        f(UnhandledContext {
+            state,
+            event,
+            context,
+            is_done,
+        })
+    })
+    .map_err(|message| {
+        extensions.poisoned = true;
+        TransitionError::ActionPanicked(message)
+    })
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: PartialEq,
+{
+    /// Returns a graph where the nodes are the states of this machine and the edges its transitions.
+    pub fn to_graph(&self) -> Graph<S, E>
+    where
+        S: Clone,
+        E: Clone,
+    {
+        self.graph_ref().map(|s| (*s).clone(), |e| (*e).clone())
+    }
+
+    /// Returns a PlantUML state diagram of this machine, using the `Debug` representation of the states and events.
+    ///
+    /// If the machine had started, the current state is marked with the `<<current>>` stereotype.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// enum Light {
+    ///     Off,
+    ///     On,
+    /// }
+    ///
+    /// #[derive(Debug, PartialEq)]
+    /// struct Toggle;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new(Light::Off).on(Toggle).go_to(Light::On).is_final())
+    ///     .start(Light::Off);
+    ///
+    /// assert_eq!(
+    ///     sm.to_plantuml(),
+    ///     r#"@startuml
+    /// skinparam state {
+    ///   BackgroundColor<<current>> LightBlue
+    /// }
+    /// state "Off" as s0 <<current>>
+    /// state "On" as s1
+    /// [*] --> s0
+    /// s0 --> s1 : Toggle
+    /// s1 --> [*]
+    /// @enduml
+    /// "#
+    /// );
+    /// ```
+    pub fn to_plantuml(&self) -> String
+    where
+        S: Debug,
+        E: Debug,
+    {
+        self.to_plantuml_with(|s| format!("{s:?}"))
+    }
+
+    /// Returns a PlantUML state diagram of this machine, using the given function to label the states.
+    pub fn to_plantuml_with(&self, labeler: impl Fn(&S) -> String) -> String
+    where
+        E: Debug,
+    {
+        let graph = self.graph_ref();
+        let current = self.current.as_ref();
+        plantuml::render(
+            &graph,
+            current.as_ref(),
+            |s| labeler(s),
+            |e| format!("{e:?}"),
+        )
+    }
+
+    /// Returns a SCXML document of this machine, using `Debug` to name the states and events.
+    ///
+    /// The current state, if the machine is started, is used as the initial state.
+    #[cfg(feature = "scxml")]
+    pub fn to_scxml(&self) -> String
+    where
+        S: Debug,
+        E: Debug,
+    {
+        self.to_scxml_with(|s| format!("{s:?}"), |e| format!("{e:?}"))
+    }
+
+    /// Returns a SCXML document of this machine, using the given functions to name the states and events.
+    #[cfg(feature = "scxml")]
+    pub fn to_scxml_with(
+        &self,
+        state_label: impl Fn(&S) -> String,
+        event_label: impl Fn(&E) -> String,
+    ) -> String {
+        let graph = self.graph_ref();
+        let current = self.current.as_ref();
+        crate::export::scxml::render(
+            &graph,
+            current.as_ref(),
+            |s| state_label(s),
+            |e| event_label(e),
+        )
+    }
+
+    /// Returns a XState machine config in JSON with the name of the machine as id, or `machine` if it has no name,
+    /// using `Debug` to name the states and events.
+    ///
+    /// The current state, if the machine is started, is used as the initial state.
+    #[cfg(feature = "xstate")]
+    pub fn to_xstate_json(&self) -> String
+    where
+        S: Debug,
+        E: Debug,
+    {
+        let id = self.extensions.name.as_deref().unwrap_or("machine");
+        self.to_xstate_json_with(id, |s| format!("{s:?}"), |e| format!("{e:?}"))
+    }
+
+    /// Returns a XState machine config in JSON with the given id,
+    /// using the given functions to name the states and events.
+    #[cfg(feature = "xstate")]
+    pub fn to_xstate_json_with(
+        &self,
+        id: &str,
+        state_label: impl Fn(&S) -> String,
+        event_label: impl Fn(&E) -> String,
+    ) -> String {
+        let graph = self.graph_ref();
+        let current = self.current.as_ref();
+        crate::export::xstate::render(
+            &graph,
+            id,
+            current.as_ref(),
+            |s| state_label(s),
+            |e| event_label(e),
+        )
+    }
+
+    // Returns the graph of this machine without cloning the states and events.
+    fn graph_ref(&self) -> Graph<&S, &E> {
+        let mut graph = Graph::new();
+
+        if let Some(name) = &self.extensions.name {
+            graph.set_name(name.clone());
+        }
+
+        for (from, event, first) in self.transitions.iter() {
+            for next in first.candidates() {
+                let from = graph.get_or_add_node(&from);
+                let to = graph.get_or_add_node(&&next.next);
+                let edge = Edge {
+                    event,
+                    is_final: next.is_final,
+                    is_ignored: next.ignored,
+                    label: next.label.as_deref().map(String::from),
+                };
+
+                graph.add_edge(from, to, edge);
+            }
+        }
+
+        graph
+    }
+}
+
 impl<S, E> Default for Machine<'_, S, E, (), (), Build> {
     fn default() -> Self {
         Self::new()
@@ -291,11 +1312,54 @@ pub(crate) mod private {
     #[doc(hidden)]
     #[derive(Debug, Clone)]
     pub struct Ready;
+
+    // The context stored by a machine which context is lent on each `send_with`.
+    #[doc(hidden)]
+    pub struct Lent<Ctx>(pub(crate) std::marker::PhantomData<fn() -> Ctx>);
+
+    impl<Ctx> std::fmt::Debug for Lent<Ctx> {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("Lent")
+        }
+    }
+
+    // The context stored by a machine, the context itself or `Lent`.
+    #[doc(hidden)]
+    pub trait ContextSlot<Ctx> {
+        // Whether the context is lent, the queue of a lent machine is never processed
+        // so its actions get no `EventSender`.
+        const LENT: bool;
+
+        // Returns the context stored in the machine, or `None` if it is lent.
+        fn stored(&mut self) -> Option<&mut Ctx>;
+    }
+
+    impl<Ctx> ContextSlot<Ctx> for Ctx {
+        const LENT: bool = false;
+
+        fn stored(&mut self) -> Option<&mut Ctx> {
+            Some(self)
+        }
+    }
+
+    impl<Ctx> ContextSlot<Ctx> for Lent<Ctx> {
+        const LENT: bool = true;
+
+        fn stored(&mut self) -> Option<&mut Ctx> {
+            None
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::blocking::{
+        Build, Builder, ContextMut, LocalBuilder, Machine, OwnedMachine, Ready, SyncBuilder,
+        SyncMachine,
+    };
+    use crate::error::TransitionError;
+    use std::cell::RefCell;
+    use std::sync::{Arc, Mutex};
 
     #[test]
     fn send_test() {
@@ -346,6 +1410,30 @@ mod tests {
         assert_eq!(value, 2);
     }
 
+    #[test]
+    fn set_on_transition_test() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let entries = log.clone();
+
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition(0, 'a'))
+            .on_transition(move |_| entries.lock().unwrap().push("builder"))
+            .start(0);
+
+        sm.send('a').unwrap();
+
+        // Replaces the hook set before the machine started
+        let entries = log.clone();
+        sm.set_on_transition(move |_| entries.lock().unwrap().push("replaced"));
+        sm.send('a').unwrap();
+
+        let entries = log.clone();
+        sm.set_on_transition(move |_| entries.lock().unwrap().push("again"));
+        sm.send('a').unwrap();
+
+        assert_eq!(*log.lock().unwrap(), ["builder", "replaced", "again"]);
+    }
+
     #[test]
     fn on_action_test() {
         let mut value = 0;
@@ -366,6 +1454,198 @@ mod tests {
         assert_eq!(value, 2);
     }
 
+    #[test]
+    fn on_next_replace_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(
+                Builder::new(1)
+                    .on('a')
+                    .go_to(2)
+                    .action(|cx: ContextMut<_, _, Vec<&str>>| cx.context.push("old")),
+            )
+            .on_next_replace(
+                Builder::new(1)
+                    .on('a')
+                    .go_to(3)
+                    .action(|cx: ContextMut<_, _, Vec<&str>>| cx.context.push("new")),
+            )
+            .start(1);
+
+        sm.send('a').unwrap();
+        assert_eq!(*sm.current(), 3);
+        assert_eq!(sm.context(), &["new"]);
+    }
+
+    #[test]
+    fn into_builder_test() {
+        fn fixture() -> OwnedMachine<u8, char, Vec<u8>, (), Ready> {
+            Machine::with_context(Vec::new())
+                .on_next(
+                    Builder::new(1)
+                        .on('a')
+                        .go_to(2)
+                        .action(|cx: ContextMut<_, _, Vec<u8>>| cx.context.push(*cx.to)),
+                )
+                .on_next(Builder::new(2).on('b').go_to(3).is_final())
+                .start(1)
+        }
+
+        let mut sm = fixture();
+        sm.post('z').unwrap();
+        sm.send('a').unwrap();
+        sm.send('b').unwrap();
+        assert!(sm.is_done());
+
+        let mut sm = sm
+            .into_builder()
+            .on_next_replace(Builder::new(2).on('b').go_to(1))
+            .on_next(Builder::new(1).on('c').go_to(3).is_final())
+            .start(1);
+
+        assert!(!sm.is_done());
+        assert_eq!(sm.pending(), 0);
+        assert_eq!(sm.send('a'), Ok(1));
+        assert_eq!(sm.send('b'), Ok(2));
+        assert_eq!(sm.send('c'), Ok(1));
+        assert!(sm.is_done());
+        assert_eq!(*sm.context(), [2, 2]);
+    }
+
+    #[test]
+    fn cancel_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(Builder::new(1).on('a').go_to(2).is_final().action(
+                |cx: ContextMut<_, _, i32>| {
+                    *cx.context += 1;
+
+                    // Only reserves every other attempt
+                    if *cx.context % 2 == 1 {
+                        cx.cancel();
+                    }
+                },
+            ))
+            .on_transition(|cx| assert!(*cx.context % 2 == 0))
+            .start(1);
+
+        assert_eq!(sm.send('a'), Err(TransitionError::Cancelled));
+        assert_eq!(*sm.current(), 1);
+        assert!(!sm.is_done());
+        assert_eq!(*sm.context(), 1);
+
+        assert_eq!(sm.send('a'), Ok(1));
+        assert_eq!(*sm.current(), 2);
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn before_after_order_test() {
+        type Log = RefCell<Vec<String>>;
+
+        let mut sm = Machine::with_context(Log::default())
+            .on_next(
+                Builder::new('a')
+                    .on(0)
+                    .go_to('b')
+                    .before(|cx: ContextMut<char, u8, Log>| {
+                        let entry = format!("before {}->{}", cx.from, cx.to);
+                        cx.context.borrow_mut().push(entry);
+                    })
+                    .after(|cx: ContextMut<char, u8, Log>| {
+                        let entry = format!("after {}->{}", cx.from, cx.to);
+                        cx.context.borrow_mut().push(entry);
+                    }),
+            )
+            .on_next(
+                Builder::new('b')
+                    .on(1)
+                    .go_to('c')
+                    .before(|cx: ContextMut<char, u8, Log>| cx.cancel())
+                    .after(|cx: ContextMut<char, u8, Log>| {
+                        cx.context.borrow_mut().push("unreachable".to_owned());
+                    }),
+            )
+            .on_transition(|cx| {
+                let entry = format!("on_transition {}->{}", cx.from, cx.to);
+                cx.context.borrow_mut().push(entry);
+            })
+            .start('a');
+
+        sm.send(0).unwrap();
+        assert_eq!(
+            *sm.context().borrow(),
+            ["before a->b", "after a->b", "on_transition a->b"]
+        );
+
+        // A cancelled `before` aborts the transition before leaving the state
+        assert_eq!(sm.send(1), Err(TransitionError::Cancelled));
+        assert_eq!(*sm.current(), 'b');
+        assert_eq!(sm.context().borrow().len(), 3);
+    }
+
+    #[test]
+    fn set_final_test() {
+        struct Upload {
+            remaining: usize,
+        }
+
+        #[derive(Debug, Clone, PartialEq)]
+        enum State {
+            Uploading,
+        }
+
+        let mut sm = Machine::with_context(Upload { remaining: 3 })
+            .on_next(Builder::self_transition(State::Uploading, "chunk").action(
+                |cx: ContextMut<_, _, Upload>| {
+                    assert!(!cx.is_final());
+                    cx.context.remaining -= 1;
+                    cx.set_final(cx.context.remaining == 0);
+                },
+            ))
+            .start(State::Uploading);
+
+        sm.send("chunk").unwrap();
+        sm.send("chunk").unwrap();
+        assert!(!sm.is_done());
+
+        sm.send("chunk").unwrap();
+        assert!(sm.is_done());
+        assert_eq!(sm.send("chunk"), Err(TransitionError::Done));
+    }
+
+    #[test]
+    fn set_final_only_current_test() {
+        let mut sm = Machine::with_context(true)
+            .on_next(Builder::self_transition(0, 'a').is_final().action(
+                |cx: ContextMut<_, _, bool>| {
+                    assert!(cx.is_final());
+
+                    // Only the first dispatch is not final
+                    cx.set_final(!*cx.context);
+                    *cx.context = false;
+                },
+            ))
+            .start(0);
+
+        sm.send('a').unwrap();
+        assert!(!sm.is_done());
+        sm.send('a').unwrap();
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn on_next_if_absent_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new(1).on('a').go_to(2))
+            .on_next_if_absent(Builder::new(1).on('a').go_to(3))
+            .on_next_if_absent(Builder::new(2).on('a').go_to(3))
+            .start(1);
+
+        sm.send('a').unwrap();
+        assert_eq!(*sm.current(), 2);
+        sm.send('a').unwrap();
+        assert_eq!(*sm.current(), 3);
+    }
+
     #[test]
     fn with_context_test() {
         #[derive(Debug, Clone, PartialEq, Eq)]
@@ -405,4 +1685,134 @@ mod tests {
 
         assert_eq!(*sm.context(), 1);
     }
+
+    #[test]
+    fn new_owned_test() {
+        fn create() -> OwnedMachine<i32, (), (), (), Ready> {
+            Machine::new_owned()
+                .on_next(Builder::new(0).on(()).go_to(1).is_final())
+                .start(0)
+        }
+
+        let mut sm = std::thread::spawn(create).join().unwrap();
+        assert_eq!(sm.send(()).unwrap(), 0);
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn local_machine_test() {
+        use std::{cell::RefCell, rc::Rc};
+
+        let value = Rc::new(RefCell::new(0));
+        let value_clone = value.clone();
+
+        let mut sm = Machine::new_local()
+            .on_next(
+                LocalBuilder::self_transition((), ()).action(move |_: ContextMut<_, _, _>| {
+                    *value_clone.borrow_mut() += 1;
+                }),
+            )
+            .start(());
+
+        sm.send(()).unwrap();
+        sm.send(()).unwrap();
+
+        assert_eq!(*value.borrow(), 2);
+    }
+
+    #[test]
+    fn machine_is_send_test() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let sm = Machine::with_context(0)
+            .on_next(
+                Builder::self_transition((), ()).action(|cx: ContextMut<(), (), i32>| {
+                    *cx.context += 1;
+                }),
+            )
+            .start(());
+
+        assert_send(&sm);
+    }
+
+    // The auto traits are checked at compile time.
+    const fn assert_send<T: Send>() {}
+    const fn assert_sync<T: Sync>() {}
+
+    const _: () = {
+        assert_send::<Machine<'static, String, u8, Vec<u8>, (), Build>>();
+        assert_send::<Machine<'static, String, u8, Vec<u8>, (), Ready>>();
+        assert_send::<SyncMachine<'static, String, u8, Vec<u8>, (), Ready>>();
+        assert_sync::<SyncMachine<'static, String, u8, Vec<u8>, (), Build>>();
+        assert_sync::<SyncMachine<'static, String, u8, Vec<u8>, (), Ready>>();
+    };
+
+    #[test]
+    fn sync_machine_test() {
+        let sm = Machine::with_context_sync(0)
+            .on_next(
+                SyncBuilder::self_transition((), ()).action(|cx: ContextMut<(), (), i32>| {
+                    *cx.context += 1;
+                }),
+            )
+            .on_unhandled(|cx| *cx.context -= 1)
+            .start(());
+
+        let sm = std::sync::RwLock::new(sm);
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| sm.write().unwrap().send(()).unwrap());
+                scope.spawn(|| sm.read().unwrap().is_done());
+            }
+        });
+
+        assert_eq!(*sm.read().unwrap().context(), 4);
+    }
+
+    #[test]
+    fn with_capacity_test() {
+        let mut sm = Machine::with_capacity(2, 1)
+            .on_next(Builder::new(0).on(()).go_to(1))
+            .on_next(Builder::new(1).on(()).go_to(0))
+            .start(0);
+
+        assert_eq!(sm.states().count(), 2);
+        assert_eq!(sm.send(()).unwrap(), 0);
+        assert_eq!(sm.send(()).unwrap(), 1);
+        assert_eq!(*sm.current(), 0);
+    }
+
+    // An event that cannot be cloned
+    #[derive(Debug, PartialEq, Eq)]
+    struct Tick(u32);
+
+    #[test]
+    fn send_ref_fan_out_test() {
+        let build = |to: &'static str| {
+            Machine::with_context(0)
+                .on_next(Builder::new("idle").on(Tick(1)).go_to(to).action(
+                    |cx: ContextMut<&str, Tick, u32>| {
+                        *cx.context += cx.event.0;
+                    },
+                ))
+                .start("idle")
+        };
+
+        let mut machines = [build("a"), build("b"), build("c")];
+        let event = Tick(1);
+
+        for sm in machines.iter_mut() {
+            assert_eq!(sm.send_ref(&event), Ok("idle"));
+        }
+
+        let states = machines.iter().map(|sm| *sm.current()).collect::<Vec<_>>();
+        assert_eq!(states, ["a", "b", "c"]);
+        assert!(machines.iter().all(|sm| *sm.context() == 1));
+
+        assert_eq!(
+            machines[0].send_ref(&Tick(2)),
+            Err(TransitionError::InvalidTransition)
+        );
+        assert_eq!(event, Tick(1));
+    }
 }
@@ -0,0 +1,258 @@
+use super::{Machine, OnAction, OnTransition, Ready, SendAction};
+use crate::error::{SendIntoError, TransitionError};
+use std::marker::PhantomData;
+
+/// A machine that receives events of other type, created with `Machine::map_events`.
+pub struct MappedMachine<'a, S, E, Ctx, F, E2, M, A: ?Sized = SendAction<'a, S, E, Ctx>> {
+    machine: Machine<'a, S, E, Ctx, F, Ready, A>,
+    map: M,
+    _marker: PhantomData<fn(E2)>,
+}
+
+impl<'a, S, E, Ctx, F, A> Machine<'a, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Triggers a transition with a value that can be converted into an event.
+    pub fn send_into<T: Into<E>>(&mut self, value: T) -> Result<S, TransitionError> {
+        self.send(value.into())
+    }
+
+    /// Triggers a transition with the event converted from the given value,
+    /// if the conversion fails the machine is left unchanged.
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(SendIntoError::Conversion): If the value could not be converted into an event.
+    /// - Err(SendIntoError::Transition): If the transition was not successful
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::SendIntoError;
+    ///
+    /// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    /// enum LightEvent {
+    ///     TurnOn,
+    ///     TurnOff,
+    /// }
+    ///
+    /// impl TryFrom<&str> for LightEvent {
+    ///     type Error = String;
+    ///
+    ///     fn try_from(value: &str) -> Result<Self, Self::Error> {
+    ///         match value {
+    ///             "turn_on" => Ok(LightEvent::TurnOn),
+    ///             "turn_off" => Ok(LightEvent::TurnOff),
+    ///             _ => Err(format!("unknown command `{value}`")),
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("off").on(LightEvent::TurnOn).go_to("on"))
+    ///     .on_next(Builder::new("on").on(LightEvent::TurnOff).go_to("off"))
+    ///     .start("off");
+    ///
+    /// assert_eq!(sm.try_send_into("turn_on"), Ok("off"));
+    /// assert_eq!(
+    ///     sm.try_send_into("blink"),
+    ///     Err(SendIntoError::Conversion(String::from("unknown command `blink`")))
+    /// );
+    /// assert_eq!(*sm.current(), "on");
+    /// ```
+    pub fn try_send_into<T: TryInto<E>>(&mut self, value: T) -> Result<S, SendIntoError<T::Error>> {
+        let event = value.try_into().map_err(SendIntoError::Conversion)?;
+        Ok(self.send(event)?)
+    }
+
+    /// Returns a machine that receives events of other type, converting them with the given function.
+    ///
+    /// If the function returns `None`, `send` fails with `TransitionError::Unmapped`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::TransitionError;
+    ///
+    /// #[derive(PartialEq)]
+    /// enum Event {
+    ///     Ping,
+    /// }
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::self_transition((), Event::Ping))
+    ///     .start(())
+    ///     .map_events(|raw: &str| match raw {
+    ///         "ping" => Some(Event::Ping),
+    ///         _ => None,
+    ///     });
+    ///
+    /// assert!(sm.send("ping").is_ok());
+    /// assert_eq!(sm.send("pong"), Err(TransitionError::Unmapped));
+    /// ```
+    pub fn map_events<E2, M>(self, map: M) -> MappedMachine<'a, S, E, Ctx, F, E2, M, A>
+    where
+        M: FnMut(E2) -> Option<E>,
+    {
+        MappedMachine {
+            machine: self,
+            map,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, F, E2, M, A: ?Sized> MappedMachine<'a, S, E, Ctx, F, E2, M, A> {
+    /// Returns the current state.
+    pub fn current(&self) -> &S {
+        self.machine.current.as_ref().unwrap()
+    }
+
+    /// Returns the context used for this state machine.
+    pub fn context(&self) -> &Ctx {
+        &self.machine.context
+    }
+
+    /// Returns `true` if this state machine had done executing.
+    pub fn is_done(&self) -> bool {
+        self.machine.done
+    }
+
+    /// Returns the wrapped machine.
+    pub fn inner(&self) -> &Machine<'a, S, E, Ctx, F, Ready, A> {
+        &self.machine
+    }
+
+    /// Returns the wrapped machine, discarding the mapping function.
+    pub fn into_inner(self) -> Machine<'a, S, E, Ctx, F, Ready, A> {
+        self.machine
+    }
+}
+
+impl<S, E, Ctx, F, E2, M, A> MappedMachine<'_, S, E, Ctx, F, E2, M, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    M: FnMut(E2) -> Option<E>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Converts the event and triggers a transition.
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(TransitionError): If the event cannot be mapped or the transition was not successful.
+    pub fn send(&mut self, event: E2) -> Result<S, TransitionError> {
+        let event = (self.map)(event).ok_or(TransitionError::Unmapped)?;
+        self.machine.send(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::{SendIntoError, TransitionError};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Light {
+        On,
+        Off,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        TurnOn,
+        TurnOff,
+    }
+
+    impl From<bool> for Event {
+        fn from(value: bool) -> Self {
+            match value {
+                true => Event::TurnOn,
+                false => Event::TurnOff,
+            }
+        }
+    }
+
+    impl TryFrom<&str> for Event {
+        type Error = String;
+
+        fn try_from(value: &str) -> Result<Self, Self::Error> {
+            match value {
+                "on" => Ok(Event::TurnOn),
+                "off" => Ok(Event::TurnOff),
+                _ => Err(value.to_owned()),
+            }
+        }
+    }
+
+    fn light() -> Machine<'static, Light, Event, i32, ()> {
+        Machine::with_context(0)
+            .on_next(
+                Builder::new(Light::Off)
+                    .on(Event::TurnOn)
+                    .go_to(Light::On)
+                    .action(|cx: ContextMut<Light, Event, i32>| {
+                        *cx.context += 1;
+                    }),
+            )
+            .on_next(Builder::new(Light::On).on(Event::TurnOff).go_to(Light::Off))
+    }
+
+    #[test]
+    fn send_into_test() {
+        let mut sm = light().start(Light::Off);
+
+        assert_eq!(sm.send_into(true).unwrap(), Light::Off);
+        assert_eq!(sm.send_into(false).unwrap(), Light::On);
+        assert_eq!(sm.send_into(false), Err(TransitionError::InvalidTransition));
+    }
+
+    #[test]
+    fn try_send_into_test() {
+        let mut sm = light()
+            .on_unhandled(|cx| *cx.context -= 1)
+            .start(Light::Off);
+
+        assert_eq!(sm.try_send_into("on").unwrap(), Light::Off);
+        assert_eq!(
+            sm.try_send_into("on"),
+            Err(SendIntoError::Transition(
+                TransitionError::InvalidTransition
+            ))
+        );
+        assert_eq!(*sm.context(), 0);
+
+        // A failed conversion doesn't reach the machine
+        assert_eq!(
+            sm.try_send_into("blink"),
+            Err(SendIntoError::Conversion(String::from("blink")))
+        );
+        assert_eq!(*sm.current(), Light::On);
+        assert_eq!(*sm.context(), 0);
+    }
+
+    #[test]
+    fn map_events_test() {
+        let mut sm = light().start(Light::Off).map_events(|byte: u8| match byte {
+            1 => Some(Event::TurnOn),
+            0 => Some(Event::TurnOff),
+            _ => None,
+        });
+
+        assert_eq!(sm.send(1).unwrap(), Light::Off);
+        assert_eq!(sm.send(7), Err(TransitionError::Unmapped));
+        assert_eq!(*sm.current(), Light::On);
+        assert_eq!(*sm.context(), 1);
+        assert!(!sm.is_done());
+
+        assert_eq!(sm.send(0).unwrap(), Light::On);
+        assert_eq!(*sm.into_inner().current(), Light::Off);
+    }
+}
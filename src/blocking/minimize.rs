@@ -0,0 +1,349 @@
+use super::{Build, Machine, Next};
+use crate::map::TransitionMap;
+
+/// The groups of behaviorally equivalent states found by `Machine::minimized`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinimizationReport<S> {
+    /// The groups of equivalent states, each state belongs to exactly one group.
+    pub groups: Vec<Vec<S>>,
+}
+
+impl<S> MinimizationReport<S> {
+    /// Returns the number of states of the minimized machine.
+    pub fn state_count(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Returns `true` if there is no states to merge.
+    pub fn is_minimal(&self) -> bool {
+        self.groups.iter().all(|g| g.len() == 1)
+    }
+
+    /// Returns an iterator over the groups with more than one state.
+    pub fn mergeable(&self) -> impl Iterator<Item = &[S]> {
+        self.groups
+            .iter()
+            .filter(|g| g.len() > 1)
+            .map(|g| g.as_slice())
+    }
+}
+
+// An outgoing transition of a state: the event, whether is final and the target state index.
+type Out<'s, E> = (&'s E, bool, usize);
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Build, A, C>
+where
+    S: PartialEq + Clone,
+    E: PartialEq,
+{
+    /// Returns the groups of states that behave the same way, which can be merged into a single state.
+    ///
+    /// Two states are equivalent if they have the same events leading to equivalent states with the same finality.
+    /// The states with transitions that have actions are never merged,
+    /// and the targets of final transitions only merge with other targets of final transitions.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new("start").on('a').go_to("left"))
+    ///     .on_next(Builder::new("start").on('b').go_to("right"))
+    ///     .on_next(Builder::new("left").on('c').go_to("end"))
+    ///     .on_next(Builder::new("right").on('c').go_to("end"));
+    ///
+    /// let report = sm.minimized();
+    ///
+    /// assert_eq!(report.state_count(), 3);
+    /// assert_eq!(report.mergeable().collect::<Vec<_>>(), vec![&["left", "right"]]);
+    /// ```
+    pub fn minimized(&self) -> MinimizationReport<S> {
+        let mut states: Vec<&S> = Vec::new();
+        let mut outgoing: Vec<Vec<Out<E>>> = Vec::new();
+        let mut with_action = Vec::new();
+        let mut final_target = Vec::new();
+
+        for (from, event, next) in self.transitions.iter() {
+            let from = index_of(&mut states, from);
+            let to = index_of(&mut states, &next.next);
+
+            let len = states.len();
+            outgoing.resize_with(len, Vec::new);
+            with_action.resize(len, false);
+            final_target.resize(len, false);
+
+            outgoing[from].push((event, next.is_final, to));
+            // The states with guarded candidates or ignored events are not merged, like the ones with actions
+            with_action[from] |=
+                next.action.is_some() || !next.candidates.is_empty() || next.ignored;
+            final_target[to] |= next.is_final;
+        }
+
+        // The initial partition separates final targets, and each state with actions is on its own class
+        let mut classes = (0..states.len())
+            .map(|i| match with_action[i] {
+                true => 2 + i,
+                false => final_target[i] as usize,
+            })
+            .collect::<Vec<_>>();
+
+        let mut class_count = count_distinct(&classes);
+
+        loop {
+            let mut next_classes: Vec<usize> = Vec::with_capacity(states.len());
+
+            for i in 0..states.len() {
+                let equivalent = (0..i).find(|&j| {
+                    classes[i] == classes[j] && same_signature(&outgoing[i], &outgoing[j], &classes)
+                });
+
+                let class = match equivalent {
+                    Some(j) => next_classes[j],
+                    None => i,
+                };
+
+                next_classes.push(class);
+            }
+
+            let next_count = count_distinct(&next_classes);
+            classes = next_classes;
+
+            if next_count == class_count {
+                break;
+            }
+
+            class_count = next_count;
+        }
+
+        let mut groups: Vec<(usize, Vec<S>)> = Vec::new();
+
+        for (i, state) in states.into_iter().enumerate() {
+            match groups.iter_mut().find(|(class, _)| *class == classes[i]) {
+                Some((_, group)) => group.push(state.clone()),
+                None => groups.push((classes[i], vec![state.clone()])),
+            }
+        }
+
+        MinimizationReport {
+            groups: groups.into_iter().map(|(_, group)| group).collect(),
+        }
+    }
+
+    /// Returns a machine where each group of states is replaced by its first state.
+    ///
+    /// The transitions that become duplicated after replacing the states are discarded,
+    /// so only equivalent states, like the ones reported by `minimized`, should be merged.
+    pub fn merge_states(self, groups: &[Vec<S>]) -> Self {
+        let representative = |state: S| -> S {
+            groups
+                .iter()
+                .find(|group| group.contains(&state))
+                .and_then(|group| group.first().cloned())
+                .unwrap_or(state)
+        };
+
+        let mut transitions: TransitionMap<S, E, Next<S, A>> = TransitionMap::new();
+
+        for (from, event, next) in self.transitions {
+            let from = representative(from);
+
+            if transitions.get(&event, &from).is_some() {
+                continue;
+            }
+
+            let next = Next {
+                next: representative(next.next),
+                ..next
+            };
+
+            transitions.insert(event, from, next);
+        }
+
+        Machine {
+            transitions,
+            ..self
+        }
+    }
+}
+
+fn index_of<'s, S: PartialEq>(states: &mut Vec<&'s S>, state: &'s S) -> usize {
+    match states.iter().position(|s| *s == state) {
+        Some(index) => index,
+        None => {
+            states.push(state);
+            states.len() - 1
+        }
+    }
+}
+
+fn count_distinct(classes: &[usize]) -> usize {
+    let mut seen = Vec::new();
+
+    for class in classes {
+        if !seen.contains(class) {
+            seen.push(*class);
+        }
+    }
+
+    seen.len()
+}
+
+fn same_signature<E: PartialEq>(a: &[Out<E>], b: &[Out<E>], classes: &[usize]) -> bool {
+    // There is at most 1 transition per event, so the signatures are sets
+    a.len() == b.len()
+        && a.iter().all(|(event, is_final, to)| {
+            b.iter().any(|(other_event, other_final, other_to)| {
+                event == other_event
+                    && is_final == other_final
+                    && classes[*to] == classes[*other_to]
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Start,
+        PayCard,
+        PayCash,
+        ConfirmCard,
+        ConfirmCash,
+        Done,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Card,
+        Cash,
+        Next,
+        Confirm,
+        Cancel,
+    }
+
+    fn duplicated() -> Machine<'static, State, Event, (), ()> {
+        Machine::new()
+            .on_next(
+                Builder::new(State::Start)
+                    .on(Event::Card)
+                    .go_to(State::PayCard),
+            )
+            .on_next(
+                Builder::new(State::Start)
+                    .on(Event::Cash)
+                    .go_to(State::PayCash),
+            )
+            .on_next(
+                Builder::new(State::PayCard)
+                    .on(Event::Next)
+                    .go_to(State::ConfirmCard),
+            )
+            .on_next(
+                Builder::new(State::PayCash)
+                    .on(Event::Next)
+                    .go_to(State::ConfirmCash),
+            )
+            .on_next(
+                Builder::new(State::PayCard)
+                    .on(Event::Cancel)
+                    .go_to(State::Start),
+            )
+            .on_next(
+                Builder::new(State::PayCash)
+                    .on(Event::Cancel)
+                    .go_to(State::Start),
+            )
+            .on_next(
+                Builder::new(State::ConfirmCard)
+                    .on(Event::Confirm)
+                    .go_to(State::Done)
+                    .is_final(),
+            )
+            .on_next(
+                Builder::new(State::ConfirmCash)
+                    .on(Event::Confirm)
+                    .go_to(State::Done)
+                    .is_final(),
+            )
+    }
+
+    #[test]
+    fn minimized_test() {
+        let report = duplicated().minimized();
+
+        assert_eq!(report.state_count(), 4);
+        assert!(!report.is_minimal());
+        assert_eq!(
+            report.mergeable().collect::<Vec<_>>(),
+            vec![
+                &[State::PayCard, State::PayCash],
+                &[State::ConfirmCard, State::ConfirmCash],
+            ]
+        );
+    }
+
+    #[test]
+    fn merge_states_test() {
+        let sm = duplicated();
+        let report = sm.minimized();
+        let mut sm = sm.merge_states(&report.groups).start(State::Start);
+
+        assert_eq!(sm.states().count(), 3);
+
+        sm.send(Event::Cash).unwrap();
+        assert_eq!(*sm.current(), State::PayCard);
+        sm.send(Event::Next).unwrap();
+        assert_eq!(*sm.current(), State::ConfirmCard);
+        sm.send(Event::Confirm).unwrap();
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn final_targets_not_merged_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Start)
+                    .on(Event::Card)
+                    .go_to(State::PayCard)
+                    .is_final(),
+            )
+            .on_next(
+                Builder::new(State::Start)
+                    .on(Event::Cash)
+                    .go_to(State::PayCash),
+            );
+
+        assert!(sm.minimized().is_minimal());
+    }
+
+    #[test]
+    fn actions_not_merged_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Start)
+                    .on(Event::Card)
+                    .go_to(State::PayCard),
+            )
+            .on_next(
+                Builder::new(State::Start)
+                    .on(Event::Cash)
+                    .go_to(State::PayCash),
+            )
+            .on_next(
+                Builder::new(State::PayCard)
+                    .on(Event::Next)
+                    .go_to(State::Done)
+                    .action(|_: ContextMut<_, _, _>| {}),
+            )
+            .on_next(
+                Builder::new(State::PayCash)
+                    .on(Event::Next)
+                    .go_to(State::Done),
+            );
+
+        assert!(sm.minimized().is_minimal());
+    }
+}
@@ -4,6 +4,34 @@ pub use transition::*;
 mod machine;
 pub use machine::*;
 
+mod transition_ref;
+pub use transition_ref::*;
+
+mod step;
+pub use step::*;
+
+mod entry;
+pub use entry::*;
+
+mod ignore;
+
+mod scope;
+pub use scope::*;
+
+mod available;
+
+mod validate;
+pub use validate::*;
+
+mod audit;
+pub use audit::*;
+
+mod finish;
+pub use finish::*;
+
+mod outcome;
+pub use outcome::*;
+
 mod on_transition;
 pub use on_transition::*;
 
@@ -12,3 +40,114 @@ pub use on_action::*;
 
 mod context;
 pub use context::*;
+
+mod shared;
+pub use shared::*;
+
+mod simulation;
+pub use simulation::*;
+
+mod path;
+
+mod product;
+pub use product::*;
+
+mod minimize;
+pub use minimize::*;
+
+mod explore;
+pub use explore::*;
+
+mod diff;
+pub use diff::*;
+
+mod chain;
+pub use chain::*;
+
+mod table;
+pub use table::*;
+
+mod from_states;
+pub use from_states::*;
+
+mod mapped;
+pub use mapped::*;
+
+mod name;
+
+mod projection;
+
+mod lent;
+
+mod lifecycle;
+
+mod timer;
+
+mod unhandled;
+
+mod on_error;
+pub use on_error::*;
+
+mod queue;
+
+mod variant;
+
+mod dynamic;
+
+mod backend;
+pub use backend::*;
+
+mod dense;
+pub use dense::*;
+
+mod interned;
+pub use interned::*;
+
+mod state_data;
+pub use state_data::DataReset;
+
+mod feed;
+pub use feed::*;
+
+mod run;
+pub use run::*;
+
+mod panic;
+
+mod pause;
+
+mod dedupe;
+
+mod sender;
+pub use sender::*;
+
+mod definition;
+pub use definition::*;
+
+mod journal;
+pub use journal::*;
+
+#[cfg(feature = "journal")]
+mod file_journal;
+#[cfg(feature = "journal")]
+pub use file_journal::*;
+
+#[cfg(feature = "loader")]
+mod loader;
+#[cfg(feature = "loader")]
+pub use loader::*;
+
+mod time_guard;
+
+mod undo;
+
+mod limit;
+pub use limit::TransitionStatus;
+
+mod size_limits;
+pub use size_limits::*;
+
+#[cfg(feature = "rand")]
+mod weighted;
+
+mod extensions;
@@ -0,0 +1,95 @@
+use super::Machine;
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C> {
+    /// Sets a human readable name for this state machine, used in its `Debug` output,
+    /// as the title of the exported diagrams and in the panic messages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .named("worker")
+    ///     .start("idle");
+    ///
+    /// assert_eq!(sm.name(), Some("worker"));
+    /// assert!(format!("{sm:?}").contains("worker"));
+    /// ```
+    pub fn named(mut self, name: impl Into<String>) -> Self {
+        self.extensions.name = Some(name.into());
+        self
+    }
+
+    /// Returns the name of this state machine, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.extensions.name.as_deref()
+    }
+}
+
+// Returns the message of the panic for a transition that already exists.
+pub(crate) fn duplicate_message(
+    name: Option<&str>,
+    label: Option<&str>,
+    index: Option<usize>,
+) -> String {
+    let mut message = String::from("a transition already exists for the event");
+
+    if let Some(index) = index {
+        message.push_str(&format!(" from the source state at index {index}"));
+    }
+
+    if let Some(label) = label {
+        message.push_str(&format!(", the transition `{label}` collided"));
+    }
+
+    if let Some(name) = name {
+        message.push_str(&format!(" in the machine `{name}`"));
+    }
+
+    message
+}
+
+#[cfg(test)]
+mod tests {
+    use super::duplicate_message;
+    use crate::blocking::{Builder, Machine};
+
+    #[test]
+    fn duplicate_message_test() {
+        assert_eq!(
+            duplicate_message(None, None, None),
+            "a transition already exists for the event"
+        );
+        assert_eq!(
+            duplicate_message(Some("door"), Some("lock"), Some(1)),
+            "a transition already exists for the event from the source state at index 1, the transition `lock` collided in the machine `door`"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the transition `again` collided in the machine `door`")]
+    fn duplicate_labeled_test() {
+        let _ = Machine::new()
+            .named("door")
+            .on_next(Builder::new("open").on("close").go_to("closed"))
+            .on_next(
+                Builder::new("open")
+                    .on("close")
+                    .go_to("shut")
+                    .label("again"),
+            );
+    }
+
+    #[test]
+    fn name_test() {
+        let sm = Machine::<u8, u8, (), ()>::new();
+        assert_eq!(sm.name(), None);
+        assert!(!format!("{sm:?}").contains("name"));
+
+        let sm = sm.named("counter").start(0);
+        assert_eq!(sm.name(), Some("counter"));
+        assert!(format!("{sm:?}").starts_with("StateMachine { name: \"counter\""));
+    }
+}
@@ -1,4 +1,6 @@
+use super::context::Abort;
 use super::ContextMut;
+use std::sync::{Arc, Mutex};
 
 /// An action executed when state machine enters to a new state.
 pub trait OnAction<S, E, Ctx> {
@@ -18,3 +20,178 @@ where
 impl<S, E, Ctx> OnAction<S, E, Ctx> for () {
     fn call(&mut self, _: ContextMut<S, E, Ctx>) {}
 }
+
+/// The boxed action used by the default machine, it can be sent across threads.
+pub type SendAction<'a, S, E, Ctx> = dyn OnAction<S, E, Ctx> + Send + 'a;
+
+/// The boxed action used by a `LocalMachine`, it is not required to be `Send`.
+pub type LocalAction<'a, S, E, Ctx> = dyn OnAction<S, E, Ctx> + 'a;
+
+/// The boxed action used by a `SyncMachine`, it can be sent and shared across threads.
+pub type SyncAction<'a, S, E, Ctx> = dyn OnAction<S, E, Ctx> + Send + Sync + 'a;
+
+/// The boxed action used by a `StatelessMachine`, it cannot keep state between calls
+/// so one action can be shared by many machines.
+pub type StatelessAction<'a, S, E, Ctx> = dyn Fn(ContextMut<S, E, Ctx>) + Send + Sync + 'a;
+
+impl<S, E, Ctx> OnAction<S, E, Ctx> for StatelessAction<'_, S, E, Ctx> {
+    fn call(&mut self, cx: ContextMut<S, E, Ctx>) {
+        (self)(cx)
+    }
+}
+
+/// An action trait object that can be constructed from the action `F`.
+///
+/// This determine the bounds required to the actions of a transition,
+/// for example `SendAction` requires all the actions to be `Send`.
+pub trait BoxedAction<'a, F, S, E, Ctx>: OnAction<S, E, Ctx> {
+    /// Boxes the given action.
+    fn boxed(action: F) -> Box<Self>;
+}
+
+impl<'a, F, S, E, Ctx> BoxedAction<'a, F, S, E, Ctx> for SendAction<'a, S, E, Ctx>
+where
+    F: OnAction<S, E, Ctx> + Send + 'a,
+{
+    fn boxed(action: F) -> Box<Self> {
+        Box::new(action)
+    }
+}
+
+impl<'a, F, S, E, Ctx> BoxedAction<'a, F, S, E, Ctx> for LocalAction<'a, S, E, Ctx>
+where
+    F: OnAction<S, E, Ctx> + 'a,
+{
+    fn boxed(action: F) -> Box<Self> {
+        Box::new(action)
+    }
+}
+
+impl<'a, F, S, E, Ctx> BoxedAction<'a, F, S, E, Ctx> for SyncAction<'a, S, E, Ctx>
+where
+    F: OnAction<S, E, Ctx> + Send + Sync + 'a,
+{
+    fn boxed(action: F) -> Box<Self> {
+        Box::new(action)
+    }
+}
+
+impl<'a, F, S, E, Ctx> BoxedAction<'a, F, S, E, Ctx> for StatelessAction<'a, S, E, Ctx>
+where
+    F: Fn(ContextMut<S, E, Ctx>) + Send + Sync + 'a,
+{
+    fn boxed(action: F) -> Box<Self> {
+        Box::new(action)
+    }
+}
+
+/// An action shared by many transitions, cloning it returns a handle to the same action.
+pub struct SharedAction<F>(Arc<Mutex<F>>);
+
+impl<F> SharedAction<F> {
+    /// Wraps the given action to be shared.
+    pub fn new(action: F) -> Self {
+        SharedAction(Arc::new(Mutex::new(action)))
+    }
+}
+
+impl<F> Clone for SharedAction<F> {
+    fn clone(&self) -> Self {
+        SharedAction(self.0.clone())
+    }
+}
+
+impl<S, E, Ctx, F> OnAction<S, E, Ctx> for SharedAction<F>
+where
+    F: OnAction<S, E, Ctx>,
+{
+    fn call(&mut self, cx: ContextMut<S, E, Ctx>) {
+        // An action can only run while the machine is borrowed mutably, so the lock is never contended,
+        // but it may be poisoned by a panicking action.
+        let mut action = self.0.lock().unwrap_or_else(|err| err.into_inner());
+        action.call(cx);
+    }
+}
+
+/// An action that runs at most once, created with `Builder::action_once`.
+pub struct ActionOnce<F> {
+    action: Option<F>,
+    strict: bool,
+}
+
+impl<F> ActionOnce<F> {
+    /// Wraps the given action, the next calls after the first one do nothing.
+    pub fn new(action: F) -> Self {
+        ActionOnce {
+            action: Some(action),
+            strict: false,
+        }
+    }
+
+    /// Wraps the given action, the next calls after the first one reject the transition
+    /// with `TransitionError::ActionSpent`.
+    pub fn strict(action: F) -> Self {
+        ActionOnce {
+            action: Some(action),
+            strict: true,
+        }
+    }
+}
+
+impl<S, E, Ctx, F> OnAction<S, E, Ctx> for ActionOnce<F>
+where
+    F: FnOnce(ContextMut<S, E, Ctx>),
+{
+    fn call(&mut self, cx: ContextMut<S, E, Ctx>) {
+        match self.action.take() {
+            Some(f) => f(cx),
+            None if self.strict => cx.abort.set(Some(Abort::ActionSpent)),
+            None => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::TransitionError;
+
+    #[derive(Debug, PartialEq, Eq)]
+    struct Resource(u32);
+
+    #[test]
+    fn action_once_moves_value_test() {
+        let resource = Resource(7);
+
+        let mut sm = Machine::with_context(vec![])
+            .on_next(Builder::self_transition(0, 'a').action_once(
+                move |cx: ContextMut<i32, char, Vec<Resource>>| {
+                    cx.context.push(resource);
+                },
+            ))
+            .start(0);
+
+        sm.send('a').unwrap();
+        sm.send('a').unwrap();
+        assert_eq!(*sm.context(), vec![Resource(7)]);
+    }
+
+    #[test]
+    fn action_once_strict_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(
+                Builder::new(0)
+                    .on('a')
+                    .go_to(1)
+                    .action_once_strict(|cx: ContextMut<i32, char, i32>| *cx.context += 1),
+            )
+            .on_next(Builder::new(1).on('b').go_to(0))
+            .start(0);
+
+        sm.send('a').unwrap();
+        sm.send('b').unwrap();
+        assert_eq!(sm.send('a'), Err(TransitionError::ActionSpent));
+        assert_eq!(*sm.current(), 0);
+        assert_eq!(*sm.context(), 1);
+    }
+}
@@ -0,0 +1,403 @@
+use super::outcome::outcome_of;
+use super::panic::invoke;
+use super::{Build, FinishInfo, JournalEntry, Machine, Next, Ready};
+use crate::error::TransitionError;
+use crate::map::TransitionMap;
+
+/// The kind of failure passed to the `Machine::on_error` callback.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// No transition is defined for the event from the current state.
+    InvalidTransition,
+
+    /// An action cancelled the transition, or its one-shot action already ran.
+    ActionFailed,
+
+    /// A guard rejected the event, or the transition is exhausted or cooling down.
+    GuardRejected,
+}
+
+impl ErrorKind {
+    // Returns the kind of the error, if `on_error` is called for it.
+    fn of(error: &TransitionError) -> Option<Self> {
+        match error {
+            TransitionError::InvalidTransition => Some(ErrorKind::InvalidTransition),
+            TransitionError::Cancelled | TransitionError::ActionSpent => {
+                Some(ErrorKind::ActionFailed)
+            }
+            TransitionError::GuardRejected
+            | TransitionError::TransitionExhausted
+            | TransitionError::CoolingDown => Some(ErrorKind::GuardRejected),
+            _ => None,
+        }
+    }
+}
+
+/// The context of a failed event, passed to the `Machine::on_error` callback.
+#[derive(Debug)]
+pub struct ErrorContext<'a, S, E, Ctx> {
+    /// The current state of the state machine, the failed event didn't change it.
+    pub current: &'a S,
+
+    /// The event that failed.
+    pub event: Option<&'a E>,
+
+    /// The kind of failure.
+    pub kind: ErrorKind,
+
+    /// The error `send` returns if the failure is propagated.
+    pub error: &'a TransitionError,
+
+    /// The mutable data associated to the state machine.
+    pub context: &'a mut Ctx,
+}
+
+/// What the state machine does after a failed event, returned by the `Machine::on_error` callback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ErrorDecision<S> {
+    /// `send` returns the error.
+    Propagate,
+
+    /// The event is dropped and `send` returns the current state.
+    Ignore,
+
+    /// The machine moves to the given state and `send` returns the previous state,
+    /// the machine is done if the state is one of its success or failure states.
+    GoTo(S),
+
+    /// The machine moves to the given state and is done, `send` returns the previous state.
+    GoToFinal(S),
+}
+
+// A callback called with the failed events, which decides what the machine does after them.
+pub(crate) type OnError<'a, S, E, Ctx> =
+    Box<dyn FnMut(ErrorContext<S, E, Ctx>) -> ErrorDecision<S> + Send + Sync + 'a>;
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Sets a callback called each time an event fails, which decides whether `send` returns the error,
+    /// the event is dropped, or the machine moves to other state, like an error state.
+    ///
+    /// The callback is called for invalid transitions, failed actions and rejected guards,
+    /// but not when the machine is done, paused or poisoned.
+    /// Moving to other state doesn't run any action nor `on_transition`,
+    /// but the machine enters the state as with any transition and `on_done` is called if it is done.
+    /// The move is a transition for the failed event: it is recorded in the journal, audited and can be undone.
+    ///
+    /// If the state of `ErrorDecision::GoTo` is not a state of the machine,
+    /// `send` returns `TransitionError::UnknownState` and the machine stays in the current state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context(Vec::new())
+    ///     .on_next(Builder::new("idle").on("fetch").go_to("loading"))
+    ///     .on_next(Builder::new("loading").on("loaded").go_to("idle"))
+    ///     .on_next(Builder::new("error").on("retry").go_to("loading"))
+    ///     .on_error(|cx: ErrorContext<&str, &str, Vec<ErrorKind>>| {
+    ///         cx.context.push(cx.kind);
+    ///         ErrorDecision::GoTo("error")
+    ///     })
+    ///     .start("idle");
+    ///
+    /// assert_eq!(sm.send("loaded"), Ok("idle"));
+    /// assert_eq!(*sm.current(), "error");
+    /// assert_eq!(*sm.context(), [ErrorKind::InvalidTransition]);
+    /// ```
+    pub fn on_error<H>(mut self, on_error: H) -> Self
+    where
+        H: FnMut(ErrorContext<S, E, Ctx>) -> ErrorDecision<S> + Send + Sync + 'a,
+    {
+        self.extensions.on_error = Some(Box::new(on_error));
+        self
+    }
+}
+
+// Returns `true` if the state is the source or the target of any transition.
+fn is_known<S: PartialEq, E, A: ?Sized>(
+    map: &TransitionMap<S, E, Next<S, A>>,
+    match_by_discriminant: bool,
+    state: &S,
+) -> bool {
+    let variant = std::mem::discriminant(state);
+    let matches = |s: &S| match match_by_discriminant {
+        true => std::mem::discriminant(s) == variant,
+        false => s == state,
+    };
+
+    map.iter()
+        .any(|(from, _, first)| matches(from) || first.candidates().any(|next| matches(&next.next)))
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    S: PartialEq + Clone,
+{
+    // Calls `on_error` with the error of the event and the context taken from the machine by `context`,
+    // and applies its decision.
+    pub(crate) fn recover<'c>(
+        &'c mut self,
+        error: TransitionError,
+        event: &E,
+        context: impl FnOnce(&'c mut C) -> &'c mut Ctx,
+    ) -> Result<S, TransitionError> {
+        let (Some(kind), Some(f)) = (ErrorKind::of(&error), self.extensions.on_error.as_mut())
+        else {
+            return Err(error);
+        };
+
+        let context = context(&mut self.context);
+
+        let current = self.current.as_ref().unwrap();
+
+        // The context is copied before the callback can change it
+        let snapshot = self
+            .extensions
+            .undo
+            .as_ref()
+            .map(|history| history.copy(context));
+        if let Some(audit) = self.extensions.audit.as_mut() {
+            audit.copy(context);
+        }
+
+        let decision = invoke(self.extensions.catch_panics, || {
+            f(ErrorContext {
+                current,
+                event: Some(event),
+                kind,
+                error: &error,
+                context: &mut *context,
+            })
+        });
+
+        let (next, is_final) = match decision {
+            Err(message) => {
+                self.extensions.poisoned = true;
+                return Err(TransitionError::ActionPanicked(message));
+            }
+            Ok(ErrorDecision::Propagate) => return Err(error),
+            Ok(ErrorDecision::Ignore) => {
+                self.extensions.dedupe.handled(event);
+                return Ok(current.clone());
+            }
+            Ok(ErrorDecision::GoTo(next)) => {
+                let tagged = outcome_of(&self.extensions.outcomes, &next).is_some();
+                (next, tagged)
+            }
+            Ok(ErrorDecision::GoToFinal(next)) => (next, true),
+        };
+
+        if !is_known(
+            &self.transitions,
+            self.extensions.match_by_discriminant,
+            &next,
+        ) {
+            return Err(TransitionError::UnknownState);
+        }
+
+        // The move has no actions, so it is recorded once whatever the order of the journal
+        if let Some(journal) = self.extensions.journal.as_mut() {
+            let entry = JournalEntry {
+                from: current,
+                to: &next,
+                event,
+            };
+
+            journal
+                .persist
+                .record(&entry)
+                .map_err(|err| TransitionError::Journal(err.to_string()))?;
+        }
+
+        let prev_state = std::mem::replace(self.current.as_mut().unwrap(), next.clone());
+
+        if let Some(audit) = self.extensions.audit.as_mut() {
+            audit.report(&prev_state, &next, event, &*context);
+        }
+
+        if is_final {
+            self.done = true;
+            self.extensions.finished = Some(FinishInfo {
+                from: prev_state.clone(),
+                to: next.clone(),
+                event: None,
+            });
+
+            if let Some(f) = self.extensions.on_done.as_mut() {
+                f(&next, context);
+            }
+        }
+
+        if let (Some(history), Some(context)) = (self.extensions.undo.as_mut(), snapshot) {
+            history.push(prev_state.clone(), context);
+        }
+
+        if prev_state != next {
+            self.extensions.state_data.exit(&prev_state);
+            self.extensions.enter();
+        }
+
+        self.extensions.dedupe.handled(event);
+        Ok(prev_state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ErrorContext, ErrorDecision, ErrorKind};
+    use crate::blocking::{
+        AuditContext, Builder, ContextMut, JournalEntry, Machine, Persist, Ready,
+    };
+    use crate::error::TransitionError;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum State {
+        Idle,
+        Charging,
+        Failed(String),
+    }
+
+    type Log = Vec<(ErrorKind, Option<char>)>;
+
+    struct Recorder(Arc<Mutex<Vec<(State, char)>>>);
+
+    impl Persist<State, char> for Recorder {
+        fn record(&mut self, entry: &JournalEntry<State, char>) -> io::Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((entry.from.clone(), *entry.event));
+            Ok(())
+        }
+    }
+
+    fn machine(
+        decide: impl Fn(&ErrorContext<State, char, Log>) -> ErrorDecision<State> + Send + Sync + 'static,
+    ) -> Machine<'static, State, char, Log, (), Ready> {
+        Machine::with_context(Vec::new())
+            .on_next(Builder::new(State::Idle).on('c').go_to(State::Charging))
+            .on_next(
+                Builder::new(State::Charging)
+                    .on('x')
+                    .go_to(State::Idle)
+                    .action(|cx: ContextMut<State, char, Log>| cx.cancel()),
+            )
+            .on_next(
+                Builder::new(State::Charging)
+                    .on('g')
+                    .go_to(State::Idle)
+                    .guard_after(Duration::from_secs(60)),
+            )
+            .on_next(
+                Builder::new(State::Failed(String::new()))
+                    .on('r')
+                    .go_to(State::Idle),
+            )
+            .match_by_discriminant()
+            .on_error(move |cx| {
+                let decision = decide(&cx);
+                cx.context.push((cx.kind, cx.event.copied()));
+                decision
+            })
+            .start(State::Idle)
+    }
+
+    #[test]
+    fn on_error_propagate_test() {
+        let mut sm = machine(|_| ErrorDecision::Propagate);
+
+        assert_eq!(sm.send('x'), Err(TransitionError::InvalidTransition));
+        sm.send('c').unwrap();
+        assert_eq!(sm.send('x'), Err(TransitionError::Cancelled));
+        assert_eq!(sm.send('g'), Err(TransitionError::GuardRejected));
+
+        assert_eq!(*sm.current(), State::Charging);
+        assert_eq!(
+            *sm.context(),
+            [
+                (ErrorKind::InvalidTransition, Some('x')),
+                (ErrorKind::ActionFailed, Some('x')),
+                (ErrorKind::GuardRejected, Some('g')),
+            ]
+        );
+    }
+
+    #[test]
+    fn on_error_ignore_test() {
+        let mut sm = machine(|_| ErrorDecision::Ignore);
+
+        assert_eq!(sm.send('r'), Ok(State::Idle));
+        sm.send('c').unwrap();
+        assert_eq!(sm.send('x'), Ok(State::Charging));
+        assert_eq!(*sm.current(), State::Charging);
+        assert_eq!(sm.context().len(), 2);
+    }
+
+    #[test]
+    fn on_error_go_to_test() {
+        let mut sm = machine(|cx| ErrorDecision::GoTo(State::Failed(cx.error.to_string())));
+
+        sm.send('c').unwrap();
+        assert_eq!(sm.send('x'), Ok(State::Charging));
+        assert_eq!(
+            *sm.current(),
+            State::Failed(String::from("transition was cancelled"))
+        );
+
+        // The machine leaves the error state as with any other state
+        assert_eq!(
+            sm.send('r'),
+            Ok(State::Failed(String::from("transition was cancelled")))
+        );
+        assert!(!sm.is_done());
+
+        let mut sm = machine(|_| ErrorDecision::GoToFinal(State::Idle));
+        sm.send('x').unwrap();
+        assert!(sm.is_done());
+        assert_eq!(sm.send('c'), Err(TransitionError::Done));
+        assert_eq!(sm.context().len(), 1);
+    }
+
+    #[test]
+    fn on_error_unknown_state_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_error(|_| ErrorDecision::GoTo('z'))
+            .start('a');
+
+        assert_eq!(sm.send(1), Err(TransitionError::UnknownState));
+        assert_eq!(*sm.current(), 'a');
+    }
+
+    #[test]
+    fn on_error_go_to_is_a_transition_test() {
+        let journal = Arc::new(Mutex::new(Vec::new()));
+        let audits = Arc::new(Mutex::new(Vec::new()));
+        let (entries, audited) = (journal.clone(), audits.clone());
+
+        let mut sm = machine(|_| ErrorDecision::GoTo(State::Failed(String::new())))
+            .into_builder()
+            .with_journal(Recorder(entries))
+            .on_audit(move |cx: AuditContext<State, char, Log>| {
+                audited
+                    .lock()
+                    .unwrap()
+                    .push((cx.before.len(), cx.after.len()));
+            })
+            .with_undo(4)
+            .start(State::Idle);
+
+        assert_eq!(sm.send('x'), Ok(State::Idle));
+        assert_eq!(*journal.lock().unwrap(), [(State::Idle, 'x')]);
+        assert_eq!(*audits.lock().unwrap(), [(0, 1)]);
+
+        // Undo goes back to the state and the context before the failed event
+        sm.undo().unwrap();
+        assert_eq!(*sm.current(), State::Idle);
+        assert!(sm.context().is_empty());
+    }
+}
@@ -0,0 +1,248 @@
+use super::{Build, Leftovers, Machine, OnAction, OnTransition, Ready};
+use crate::error::RunError;
+
+/// How a state machine completed, returned by `Machine::outcome`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    /// The machine completed in one of its success states.
+    Success,
+
+    /// The machine completed in one of its failure states.
+    Failure,
+}
+
+// Returns the outcome the state is tagged with.
+pub(crate) fn outcome_of<S: PartialEq>(outcomes: &[(S, Outcome)], state: &S) -> Option<Outcome> {
+    outcomes
+        .iter()
+        .find(|(s, _)| s == state)
+        .map(|(_, outcome)| *outcome)
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Build, A, C>
+where
+    S: PartialEq,
+{
+    /// Tags the given states as success states, entering any of them completes the machine.
+    ///
+    /// # Panics
+    /// If any of the states is already tagged as a failure state.
+    pub fn success_states(self, states: impl IntoIterator<Item = S>) -> Self {
+        self.tag_outcome(states, Outcome::Success)
+    }
+
+    /// Tags the given states as failure states, entering any of them completes the machine.
+    ///
+    /// # Panics
+    /// If any of the states is already tagged as a success state.
+    pub fn failure_states(self, states: impl IntoIterator<Item = S>) -> Self {
+        self.tag_outcome(states, Outcome::Failure)
+    }
+
+    fn tag_outcome(mut self, states: impl IntoIterator<Item = S>, outcome: Outcome) -> Self {
+        for state in states {
+            match outcome_of(&self.extensions.outcomes, &state) {
+                Some(tagged) if tagged != outcome => {
+                    panic!("a state cannot be tagged as both a success and a failure state")
+                }
+                Some(_) => {}
+                None => self.extensions.outcomes.push((state, outcome)),
+            }
+        }
+
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    S: PartialEq,
+{
+    /// Returns how this state machine completed, or `None` if it is still running
+    /// or completed in a state that is not tagged with `success_states` nor `failure_states`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("pending").on("ok").go_to("completed"))
+    ///     .on_next(Builder::new("pending").on("error").go_to("failed"))
+    ///     .success_states(["completed"])
+    ///     .failure_states(["failed"])
+    ///     .start("pending");
+    ///
+    /// assert_eq!(sm.outcome(), None);
+    ///
+    /// sm.send("error").unwrap();
+    /// assert!(sm.is_done());
+    /// assert_eq!(sm.outcome(), Some(Outcome::Failure));
+    /// ```
+    pub fn outcome(&self) -> Option<Outcome> {
+        if !self.done {
+            return None;
+        }
+
+        outcome_of(&self.extensions.outcomes, self.current.as_ref()?)
+    }
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends the events until the machine is done and returns its context keyed by the outcome, ignoring the events left.
+    ///
+    /// # Returns
+    /// - Ok(Ok(Ctx)): If the machine completed in a state that is not a failure state.
+    /// - Ok(Err((Ctx, S))): If the machine completed in a failure state, with the state.
+    /// - Err(RunError): If the machine could not complete, see `run_to_completion_with`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let machine = || {
+    ///     Machine::with_context(0)
+    ///         .on_next(Builder::self_transition("open", 1).action(|cx: ContextMut<_, i32, i32>| {
+    ///             *cx.context += cx.event;
+    ///         }))
+    ///         .on_next(Builder::new("open").on(0).go_to("closed"))
+    ///         .on_next(Builder::new("open").on(-1).go_to("aborted"))
+    ///         .success_states(["closed"])
+    ///         .failure_states(["aborted"])
+    ///         .start("open")
+    /// };
+    ///
+    /// assert_eq!(machine().run_to_outcome([1, 1, 0]).unwrap(), Ok(2));
+    /// assert_eq!(machine().run_to_outcome([1, -1]).unwrap(), Err((1, "aborted")));
+    /// ```
+    pub fn run_to_outcome<I>(self, events: I) -> Result<Result<Ctx, (Ctx, S)>, RunError<S, E>>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        self.run_to_outcome_with(events, Leftovers::Ignore)
+    }
+
+    /// Sends the events until the machine is done and returns its context keyed by the outcome.
+    ///
+    /// # Returns
+    /// - Ok(Ok(Ctx)): If the machine completed in a state that is not a failure state.
+    /// - Ok(Err((Ctx, S))): If the machine completed in a failure state, with the state.
+    /// - Err(RunError): If the machine could not complete, see `run_to_completion_with`.
+    pub fn run_to_outcome_with<I>(
+        mut self,
+        events: I,
+        leftovers: Leftovers,
+    ) -> Result<Result<Ctx, (Ctx, S)>, RunError<S, E>>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        self.run(events, leftovers)?;
+
+        let outcome = self.outcome();
+        let context = self.context;
+
+        Ok(match outcome {
+            Some(Outcome::Failure) => Err((context, self.current.unwrap())),
+            _ => Ok(context),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Outcome;
+    use crate::blocking::{Builder, ContextMut, Leftovers, Machine, Ready};
+    use crate::error::RunError;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Job {
+        Pending,
+        Running,
+        Completed,
+        Failed,
+    }
+
+    fn count(cx: ContextMut<Job, char, u32>) {
+        *cx.context += 1;
+    }
+
+    fn machine() -> Machine<'static, Job, char, u32, (), Ready> {
+        Machine::with_context(0)
+            .on_next(
+                Builder::new(Job::Pending)
+                    .on('s')
+                    .go_to(Job::Running)
+                    .action(count),
+            )
+            .on_next(
+                Builder::new(Job::Running)
+                    .on('o')
+                    .go_to(Job::Completed)
+                    .action(count),
+            )
+            .on_next(
+                Builder::new(Job::Running)
+                    .on('e')
+                    .go_to(Job::Failed)
+                    .action(count),
+            )
+            .on_next(Builder::new(Job::Failed).on('r').go_to(Job::Pending))
+            .success_states([Job::Completed])
+            .failure_states([Job::Failed])
+            .start(Job::Pending)
+    }
+
+    #[test]
+    fn outcome_test() {
+        let mut sm = machine();
+        sm.send('s').unwrap();
+        assert_eq!(sm.outcome(), None);
+
+        sm.send('o').unwrap();
+        assert!(sm.is_done());
+        assert_eq!(sm.outcome(), Some(Outcome::Success));
+
+        let mut sm = machine();
+        sm.send('s').unwrap();
+        sm.send('e').unwrap();
+        assert!(sm.is_done());
+        assert_eq!(sm.outcome(), Some(Outcome::Failure));
+    }
+
+    #[test]
+    fn run_to_outcome_test() {
+        assert_eq!(machine().run_to_outcome("so".chars()), Ok(Ok(2)));
+        assert_eq!(
+            machine().run_to_outcome("seo".chars()),
+            Ok(Err((2, Job::Failed)))
+        );
+        assert_eq!(
+            machine().run_to_outcome_with("seo".chars(), Leftovers::Reject),
+            Err(RunError::Leftover {
+                state: Job::Failed,
+                event: 'o',
+            })
+        );
+        assert_eq!(
+            machine().run_to_outcome("s".chars()),
+            Err(RunError::Incomplete {
+                state: Job::Running
+            })
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn conflicting_outcome_test() {
+        let _ = Machine::<Job, char, (), ()>::new()
+            .success_states([Job::Completed, Job::Failed])
+            .failure_states([Job::Failed]);
+    }
+}
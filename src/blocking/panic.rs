@@ -0,0 +1,135 @@
+use super::{Build, Machine, Ready};
+use std::panic::{self, AssertUnwindSafe};
+
+// Calls the function, catching its panic and returning the panic message if `catch` is `true`.
+pub(crate) fn invoke<R>(catch: bool, f: impl FnOnce() -> R) -> Result<R, String> {
+    if !catch {
+        return Ok(f());
+    }
+
+    // The callbacks are not required to be `UnwindSafe`,
+    // after a panic the machine is poisoned and the context must be considered suspect.
+    panic::catch_unwind(AssertUnwindSafe(f)).map_err(|payload| {
+        if let Some(message) = payload.downcast_ref::<&str>() {
+            message.to_string()
+        } else if let Some(message) = payload.downcast_ref::<String>() {
+            message.clone()
+        } else {
+            String::from("unknown panic")
+        }
+    })
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Catches the panics of the actions and callbacks of this state machine.
+    ///
+    /// When a callback panics, `send` returns `TransitionError::ActionPanicked`, the state and done flag
+    /// go back to the values before the transition, and the machine is poisoned:
+    /// the next `send` calls fail with `TransitionError::Poisoned` until `clear_poison` is called.
+    ///
+    /// The changes made to the context before the panic are not undone.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::TransitionError;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running").action(|_: ContextMut<_, _, _>| {
+    ///         panic!("cannot start");
+    ///     }))
+    ///     .catch_panics()
+    ///     .start("idle");
+    ///
+    /// let error = TransitionError::ActionPanicked(String::from("cannot start"));
+    /// assert_eq!(sm.send("start"), Err(error));
+    /// assert_eq!(*sm.current(), "idle");
+    /// assert_eq!(sm.send("start"), Err(TransitionError::Poisoned));
+    /// ```
+    pub fn catch_panics(mut self) -> Self {
+        self.extensions.catch_panics = true;
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C> {
+    /// Returns `true` if a callback panicked and the machine was not cleared.
+    pub fn is_poisoned(&self) -> bool {
+        self.extensions.poisoned
+    }
+
+    /// Clears the poisoned state, allowing to send events again.
+    pub fn clear_poison(&mut self) {
+        self.extensions.poisoned = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine, UnhandledContext};
+    use crate::error::TransitionError;
+
+    fn reserve(cx: ContextMut<i32, char, Vec<char>>) {
+        cx.context.push(*cx.event);
+
+        if *cx.event == 'x' {
+            panic!("reservation failed");
+        }
+    }
+
+    #[test]
+    fn catch_panics_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::new(0).on('a').go_to(1).action(reserve))
+            .on_next(Builder::new(1).on('x').go_to(2).is_final().action(reserve))
+            .catch_panics()
+            .start(0);
+
+        sm.send('a').unwrap();
+        assert_eq!(
+            sm.send('x'),
+            Err(TransitionError::ActionPanicked("reservation failed".into()))
+        );
+
+        // The state is restored, but the context keeps the changes before the panic
+        assert_eq!(*sm.current(), 1);
+        assert!(!sm.is_done());
+        assert!(sm.is_poisoned());
+        assert_eq!(sm.context(), &['a', 'x']);
+        assert_eq!(sm.send('x'), Err(TransitionError::Poisoned));
+
+        sm.clear_poison();
+        assert!(!sm.is_poisoned());
+        assert!(sm.send('x').is_err());
+    }
+
+    #[test]
+    fn catch_panics_on_transition_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_transition(|cx| assert_ne!(*cx.to, 1, "entered 1"))
+            .catch_panics()
+            .start(0);
+
+        assert!(matches!(
+            sm.send('a'),
+            Err(TransitionError::ActionPanicked(_))
+        ));
+        assert_eq!(*sm.current(), 0);
+    }
+
+    #[test]
+    fn catch_panics_on_unhandled_test() {
+        let mut sm = Machine::new()
+            .on_unhandled(|_: UnhandledContext<i32, char, ()>| panic!("unhandled"))
+            .catch_panics()
+            .start(0);
+
+        assert_eq!(
+            sm.send('a'),
+            Err(TransitionError::ActionPanicked("unhandled".into()))
+        );
+        assert!(sm.is_poisoned());
+    }
+}
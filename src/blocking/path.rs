@@ -0,0 +1,163 @@
+use super::Machine;
+use std::collections::VecDeque;
+
+// A hop of a path, the event and the state it leads to.
+type Hop<'s, S, E> = (&'s E, &'s S);
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: PartialEq,
+{
+    /// Returns the shortest sequence of `(event, state)` hops that goes from `from` to `to`.
+    ///
+    /// Returns an empty path if `from` and `to` are the same state, and `None` if `to` is not reachable.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new('A').on(1).go_to('B'))
+    ///     .on_next(Builder::new('B').on(2).go_to('C'))
+    ///     .on_next(Builder::new('A').on(3).go_to('C'));
+    ///
+    /// assert_eq!(sm.find_path(&'A', &'C'), Some(vec![(&3, &'C')]));
+    /// assert_eq!(sm.find_path(&'C', &'A'), None);
+    /// ```
+    pub fn find_path<'s>(&'s self, from: &'s S, to: &S) -> Option<Vec<Hop<'s, S, E>>> {
+        // Each visited state and the index of its parent with the event that leads to it
+        let mut visited: Vec<(&S, Option<(usize, &E)>)> = vec![(from, None)];
+        let mut queue = VecDeque::from([0]);
+
+        while let Some(index) = queue.pop_front() {
+            let state = visited[index].0;
+
+            if state == to {
+                let mut path = Vec::new();
+                let mut cur = index;
+
+                while let (state, Some((parent, event))) = visited[cur] {
+                    path.push((event, state));
+                    cur = parent;
+                }
+
+                path.reverse();
+                return Some(path);
+            }
+
+            for (event, next) in self.transitions.outgoing(state) {
+                if !visited.iter().any(|(s, _)| *s == &next.next) {
+                    visited.push((&next.next, Some((index, event))));
+                    queue.push_back(visited.len() - 1);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Returns all the sequences of `(event, state)` hops with at most `max_len` hops that go from `from` to `to`.
+    ///
+    /// States can be visited more than once, so `max_len` bounds the search on cyclic machines.
+    pub fn find_all_paths<'s>(
+        &'s self,
+        from: &'s S,
+        to: &S,
+        max_len: usize,
+    ) -> Vec<Vec<Hop<'s, S, E>>> {
+        let mut paths = Vec::new();
+        let mut path = Vec::new();
+        self.collect_paths(from, to, max_len, &mut path, &mut paths);
+        paths
+    }
+
+    fn collect_paths<'s>(
+        &'s self,
+        state: &'s S,
+        to: &S,
+        max_len: usize,
+        path: &mut Vec<Hop<'s, S, E>>,
+        paths: &mut Vec<Vec<Hop<'s, S, E>>>,
+    ) {
+        if state == to {
+            paths.push(path.clone());
+        }
+
+        if path.len() == max_len {
+            return;
+        }
+
+        for (event, next) in self.transitions.outgoing(state) {
+            path.push((event, &next.next));
+            self.collect_paths(&next.next, to, max_len, path, paths);
+            path.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        A,
+        B,
+        C,
+        D,
+    }
+
+    fn machine() -> Machine<'static, State, u8, (), ()> {
+        Machine::new()
+            .on_next(Builder::new(State::A).on(1).go_to(State::B))
+            .on_next(Builder::new(State::B).on(2).go_to(State::C))
+            .on_next(Builder::new(State::C).on(3).go_to(State::D))
+            .on_next(Builder::new(State::B).on(4).go_to(State::D))
+            .on_next(Builder::new(State::D).on(5).go_to(State::A))
+    }
+
+    #[test]
+    fn find_path_test() {
+        let sm = machine();
+
+        assert_eq!(
+            sm.find_path(&State::A, &State::D),
+            Some(vec![(&1, &State::B), (&4, &State::D)])
+        );
+        assert_eq!(sm.find_path(&State::A, &State::A), Some(vec![]));
+        assert_eq!(
+            sm.find_path(&State::C, &State::B),
+            Some(vec![(&3, &State::D), (&5, &State::A), (&1, &State::B)])
+        );
+    }
+
+    #[test]
+    fn find_path_unreachable_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new(State::A).on(1).go_to(State::B))
+            .on_next(Builder::new(State::C).on(1).go_to(State::D))
+            .start(State::A);
+
+        assert_eq!(sm.find_path(&State::A, &State::D), None);
+    }
+
+    #[test]
+    fn find_all_paths_test() {
+        let sm = machine();
+
+        let paths = sm.find_all_paths(&State::A, &State::D, 3);
+        assert_eq!(
+            paths,
+            vec![
+                vec![(&1, &State::B), (&2, &State::C), (&3, &State::D)],
+                vec![(&1, &State::B), (&4, &State::D)],
+            ]
+        );
+
+        // The cycle `A -> B -> D -> A` can be taken again
+        let paths = sm.find_all_paths(&State::A, &State::D, 5);
+        assert_eq!(paths.len(), 3);
+        assert!(sm.find_all_paths(&State::A, &State::D, 1).is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use super::{Machine, Ready};
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C> {
+    /// Pauses this state machine, the events sent until it is resumed fail with `TransitionError::Paused`.
+    ///
+    /// No action nor hook runs while the machine is paused,
+    /// the posted events and the events of the `EventSender`s remain queued until it is resumed.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::TransitionError;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .start("idle");
+    ///
+    /// sm.pause();
+    /// assert_eq!(sm.send("start"), Err(TransitionError::Paused));
+    ///
+    /// sm.resume();
+    /// assert_eq!(sm.send("start"), Ok("idle"));
+    /// ```
+    pub fn pause(&mut self) {
+        self.extensions.paused = true;
+    }
+
+    /// Resumes this state machine if it is paused, allowing to send events again.
+    pub fn resume(&mut self) {
+        self.extensions.paused = false;
+    }
+
+    /// Returns `true` if the machine is paused.
+    pub fn is_paused(&self) -> bool {
+        self.extensions.paused
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::TransitionError;
+
+    fn count(cx: ContextMut<char, u8, i32>) {
+        *cx.context += 1;
+    }
+
+    #[test]
+    fn pause_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(Builder::new('a').on(0).go_to('b').action(count))
+            .on_next(Builder::new('b').on(1).go_to('a').action(count))
+            .on_unhandled(|cx| *cx.context = -1)
+            .on_transition(|cx| assert!(*cx.context > 0))
+            .start('a');
+
+        sm.pause();
+        sm.pause();
+        assert!(sm.is_paused());
+
+        assert_eq!(sm.send(0), Err(TransitionError::Paused));
+        assert_eq!(sm.send(9), Err(TransitionError::Paused));
+        assert_eq!(*sm.current(), 'a');
+        assert_eq!(*sm.context(), 0);
+
+        sm.resume();
+        assert!(!sm.is_paused());
+        assert_eq!(sm.send(0), Ok('a'));
+        assert_eq!(*sm.context(), 1);
+    }
+
+    #[test]
+    fn pause_keeps_queued_events_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(Builder::new('a').on(0).go_to('b').action(count))
+            .on_next(Builder::new('b').on(1).go_to('a').action(count))
+            .start('a');
+
+        let sender = sm.event_sender();
+        sm.pause();
+
+        sm.post(0).unwrap();
+        sender.send(1).unwrap();
+
+        assert_eq!(sm.process_one(), Some(Err(TransitionError::Paused)));
+        assert!(sm.process().is_empty());
+        assert!(sm.pump().is_empty());
+        assert_eq!(sm.pending(), 2);
+        assert_eq!(sender.len(), 2);
+
+        sm.resume();
+        assert_eq!(sm.process(), vec![Ok('a'), Ok('b')]);
+        assert!(sm.pump().is_empty());
+        assert_eq!(*sm.current(), 'a');
+        assert_eq!(*sm.context(), 2);
+    }
+}
@@ -0,0 +1,398 @@
+use super::{ContextMut, Machine, Next, Ready, SendAction};
+use crate::map::TransitionMap;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// An event of a product machine, that is sent to one of its components.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Either<L, R> {
+    /// An event for the first machine.
+    Left(L),
+
+    /// An event for the second machine.
+    Right(R),
+}
+
+/// Determines when a product machine is done.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProductFinal {
+    /// The product is done when any of the machines is done.
+    #[default]
+    Either,
+
+    /// The product is done when both machines are done.
+    Both,
+}
+
+impl ProductFinal {
+    fn is_done(self, left: bool, right: bool) -> bool {
+        match self {
+            ProductFinal::Either => left || right,
+            ProductFinal::Both => left && right,
+        }
+    }
+}
+
+/// The synchronous product of two state machines, created with `Machine::product`.
+pub type ProductMachine<'a, S1, E1, Ctx1, S2, E2, Ctx2> =
+    Machine<'a, (S1, S2), Either<E1, E2>, (Ctx1, Ctx2), (), Ready>;
+
+// An action shared by all the product transitions created from the same component transition.
+type SharedAction<'a, S, E, Ctx> = Arc<Mutex<Box<SendAction<'a, S, E, Ctx>>>>;
+
+// A transition of a component machine.
+struct Edge<'a, S, E, Ctx> {
+    from: S,
+    event: E,
+    to: S,
+    is_final: bool,
+    action: Option<SharedAction<'a, S, E, Ctx>>,
+}
+
+fn into_edges<'a, S, E, Ctx>(
+    transitions: TransitionMap<S, E, Next<S, SendAction<'a, S, E, Ctx>>>,
+) -> Vec<Edge<'a, S, E, Ctx>>
+where
+    S: Clone,
+{
+    transitions
+        .into_iter()
+        .map(|(from, event, next)| {
+            assert!(
+                next.before.is_none(),
+                "before actions are not supported by `Machine::product`"
+            );
+            assert!(
+                next.candidates.is_empty(),
+                "guarded candidate transitions are not supported by `Machine::product`"
+            );
+            assert!(
+                !next.ignored,
+                "ignored events are not supported by `Machine::product`"
+            );
+
+            Edge {
+                from,
+                event,
+                to: next.next,
+                is_final: next.is_final,
+                action: next.action.map(|f| Arc::new(Mutex::new(f))),
+            }
+        })
+        .collect()
+}
+
+// The product state being explored, with whether each component is done.
+struct Pair<S1, S2> {
+    left: S1,
+    right: S2,
+    left_done: bool,
+    right_done: bool,
+}
+
+impl<'a, S1, E1, Ctx1> Machine<'a, S1, E1, Ctx1, (), Ready>
+where
+    S1: PartialEq + Clone + Send + 'a,
+    E1: PartialEq + Clone + Send + 'a,
+    Ctx1: 'a,
+{
+    /// Returns the synchronous product of this machine and other.
+    ///
+    /// The states of the product are pairs of states, an `Either::Left` event advances the first machine
+    /// and an `Either::Right` event advances the second one, running the actions of the machine that owns the transition.
+    /// The product starts from the current states of both machines and only contains the reachable pairs.
+    ///
+    /// The `on_transition` hooks and the time guards are not part of the product, so both machines cannot have one.
+    ///
+    /// A machine is considered done on the pairs reached through its final transitions,
+    /// if a pair is reachable both before and after a machine is done, the first path explored is used.
+    ///
+    /// # Panics
+    /// If a transition of any machine has a before action, guarded candidates or is an ignored event,
+    /// or a machine has a hook set with `set_on_transition`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Auth {
+    ///     Valid,
+    ///     Expired,
+    /// }
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum Session {
+    ///     Active,
+    ///     Closed,
+    /// }
+    ///
+    /// let auth = Machine::new()
+    ///     .on_next(Builder::new(Auth::Valid).on("expire").go_to(Auth::Expired))
+    ///     .start(Auth::Valid);
+    ///
+    /// let session = Machine::new()
+    ///     .on_next(Builder::new(Session::Active).on("close").go_to(Session::Closed).is_final())
+    ///     .start(Session::Active);
+    ///
+    /// let mut sm = auth.product(session, ProductFinal::Either);
+    ///
+    /// sm.send(Either::Left("expire")).unwrap();
+    /// assert_eq!(*sm.current(), (Auth::Expired, Session::Active));
+    ///
+    /// sm.send(Either::Right("close")).unwrap();
+    /// assert_eq!(*sm.current(), (Auth::Expired, Session::Closed));
+    /// assert!(sm.is_done());
+    /// ```
+    pub fn product<S2, E2, Ctx2>(
+        self,
+        other: Machine<'a, S2, E2, Ctx2, (), Ready>,
+        mode: ProductFinal,
+    ) -> ProductMachine<'a, S1, E1, Ctx1, S2, E2, Ctx2>
+    where
+        S2: PartialEq + Clone + Send + 'a,
+        E2: PartialEq + Clone + Send + 'a,
+        Ctx2: 'a,
+    {
+        assert!(
+            self.extensions.on_transition.is_none() && other.extensions.on_transition.is_none(),
+            "`on_transition` hooks are not supported by `Machine::product`"
+        );
+
+        let left_edges = into_edges(self.transitions);
+        let right_edges = into_edges(other.transitions);
+        let initial = (self.current.unwrap(), other.current.unwrap());
+        let done = mode.is_done(self.done, other.done);
+
+        let mut transitions = TransitionMap::new();
+        let mut visited = vec![initial.clone()];
+        let mut pending = VecDeque::from([Pair {
+            left: initial.0.clone(),
+            right: initial.1.clone(),
+            left_done: self.done,
+            right_done: other.done,
+        }]);
+
+        while let Some(pair) = pending.pop_front() {
+            if mode.is_done(pair.left_done, pair.right_done) {
+                continue;
+            }
+
+            let from = (pair.left.clone(), pair.right.clone());
+            let mut targets = Vec::new();
+
+            if !pair.left_done {
+                for edge in left_edges.iter().filter(|e| e.from == pair.left) {
+                    let left_done = edge.is_final;
+                    let action = edge.action.clone().map(|action| {
+                        Box::new(
+                            move |cx: ContextMut<(S1, S2), Either<E1, E2>, (Ctx1, Ctx2)>| {
+                                if let Either::Left(event) = cx.event {
+                                    action.lock().unwrap().call(ContextMut {
+                                        from: &cx.from.0,
+                                        to: &cx.to.0,
+                                        event,
+                                        context: &mut cx.context.0,
+                                        state_data: None,
+                                        abort: cx.abort,
+                                        is_final: cx.is_final,
+                                        sender: None,
+                                    });
+                                }
+                            },
+                        ) as Box<SendAction<_, _, _>>
+                    });
+
+                    targets.push((
+                        Either::Left(edge.event.clone()),
+                        Pair {
+                            left: edge.to.clone(),
+                            right: pair.right.clone(),
+                            left_done,
+                            right_done: pair.right_done,
+                        },
+                        action,
+                    ));
+                }
+            }
+
+            if !pair.right_done {
+                for edge in right_edges.iter().filter(|e| e.from == pair.right) {
+                    let right_done = edge.is_final;
+                    let action = edge.action.clone().map(|action| {
+                        Box::new(
+                            move |cx: ContextMut<(S1, S2), Either<E1, E2>, (Ctx1, Ctx2)>| {
+                                if let Either::Right(event) = cx.event {
+                                    action.lock().unwrap().call(ContextMut {
+                                        from: &cx.from.1,
+                                        to: &cx.to.1,
+                                        event,
+                                        context: &mut cx.context.1,
+                                        state_data: None,
+                                        abort: cx.abort,
+                                        is_final: cx.is_final,
+                                        sender: None,
+                                    });
+                                }
+                            },
+                        ) as Box<SendAction<_, _, _>>
+                    });
+
+                    targets.push((
+                        Either::Right(edge.event.clone()),
+                        Pair {
+                            left: pair.left.clone(),
+                            right: edge.to.clone(),
+                            left_done: pair.left_done,
+                            right_done,
+                        },
+                        action,
+                    ));
+                }
+            }
+
+            for (event, target, action) in targets {
+                let to = (target.left.clone(), target.right.clone());
+                let is_final = mode.is_done(target.left_done, target.right_done);
+
+                transitions.insert(
+                    event,
+                    from.clone(),
+                    Next {
+                        next: to.clone(),
+                        is_final,
+                        action,
+                        before: None,
+                        guard: None,
+                        limit: None,
+                        priority: 0,
+                        label: None,
+                        candidates: Vec::new(),
+                        ignored: false,
+                        scope: None,
+                        #[cfg(feature = "rand")]
+                        weight: 1,
+                    },
+                );
+
+                if !visited.contains(&to) {
+                    visited.push(to);
+                    pending.push_back(target);
+                }
+            }
+        }
+
+        let mut machine = Machine::from_parts(transitions, (self.context, other.context));
+        machine.current = Some(initial);
+        machine.done = done;
+        machine
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Either, Machine, ProductFinal};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Light {
+        On,
+        Off,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Door {
+        Open,
+        Closed,
+        Locked,
+    }
+
+    fn light() -> Machine<'static, Light, char, i32, ()> {
+        Machine::with_context(0)
+            .on_next(Builder::new(Light::Off).on('t').go_to(Light::On).action(
+                |cx: ContextMut<Light, char, i32>| {
+                    *cx.context += 1;
+                },
+            ))
+            .on_next(Builder::new(Light::On).on('t').go_to(Light::Off))
+    }
+
+    fn door() -> Machine<'static, Door, u8, Vec<Door>, ()> {
+        Machine::with_context(vec![])
+            .on_next(Builder::new(Door::Closed).on(0).go_to(Door::Open).action(
+                |cx: ContextMut<Door, u8, Vec<Door>>| {
+                    cx.context.push(cx.to.clone());
+                },
+            ))
+            .on_next(Builder::new(Door::Open).on(1).go_to(Door::Closed))
+            .on_next(
+                Builder::new(Door::Closed)
+                    .on(2)
+                    .go_to(Door::Locked)
+                    .is_final(),
+            )
+    }
+
+    #[test]
+    fn product_test() {
+        let mut sm = light()
+            .start(Light::Off)
+            .product(door().start(Door::Closed), ProductFinal::Either);
+
+        // 2 light states x 3 door states
+        assert_eq!(sm.states().count(), 4);
+
+        sm.send(Either::Left('t')).unwrap();
+        sm.send(Either::Right(0)).unwrap();
+        sm.send(Either::Left('t')).unwrap();
+        sm.send(Either::Left('t')).unwrap();
+        sm.send(Either::Right(1)).unwrap();
+
+        assert_eq!(*sm.current(), (Light::On, Door::Closed));
+        assert_eq!(sm.context().0, 2);
+        assert_eq!(sm.context().1, vec![Door::Open]);
+        assert!(sm.send(Either::Right(1)).is_err());
+
+        sm.send(Either::Right(2)).unwrap();
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn product_both_final_test() {
+        let finish = || {
+            Machine::new()
+                .on_next(Builder::new(0).on(()).go_to(1).is_final())
+                .start(0)
+        };
+
+        let mut sm = finish().product(finish(), ProductFinal::Both);
+
+        sm.send(Either::Left(())).unwrap();
+        assert!(!sm.is_done());
+
+        // The first machine is done
+        assert!(sm.send(Either::Left(())).is_err());
+
+        sm.send(Either::Right(())).unwrap();
+        assert_eq!(*sm.current(), (1, 1));
+        assert!(sm.is_done());
+    }
+
+    #[test]
+    fn product_reachable_test() {
+        let left = Machine::new()
+            .on_next(Builder::new('a').on(1).go_to('b'))
+            .on_next(Builder::new('x').on(1).go_to('y'))
+            .start('a');
+
+        let right = Machine::new()
+            .on_next(Builder::new('c').on(2).go_to('d'))
+            .start('c');
+
+        let sm = left.product(right, ProductFinal::Either);
+
+        // ('a', 'c'), ('b', 'c'), ('a', 'd'), the state 'x' is not reachable
+        assert_eq!(sm.states().count(), 3);
+        assert!(sm.states().all(|(l, _)| *l != 'x'));
+    }
+}
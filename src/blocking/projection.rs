@@ -0,0 +1,439 @@
+use super::audit::{Audit, Auditor};
+use super::extensions::{Extensions, OnTransitionHook, OnUnhandled};
+use super::lifecycle::LifecycleHook;
+use super::on_error::OnError;
+use super::{
+    Build, Context, ContextMut, ErrorContext, Machine, Next, OnTransition, SendAction,
+    UnhandledContext,
+};
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+// The functions that project the context of the machine into the context of its actions.
+struct Lens<G, M> {
+    get: G,
+    get_mut: M,
+}
+
+// The audit callback of a machine which context is projected from a larger one.
+struct ProjectedAudit<'a, S, E, Ctx, G, M> {
+    audit: Audit<'a, S, E, Ctx>,
+    lens: Arc<Lens<G, M>>,
+}
+
+impl<S, E, Ctx, Ctx2, G, M> Auditor<S, E, Ctx2> for ProjectedAudit<'_, S, E, Ctx, G, M>
+where
+    G: Fn(&Ctx2) -> &Ctx + Send + Sync,
+    M: Send + Sync,
+{
+    // Only the projected part of the context is copied
+    fn copy(&mut self, context: &Ctx2) {
+        self.audit.copy((self.lens.get)(context));
+    }
+
+    fn report(&mut self, from: &S, to: &S, event: &E, after: &Ctx2) {
+        self.audit.report(from, to, event, (self.lens.get)(after));
+    }
+}
+
+// Wraps an action so it receives the projected context.
+fn project_action<'a, S, E, Ctx, Ctx2, G, M>(
+    mut action: Box<SendAction<'a, S, E, Ctx>>,
+    lens: Arc<Lens<G, M>>,
+) -> Box<SendAction<'a, S, E, Ctx2>>
+where
+    S: 'a,
+    E: 'a,
+    Ctx: 'a,
+    Ctx2: 'a,
+    G: Send + Sync + 'a,
+    M: Fn(&mut Ctx2) -> &mut Ctx + Send + Sync + 'a,
+{
+    Box::new(move |cx: ContextMut<S, E, Ctx2>| {
+        action.call(ContextMut {
+            from: cx.from,
+            to: cx.to,
+            event: cx.event,
+            context: (lens.get_mut)(cx.context),
+            state_data: cx.state_data,
+            abort: cx.abort,
+            is_final: cx.is_final,
+            sender: cx.sender,
+        })
+    })
+}
+
+// Wraps the actions of a transition and its candidates so they receive the projected context.
+fn project_next<'a, S, E, Ctx, Ctx2, G, M>(
+    next: Next<S, SendAction<'a, S, E, Ctx>>,
+    lens: &Arc<Lens<G, M>>,
+) -> Next<S, SendAction<'a, S, E, Ctx2>>
+where
+    S: 'a,
+    E: 'a,
+    Ctx: 'a,
+    Ctx2: 'a,
+    G: Send + Sync + 'a,
+    M: Fn(&mut Ctx2) -> &mut Ctx + Send + Sync + 'a,
+{
+    Next {
+        next: next.next,
+        is_final: next.is_final,
+        action: next.action.map(|f| project_action(f, lens.clone())),
+        before: next.before.map(|f| project_action(f, lens.clone())),
+        guard: next.guard,
+        limit: next.limit,
+        priority: next.priority,
+        label: next.label,
+        ignored: next.ignored,
+        scope: next.scope,
+        candidates: next
+            .candidates
+            .into_iter()
+            .map(|c| project_next(c, lens))
+            .collect(),
+        #[cfg(feature = "rand")]
+        weight: next.weight,
+    }
+}
+
+impl<'a, S, E, Ctx, F> Machine<'a, S, E, Ctx, F, Build>
+where
+    S: 'a,
+    E: 'a,
+    Ctx: 'a,
+    F: OnTransition<S, E, Ctx> + 'a,
+{
+    /// Returns this machine owning a larger context, from which the actions and hooks receive the part they use.
+    ///
+    /// The given functions return the part of the new context used by this machine,
+    /// and are called each time an action or hook runs, `on_audit` only copies the part of the context it uses.
+    /// The returned machine keeps the projected `on_transition`, so another one cannot be added.
+    ///
+    /// The `with_undo` of this machine is not kept, because the copies would be of the part of the context,
+    /// call `with_undo` on the returned machine to keep copies of the new context instead.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// struct AppCtx {
+    ///     count: i32,
+    ///     name: String,
+    /// }
+    ///
+    /// let counter = Machine::with_context(0).on_next(Builder::self_transition((), "inc").action(
+    ///     |cx: ContextMut<(), &str, i32>| {
+    ///         *cx.context += 1;
+    ///     },
+    /// ));
+    ///
+    /// let app = AppCtx {
+    ///     count: 0,
+    ///     name: String::from("app"),
+    /// };
+    ///
+    /// let mut sm = counter
+    ///     .map_context(|app: &AppCtx| &app.count, |app: &mut AppCtx| &mut app.count, app)
+    ///     .start(());
+    ///
+    /// sm.send("inc").unwrap();
+    /// assert_eq!(sm.context().count, 1);
+    /// assert_eq!(sm.context().name, "app");
+    /// ```
+    pub fn map_context<Ctx2, G, M>(
+        self,
+        get: G,
+        get_mut: M,
+        new_ctx: Ctx2,
+    ) -> Machine<'a, S, E, Ctx2, impl OnTransition<S, E, Ctx2> + 'a, Build>
+    where
+        Ctx2: 'a,
+        G: Fn(&Ctx2) -> &Ctx + Send + Sync + 'a,
+        M: Fn(&mut Ctx2) -> &mut Ctx + Send + Sync + 'a,
+    {
+        let lens = Arc::new(Lens { get, get_mut });
+
+        let transitions = self
+            .transitions
+            .map_values(|next| project_next(next, &lens));
+
+        let on_transition = self.on_transition.map(|mut f| {
+            let lens = lens.clone();
+            move |cx: Context<S, E, Ctx2>| {
+                f.call(Context {
+                    from: cx.from,
+                    to: cx.to,
+                    event: cx.event,
+                    context: (lens.get)(cx.context),
+                })
+            }
+        });
+
+        let Extensions {
+            clock,
+            timers,
+            on_unhandled,
+            on_error,
+            on_transition: on_transition_hook,
+            match_by_discriminant,
+            state_data,
+            node_hint,
+            catch_panics,
+            poisoned,
+            paused,
+            sender,
+            journal,
+            entered_at,
+            undo: _,
+            scoped,
+            sends,
+            invariants,
+            audit,
+            finished,
+            outcomes,
+            name,
+            on_start,
+            on_done,
+            sizes,
+            dedupe,
+        } = self.extensions;
+
+        let project_hook = |mut f: LifecycleHook<'a, S, Ctx>| {
+            let lens = lens.clone();
+            Box::new(move |state: &S, cx: &mut Ctx2| f(state, (lens.get_mut)(cx)))
+                as LifecycleHook<'a, S, Ctx2>
+        };
+
+        let on_transition_hook = on_transition_hook.map(|mut f| {
+            let lens = lens.clone();
+            Box::new(move |cx: Context<S, E, Ctx2>| {
+                f(Context {
+                    from: cx.from,
+                    to: cx.to,
+                    event: cx.event,
+                    context: (lens.get)(cx.context),
+                })
+            }) as OnTransitionHook<'a, S, E, Ctx2>
+        });
+
+        let audit = audit.map(|audit| {
+            Box::new(ProjectedAudit {
+                audit,
+                lens: lens.clone(),
+            }) as Audit<'a, S, E, Ctx2>
+        });
+
+        let on_start = on_start.map(project_hook);
+        let on_done = on_done.map(project_hook);
+
+        let on_error = on_error.map(|mut f| {
+            let lens = lens.clone();
+            Box::new(move |cx: ErrorContext<S, E, Ctx2>| {
+                f(ErrorContext {
+                    current: cx.current,
+                    event: cx.event,
+                    kind: cx.kind,
+                    error: cx.error,
+                    context: (lens.get_mut)(cx.context),
+                })
+            }) as OnError<'a, S, E, Ctx2>
+        });
+
+        let on_unhandled = on_unhandled.map(|mut f| {
+            Box::new(move |cx: UnhandledContext<S, E, Ctx2>| {
+                f(UnhandledContext {
+                    state: cx.state,
+                    event: cx.event,
+                    context: (lens.get_mut)(cx.context),
+                    is_done: cx.is_done,
+                })
+            }) as OnUnhandled<'a, S, E, Ctx2>
+        });
+
+        Machine {
+            transitions,
+            current: self.current,
+            done: self.done,
+            context: new_ctx,
+            on_transition,
+            extensions: Extensions {
+                clock,
+                timers,
+                on_unhandled,
+                on_error,
+                on_transition: on_transition_hook,
+                match_by_discriminant,
+                state_data,
+                node_hint,
+                catch_panics,
+                poisoned,
+                paused,
+                sender,
+                journal,
+                entered_at,
+                undo: None,
+                scoped,
+                sends,
+                invariants,
+                audit,
+                finished,
+                outcomes,
+                name,
+                on_start,
+                on_done,
+                sizes,
+                dedupe,
+            },
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{AuditContext, Builder, Context, ContextMut, Machine};
+    use crate::error::UndoError;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default, Clone)]
+    struct CounterCtx {
+        count: u32,
+    }
+
+    #[derive(Clone)]
+    struct AppCtx {
+        counter: CounterCtx,
+        other: String,
+    }
+
+    #[test]
+    fn map_context_test() {
+        let counter = Machine::with_context(CounterCtx::default())
+            .on_next(Builder::new("idle").on("start").go_to("counting").action(
+                |cx: ContextMut<&str, &str, CounterCtx>| {
+                    cx.context.count += 1;
+                },
+            ))
+            .on_next(
+                Builder::self_transition("counting", "tick")
+                    .before(|cx: ContextMut<&str, &str, CounterCtx>| {
+                        if cx.context.count >= 3 {
+                            cx.cancel();
+                        }
+                    })
+                    .action(|cx: ContextMut<&str, &str, CounterCtx>| {
+                        cx.context.count += 1;
+                    }),
+            )
+            .on_transition(|cx| assert!(cx.context.count > 0))
+            .on_unhandled(|cx| cx.context.count = 0);
+
+        let app = AppCtx {
+            counter: CounterCtx::default(),
+            other: String::from("untouched"),
+        };
+
+        let mut sm = counter
+            .map_context(
+                |app: &AppCtx| &app.counter,
+                |app: &mut AppCtx| &mut app.counter,
+                app,
+            )
+            .start("idle");
+
+        sm.send("start").unwrap();
+        sm.send("tick").unwrap();
+        sm.send("tick").unwrap();
+        assert!(sm.send("tick").is_err());
+        assert_eq!(sm.context().counter.count, 3);
+
+        assert!(sm.send("unknown").is_err());
+        assert_eq!(sm.context().counter.count, 0);
+        assert_eq!(sm.context().other, "untouched");
+    }
+
+    #[test]
+    fn map_context_undo_test() {
+        let counter = || {
+            Machine::with_context(CounterCtx::default()).on_next(
+                Builder::self_transition("counting", "tick").action(
+                    |cx: ContextMut<&str, &str, CounterCtx>| {
+                        cx.context.count += 1;
+                    },
+                ),
+            )
+        };
+        let app = || AppCtx {
+            counter: CounterCtx::default(),
+            other: String::from("untouched"),
+        };
+        fn get(app: &AppCtx) -> &CounterCtx {
+            &app.counter
+        }
+        fn get_mut(app: &mut AppCtx) -> &mut CounterCtx {
+            &mut app.counter
+        }
+
+        // The undo history of the projected machine is dropped
+        let mut sm = counter()
+            .with_undo(4)
+            .map_context(get, get_mut, app())
+            .start("counting");
+        sm.send("tick").unwrap();
+        assert_eq!(sm.undo(), Err(UndoError::Disabled));
+
+        let mut sm = counter()
+            .map_context(get, get_mut, app())
+            .with_undo(4)
+            .start("counting");
+        sm.send("tick").unwrap();
+        sm.undo().unwrap();
+        assert_eq!(sm.context().counter.count, 0);
+    }
+
+    #[test]
+    fn map_context_hooks_test() {
+        let audits = Arc::new(Mutex::new(Vec::new()));
+        let transitions = Arc::new(Mutex::new(Vec::new()));
+        let (audited, entries) = (audits.clone(), transitions.clone());
+
+        let mut counter = Machine::with_context(CounterCtx::default())
+            .on_next(Builder::self_transition("counting", "tick").action(
+                |cx: ContextMut<&str, &str, CounterCtx>| {
+                    cx.context.count += 1;
+                },
+            ))
+            .on_audit(move |cx: AuditContext<&str, &str, CounterCtx>| {
+                audited
+                    .lock()
+                    .unwrap()
+                    .push((cx.before.count, cx.after.count));
+            })
+            .start("counting");
+
+        counter.set_on_transition(move |cx: Context<&str, &str, CounterCtx>| {
+            entries.lock().unwrap().push(cx.context.count);
+        });
+
+        let app = AppCtx {
+            counter: CounterCtx::default(),
+            other: String::from("untouched"),
+        };
+
+        let mut sm = counter
+            .into_builder()
+            .map_context(
+                |app: &AppCtx| &app.counter,
+                |app: &mut AppCtx| &mut app.counter,
+                app,
+            )
+            .start("counting");
+
+        sm.send("tick").unwrap();
+        sm.send("tick").unwrap();
+        assert_eq!(*audits.lock().unwrap(), [(0, 1), (1, 2)]);
+        assert_eq!(*transitions.lock().unwrap(), [1, 2]);
+    }
+}
@@ -0,0 +1,235 @@
+use super::{Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+
+// An event waiting in the queue of a machine.
+struct Queued<E> {
+    priority: u8,
+    seq: u64,
+    event: E,
+}
+
+impl<E> PartialEq for Queued<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<E> Eq for Queued<E> {}
+
+impl<E> PartialOrd for Queued<E> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<E> Ord for Queued<E> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Highest priority first, then the oldest event first
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.seq.cmp(&self.seq))
+    }
+}
+
+// The events posted to a machine or queued by its `EventSender`s, ordered by priority.
+pub(crate) struct EventQueue<E> {
+    heap: BinaryHeap<Queued<E>>,
+    seq: u64,
+    // The maximum number of events waiting in the queue, if it is bounded.
+    pub(crate) capacity: Option<usize>,
+}
+
+impl<E> EventQueue<E> {
+    pub(crate) fn new() -> Self {
+        EventQueue {
+            heap: BinaryHeap::new(),
+            seq: 0,
+            capacity: None,
+        }
+    }
+
+    // Queues an event, returns the event back if the queue is full.
+    pub(crate) fn push(&mut self, event: E, priority: u8) -> Result<(), E> {
+        if self.capacity.is_some_and(|capacity| self.len() >= capacity) {
+            return Err(event);
+        }
+
+        let seq = self.seq;
+        self.seq += 1;
+        self.heap.push(Queued {
+            priority,
+            seq,
+            event,
+        });
+
+        Ok(())
+    }
+
+    pub(crate) fn pop(&mut self) -> Option<E> {
+        self.heap.pop().map(|q| q.event)
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.heap.clear();
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized> Machine<'_, S, E, Ctx, F, Ready, A> {
+    /// Posts an event to the queue of this state machine with the default priority `0`,
+    /// the event is not sent until the queue is processed.
+    ///
+    /// The queue is the same used by the `EventSender`s of the machine,
+    /// returns the event back if the queue has `Limits::max_queued_events`.
+    pub fn post(&mut self, event: E) -> Result<(), E> {
+        self.post_with_priority(event, 0)
+    }
+
+    /// Posts an event to the queue of this state machine with the given priority.
+    ///
+    /// Events with higher priority are processed first,
+    /// and events with the same priority are processed in the order they were posted.
+    ///
+    /// Returns the event back if the queue has `Limits::max_queued_events`.
+    pub fn post_with_priority(&mut self, event: E, priority: u8) -> Result<(), E> {
+        self.extensions.sender.lock().push(event, priority)
+    }
+
+    /// Returns the number of events waiting in the queue.
+    pub fn pending(&self) -> usize {
+        self.extensions.sender.len()
+    }
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends the next event in the queue, if any.
+    ///
+    /// While the machine is paused the event remains in the queue and `TransitionError::Paused` is returned.
+    pub fn process_one(&mut self) -> Option<Result<S, TransitionError>> {
+        if self.extensions.paused && self.pending() > 0 {
+            return Some(Err(TransitionError::Paused));
+        }
+
+        // The queue is not locked while sending, so the actions can queue more events
+        let event = self.extensions.sender.lock().pop()?;
+        Some(self.send(event))
+    }
+
+    /// Sends all the events in the queue and returns the result of each one.
+    ///
+    /// Stops when the machine is done or paused, the events left remain in the queue, see `pending`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_next(Builder::new("running").on("stop").go_to("stopped").is_final())
+    ///     .start("idle");
+    ///
+    /// sm.post("stop").unwrap();
+    /// sm.post_with_priority("start", 1).unwrap();
+    /// sm.post("start").unwrap();
+    ///
+    /// assert_eq!(sm.process(), vec![Ok("idle"), Ok("running")]);
+    /// assert_eq!(sm.pending(), 1);
+    /// ```
+    pub fn process(&mut self) -> Vec<Result<S, TransitionError>> {
+        let mut results = Vec::new();
+
+        while !self.is_done() && !self.extensions.paused {
+            match self.process_one() {
+                Some(result) => results.push(result),
+                None => break,
+            }
+        }
+
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::TransitionError;
+
+    fn record(cx: ContextMut<i32, i32, Vec<i32>>) {
+        cx.context.push(*cx.event);
+    }
+
+    #[test]
+    fn process_priority_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition(0, 'a'))
+            .on_next(Builder::self_transition(0, 'b'))
+            .on_next(Builder::self_transition(0, 'c'))
+            .start(0);
+
+        sm.post('a').unwrap();
+        sm.post_with_priority('b', 2).unwrap();
+        sm.post('c').unwrap();
+        sm.post_with_priority('a', 2).unwrap();
+        sm.post('x').unwrap();
+
+        assert_eq!(sm.pending(), 5);
+        assert_eq!(
+            sm.process(),
+            vec![
+                Ok(0),
+                Ok(0),
+                Ok(0),
+                Ok(0),
+                Err(TransitionError::InvalidTransition)
+            ]
+        );
+        assert_eq!(sm.pending(), 0);
+    }
+
+    #[test]
+    fn process_fifo_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::self_transition(0, 1).action(record))
+            .on_next(Builder::self_transition(0, 2).action(record))
+            .on_next(Builder::self_transition(0, 3).action(record))
+            .start(0);
+
+        sm.post(3).unwrap();
+        sm.post_with_priority(1, 1).unwrap();
+        sm.post(2).unwrap();
+        sm.post_with_priority(3, 1).unwrap();
+        sm.post(1).unwrap();
+
+        sm.process();
+        assert_eq!(sm.context(), &[1, 3, 3, 2, 1]);
+    }
+
+    #[test]
+    fn process_stops_when_done_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('b').go_to(2).is_final())
+            .start(0);
+
+        sm.post('a').unwrap();
+        sm.post('b').unwrap();
+        sm.post('a').unwrap();
+
+        assert_eq!(sm.process_one(), Some(Ok(0)));
+        assert_eq!(sm.process(), vec![Ok(1)]);
+        assert!(sm.is_done());
+        assert_eq!(sm.pending(), 1);
+    }
+}
@@ -0,0 +1,151 @@
+use super::{Machine, OnAction, OnTransition, Ready};
+use crate::error::RunError;
+
+/// What to do with the events left after a machine is done, used by `Machine::run_to_completion_with`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Leftovers {
+    /// The events left are not consumed.
+    #[default]
+    Ignore,
+
+    /// Fails with the first event left.
+    Reject,
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends the events until the machine is done and returns its context, ignoring the events left.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let total = Machine::with_context(0)
+    ///     .on_next(Builder::self_transition("open", 1).action(|cx: ContextMut<_, i32, i32>| {
+    ///         *cx.context += cx.event;
+    ///     }))
+    ///     .on_next(Builder::new("open").on(0).go_to("closed").is_final())
+    ///     .start("open")
+    ///     .run_to_completion([1, 1, 1, 0]);
+    ///
+    /// assert_eq!(total.unwrap(), 3);
+    /// ```
+    pub fn run_to_completion<I>(self, events: I) -> Result<Ctx, RunError<S, E>>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        self.run_to_completion_with(events, Leftovers::Ignore)
+    }
+
+    /// Sends the events until the machine is done and returns its context.
+    ///
+    /// # Returns
+    /// - Ok(Ctx): If the machine is done.
+    /// - Err(RunError::Rejected): If an event could not be handled.
+    /// - Err(RunError::Incomplete): If the events ran out before the machine was done.
+    /// - Err(RunError::Leftover): If there are events left and `leftovers` is `Leftovers::Reject`.
+    pub fn run_to_completion_with<I>(
+        mut self,
+        events: I,
+        leftovers: Leftovers,
+    ) -> Result<Ctx, RunError<S, E>>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        self.run(events, leftovers)?;
+        Ok(self.context)
+    }
+
+    // Sends the events until the machine is done.
+    pub(crate) fn run<I>(&mut self, events: I, leftovers: Leftovers) -> Result<(), RunError<S, E>>
+    where
+        I: IntoIterator<Item = E>,
+    {
+        let mut events = events.into_iter();
+
+        while !self.done {
+            let Some(event) = events.next() else {
+                return Err(RunError::Incomplete {
+                    state: self.current().clone(),
+                });
+            };
+
+            if let Err((error, event)) = self.send_or_return(event) {
+                return Err(RunError::Rejected {
+                    state: self.current().clone(),
+                    event,
+                    error,
+                });
+            }
+        }
+
+        if leftovers == Leftovers::Reject {
+            if let Some(event) = events.next() {
+                return Err(RunError::Leftover {
+                    state: self.current().clone(),
+                    event,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Leftovers;
+    use crate::blocking::{Builder, ContextMut, Machine, Ready};
+    use crate::error::{RunError, TransitionError};
+
+    fn machine() -> Machine<'static, i32, char, Vec<char>, (), Ready> {
+        let push = |cx: ContextMut<i32, char, Vec<char>>| cx.context.push(*cx.event);
+
+        Machine::with_context(Vec::new())
+            .on_next(Builder::new(0).on('a').go_to(1).action(push))
+            .on_next(Builder::new(1).on('b').go_to(2).is_final().action(push))
+            .start(0)
+    }
+
+    #[test]
+    fn run_to_completion_test() {
+        assert_eq!(
+            machine().run_to_completion("abab".chars()),
+            Ok(vec!['a', 'b'])
+        );
+        assert_eq!(
+            machine().run_to_completion_with("abab".chars(), Leftovers::Reject),
+            Err(RunError::Leftover {
+                state: 2,
+                event: 'a'
+            })
+        );
+        assert_eq!(
+            machine().run_to_completion_with("ab".chars(), Leftovers::Reject),
+            Ok(vec!['a', 'b'])
+        );
+    }
+
+    #[test]
+    fn run_to_completion_error_test() {
+        assert_eq!(
+            machine().run_to_completion("aa".chars()),
+            Err(RunError::Rejected {
+                state: 1,
+                event: 'a',
+                error: TransitionError::InvalidTransition
+            })
+        );
+
+        assert_eq!(
+            machine().run_to_completion("a".chars()),
+            Err(RunError::Incomplete { state: 1 })
+        );
+    }
+}
@@ -0,0 +1,240 @@
+use super::machine::split;
+use super::{IntoTransition, Machine, Ready};
+use crate::error::BuildError;
+use std::sync::{Arc, Weak};
+use std::time::Instant;
+
+/// How long a transition added with `Machine::add_scoped_transition` lasts.
+#[derive(Debug, Clone)]
+pub enum Scope {
+    /// The transition is removed after the given number of events are sent to the machine.
+    Sends(u32),
+
+    /// The transition is removed at the given time of the clock of the machine.
+    Until(Instant),
+
+    /// The transition is removed when its `ScopeHandle` is dropped or revoked, see `ScopeHandle::scope`.
+    Manual(ScopeLink),
+}
+
+/// A handle that keeps alive the transitions added with its `scope`, they are removed when it is dropped.
+#[derive(Debug, Default)]
+pub struct ScopeHandle {
+    alive: Arc<()>,
+}
+
+/// The link between a `ScopeHandle` and a transition, used by `Scope::Manual`.
+#[derive(Debug, Clone)]
+pub struct ScopeLink {
+    alive: Weak<()>,
+}
+
+impl ScopeHandle {
+    /// Returns a new handle.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a scope that lasts until this handle is dropped or revoked.
+    pub fn scope(&self) -> Scope {
+        Scope::Manual(ScopeLink {
+            alive: Arc::downgrade(&self.alive),
+        })
+    }
+
+    /// Removes the transitions of this handle, the same as dropping it.
+    pub fn revoke(self) {}
+}
+
+// When a scoped transition is removed.
+pub(crate) enum Expiry {
+    // After this number of sends of the machine.
+    Sends(u64),
+    Until(Instant),
+    Manual(Weak<()>),
+}
+
+impl Expiry {
+    pub(crate) fn is_expired(&self, sends: u64, now: impl FnOnce() -> Instant) -> bool {
+        match self {
+            Expiry::Sends(last) => sends > *last,
+            Expiry::Until(deadline) => now() >= *deadline,
+            Expiry::Manual(alive) => alive.strong_count() == 0,
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Ready, A, C>
+where
+    E: PartialEq,
+    S: PartialEq,
+{
+    /// Adds a transition to the started machine that is removed when the scope ends.
+    ///
+    /// The expired transitions are removed on the next `send`, so there is no background work.
+    ///
+    /// # Returns
+    /// - Ok(()): If the transition was added.
+    /// - Err(BuildError::DuplicateTransition): If a transition already exists for the event from the state,
+    ///   a scoped transition never replaces other transition.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::error::TransitionError;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("active").on("upgrade").go_to("migrating"))
+    ///     .on_next(Builder::new("migrating").on("done").go_to("active"))
+    ///     .start("active");
+    ///
+    /// sm.add_scoped_transition(
+    ///     Builder::new("active").on("legacy").go_to("migrating"),
+    ///     Scope::Sends(2),
+    /// )
+    /// .unwrap();
+    ///
+    /// sm.send("legacy").unwrap();
+    /// sm.send("done").unwrap();
+    /// assert_eq!(sm.send("legacy"), Err(TransitionError::InvalidTransition));
+    /// ```
+    pub fn add_scoped_transition(
+        &mut self,
+        transition: impl IntoTransition<'a, S, E, Ctx, A>,
+        scope: Scope,
+    ) -> Result<(), BuildError> {
+        // An expired transition doesn't conflict with the new one
+        self.remove_expired();
+
+        let (event, from, mut next) = split(transition);
+        next.scope = Some(match scope {
+            Scope::Sends(count) => Expiry::Sends(self.extensions.sends + u64::from(count)),
+            Scope::Until(deadline) => Expiry::Until(deadline),
+            Scope::Manual(link) => Expiry::Manual(link.alive),
+        });
+
+        let added = self.extensions.check_limits(&event, &from, &next.next)?;
+
+        self.transitions
+            .try_insert(event, from, next)
+            .map_err(|_| BuildError::DuplicateTransition)?;
+
+        self.extensions.count_added(added);
+        self.extensions.scoped += 1;
+        Ok(())
+    }
+
+    // Removes the scoped transitions that expired.
+    pub(crate) fn remove_expired(&mut self) {
+        if self.extensions.scoped == 0 {
+            return;
+        }
+
+        let (sends, clock) = (self.extensions.sends, &self.extensions.clock);
+        let mut now = None;
+        let mut removed = 0;
+
+        self.transitions.retain(|_, _, next| {
+            let expired = next.scope.as_ref().is_some_and(|scope| {
+                scope.is_expired(sends, || *now.get_or_insert_with(|| clock.now()))
+            });
+
+            removed += usize::from(expired);
+            !expired
+        });
+
+        self.extensions.scoped -= removed;
+        self.extensions.count_removed(removed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Scope, ScopeHandle};
+    use crate::blocking::{Builder, Machine};
+    use crate::clock::{Clock, MockClock};
+    use crate::error::{BuildError, TransitionError};
+    use std::time::Duration;
+
+    #[test]
+    fn scope_sends_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_next(Builder::new('b').on(0).go_to('a'))
+            .start('a');
+
+        sm.add_scoped_transition(Builder::self_transition('a', 1), Scope::Sends(3))
+            .unwrap();
+        assert_eq!(sm.transitions().count(), 3);
+
+        assert_eq!(sm.send(1), Ok('a'));
+        assert_eq!(sm.send(9), Err(TransitionError::InvalidTransition));
+        assert_eq!(sm.send(1), Ok('a'));
+        assert_eq!(sm.send(1), Err(TransitionError::InvalidTransition));
+        assert_eq!(sm.transitions().count(), 2);
+    }
+
+    #[test]
+    fn scope_until_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .with_clock(clock.clone())
+            .start('a');
+
+        let deadline = clock.now() + Duration::from_secs(5);
+        sm.add_scoped_transition(Builder::self_transition('a', 1), Scope::Until(deadline))
+            .unwrap();
+
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(sm.send(1), Ok('a'));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(sm.send(1), Err(TransitionError::InvalidTransition));
+        assert_eq!(sm.send(0), Ok('a'));
+    }
+
+    #[test]
+    fn scope_manual_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .start('a');
+
+        let first = ScopeHandle::new();
+        let second = ScopeHandle::new();
+        sm.add_scoped_transition(Builder::self_transition('a', 1), first.scope())
+            .unwrap();
+        sm.add_scoped_transition(Builder::self_transition('a', 2), second.scope())
+            .unwrap();
+
+        assert_eq!(sm.send(1), Ok('a'));
+        drop(first);
+        assert_eq!(sm.send(1), Err(TransitionError::InvalidTransition));
+
+        assert_eq!(sm.send(2), Ok('a'));
+        second.revoke();
+        assert_eq!(sm.send(2), Err(TransitionError::InvalidTransition));
+    }
+
+    #[test]
+    fn scope_conflict_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .start('a');
+
+        assert_eq!(
+            sm.add_scoped_transition(Builder::self_transition('a', 0), Scope::Sends(1)),
+            Err(BuildError::DuplicateTransition)
+        );
+
+        // An expired scoped transition can be added again
+        let handle = ScopeHandle::new();
+        sm.add_scoped_transition(Builder::self_transition('a', 1), handle.scope())
+            .unwrap();
+        drop(handle);
+        sm.add_scoped_transition(Builder::self_transition('a', 1), Scope::Sends(1))
+            .unwrap();
+        assert_eq!(sm.send(1), Ok('a'));
+    }
+}
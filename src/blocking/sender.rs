@@ -0,0 +1,218 @@
+use super::queue::EventQueue;
+use super::{Build, ContextMut, Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// A handle to queue events to a state machine from anywhere,
+/// the events are sent when calling `Machine::process` or `Machine::pump`.
+///
+/// The events join the same queue of `Machine::post` with the default priority `0`.
+pub struct EventSender<E> {
+    queue: Arc<Mutex<EventQueue<E>>>,
+}
+
+impl<E> EventSender<E> {
+    pub(crate) fn new() -> Self {
+        EventSender {
+            queue: Arc::new(Mutex::new(EventQueue::new())),
+        }
+    }
+
+    /// Queues an event, returns the event back if the queue is full.
+    pub fn send(&self, event: E) -> Result<(), E> {
+        self.lock().push(event, 0)
+    }
+
+    /// Returns the number of events waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Returns `true` if there are no events waiting to be sent.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn lock(&self) -> MutexGuard<'_, EventQueue<E>> {
+        // The lock is only held while modifying the queue, so a panic cannot leave it inconsistent
+        self.queue.lock().unwrap_or_else(|err| err.into_inner())
+    }
+}
+
+impl<E> Clone for EventSender<E> {
+    fn clone(&self) -> Self {
+        EventSender {
+            queue: self.queue.clone(),
+        }
+    }
+}
+
+impl<E> Debug for EventSender<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventSender")
+            .field("pending", &self.len())
+            .finish()
+    }
+}
+
+impl<S, E, Ctx> ContextMut<'_, S, E, Ctx> {
+    /// Returns a sender to queue events to the state machine running this action, if the machine supports it.
+    ///
+    /// The events are sent after the current transition, when the queue of the machine is processed.
+    pub fn sender(&self) -> Option<&EventSender<E>> {
+        self.sender
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized> Machine<'a, S, E, Ctx, F, Build, A> {
+    /// Limits the number of events that can wait in the queue of this machine,
+    /// either posted or queued by the `EventSender`s, by default the queue is unbounded.
+    ///
+    /// This is the same bound set by `Limits::max_queued_events`.
+    pub fn sender_capacity(self, capacity: usize) -> Self {
+        self.extensions.sender.lock().capacity = Some(capacity);
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized> Machine<'_, S, E, Ctx, F, Ready, A> {
+    /// Returns a handle to queue events to this state machine.
+    pub fn event_sender(&self) -> EventSender<E> {
+        self.extensions.sender.clone()
+    }
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends the events queued by the `EventSender`s and returns the result of each one,
+    /// including the events queued by the actions while pumping.
+    ///
+    /// The senders use the queue of `post`, so this is the same as `process`,
+    /// the events are sent by priority and it stops when the machine is done or paused.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_next(Builder::new("running").on("stop").go_to("idle"))
+    ///     .start("idle");
+    ///
+    /// let sender = sm.event_sender();
+    /// std::thread::spawn(move || {
+    ///     sender.send("start").unwrap();
+    ///     sender.send("stop").unwrap();
+    /// })
+    /// .join()
+    /// .unwrap();
+    ///
+    /// assert_eq!(sm.pump(), vec![Ok("idle"), Ok("running")]);
+    /// ```
+    pub fn pump(&mut self) -> Vec<Result<S, TransitionError>> {
+        self.process()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[test]
+    fn sender_from_action_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::new(0).on('a').go_to(1).action(
+                |cx: ContextMut<i32, char, Vec<char>>| {
+                    cx.context.push(*cx.event);
+                    cx.sender().unwrap().send('b').unwrap();
+                },
+            ))
+            .on_next(
+                Builder::new(1)
+                    .on('b')
+                    .go_to(2)
+                    .action(|cx: ContextMut<i32, char, Vec<char>>| cx.context.push(*cx.event)),
+            )
+            .start(0);
+
+        // The event is queued and not sent during the dispatch
+        sm.send('a').unwrap();
+        assert_eq!(*sm.current(), 1);
+        assert_eq!(sm.event_sender().len(), 1);
+
+        assert_eq!(sm.pump(), vec![Ok(1)]);
+        assert_eq!(*sm.current(), 2);
+        assert_eq!(sm.context(), &['a', 'b']);
+    }
+
+    #[test]
+    fn sender_capacity_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition(0, 'a'))
+            .sender_capacity(2)
+            .start(0);
+
+        let sender = sm.event_sender();
+        assert_eq!(sender.send('a'), Ok(()));
+        assert_eq!(sender.clone().send('a'), Ok(()));
+        assert_eq!(sender.send('a'), Err('a'));
+
+        assert_eq!(sm.pump().len(), 2);
+        assert!(sender.is_empty());
+    }
+
+    #[test]
+    fn sender_shares_post_queue_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::self_transition(0, 'a').action(
+                |cx: ContextMut<i32, char, Vec<char>>| {
+                    cx.context.push(*cx.event);
+                    cx.sender().unwrap().send('c').unwrap();
+                },
+            ))
+            .on_next(
+                Builder::self_transition(0, 'b')
+                    .action(|cx: ContextMut<i32, char, Vec<char>>| cx.context.push(*cx.event)),
+            )
+            .on_next(
+                Builder::self_transition(0, 'c')
+                    .action(|cx: ContextMut<i32, char, Vec<char>>| cx.context.push(*cx.event)),
+            )
+            .start(0);
+
+        // The events of the senders are processed with the posted ones, by priority
+        sm.post('a').unwrap();
+        sm.event_sender().send('b').unwrap();
+        sm.post_with_priority('b', 1).unwrap();
+        assert_eq!(sm.pending(), 3);
+
+        assert_eq!(sm.process().len(), 4);
+        assert_eq!(sm.context(), &['b', 'a', 'b', 'c']);
+        assert!(sm.event_sender().is_empty());
+    }
+
+    #[test]
+    fn pump_without_senders_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition(0, 'a'))
+            .start(0);
+
+        let sender = sm.event_sender();
+        sender.send('a').unwrap();
+        drop(sender);
+
+        assert_eq!(sm.pump(), vec![Ok(0)]);
+        assert!(sm.pump().is_empty());
+
+        // New senders can still be created
+        sm.event_sender().send('a').unwrap();
+        assert_eq!(sm.pump(), vec![Ok(0)]);
+    }
+}
@@ -0,0 +1,516 @@
+use super::{OnTransition, OwnedMachine, Ready};
+use crate::error::{SharedError, TransitionError};
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, TryLockError};
+use std::time::Duration;
+
+// The machine wrapped by a `SharedMachine`.
+type Inner<S, E, Ctx, F> = OwnedMachine<S, E, Ctx, F, Ready>;
+
+thread_local! {
+    // The machines locked by this thread, by the address of their lock.
+    static DISPATCHING: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+// Marks a machine as locked by this thread until it is dropped, also if an action panics.
+struct Dispatching(usize);
+
+impl Dispatching {
+    // Returns `None` if the machine is already locked by this thread.
+    fn enter(id: usize) -> Option<Self> {
+        DISPATCHING.with_borrow_mut(|ids| {
+            if ids.contains(&id) {
+                return None;
+            }
+
+            ids.push(id);
+            Some(Dispatching(id))
+        })
+    }
+}
+
+impl Drop for Dispatching {
+    fn drop(&mut self) {
+        DISPATCHING.with_borrow_mut(|ids| ids.retain(|id| *id != self.0));
+    }
+}
+
+/// A thread-safe handle to a state machine.
+///
+/// Cloning a `SharedMachine` returns a new handle to the same machine.
+///
+/// The actions and hooks of the machine cannot use its handles, which would block forever on the lock,
+/// the methods called from them fail with `TransitionError::ReentrantSend` instead.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// let sm = Machine::with_context_owned(0)
+///     .on_next(
+///         Builder::self_transition((), ()).action(|cx: ContextMut<(), (), i32>| {
+///             *cx.context += 1;
+///         }),
+///     )
+///     .start(());
+///
+/// let shared = SharedMachine::new(sm);
+///
+/// let handles = (0..4)
+///     .map(|_| {
+///         let shared = shared.clone();
+///         std::thread::spawn(move || {
+///             for _ in 0..10 {
+///                 shared.send(()).unwrap();
+///             }
+///         })
+///     })
+///     .collect::<Vec<_>>();
+///
+/// for handle in handles {
+///     handle.join().unwrap();
+/// }
+///
+/// assert_eq!(shared.with_context(|ctx| *ctx).unwrap(), 40);
+/// ```
+pub struct SharedMachine<S, E, Ctx, F = ()> {
+    inner: Arc<Mutex<Inner<S, E, Ctx, F>>>,
+
+    // Notified after each successful transition.
+    changed: Arc<Condvar>,
+}
+
+impl<S, E, Ctx, F> SharedMachine<S, E, Ctx, F> {
+    /// Wraps the given machine into a `SharedMachine`.
+    pub fn new(machine: OwnedMachine<S, E, Ctx, F, Ready>) -> Self {
+        SharedMachine {
+            inner: Arc::new(Mutex::new(machine)),
+            changed: Arc::new(Condvar::new()),
+        }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, Inner<S, E, Ctx, F>>, SharedError> {
+        self.inner.lock().map_err(|_| SharedError::Poisoned)
+    }
+
+    // Fails if called from an action or hook of this machine, which holds its lock.
+    fn dispatching(&self) -> Result<Dispatching, SharedError> {
+        let id = Arc::as_ptr(&self.inner) as usize;
+        Dispatching::enter(id).ok_or(SharedError::Transition(TransitionError::ReentrantSend))
+    }
+}
+
+impl<S, E, Ctx, F> SharedMachine<S, E, Ctx, F>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+{
+    /// Triggers a transition, blocking the current thread until the machine is available.
+    ///
+    /// To send an event from an action after the current transition,
+    /// queue it with `ContextMut::sender` and send the queued events with `SharedMachine::pump`.
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(SharedError): If the transition was not successful or the lock is poisoned.
+    pub fn send(&self, event: E) -> Result<S, SharedError> {
+        let _dispatching = self.dispatching()?;
+        let mut machine = self.lock()?;
+        let prev = machine.send(event)?;
+        self.changed.notify_all();
+        Ok(prev)
+    }
+
+    /// Triggers a transition if the machine is not locked by other handle.
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(SharedError): If the transition was not successful, the machine is locked or the lock is poisoned.
+    pub fn try_send(&self, event: E) -> Result<S, SharedError> {
+        let _dispatching = self.dispatching()?;
+        let mut machine = match self.inner.try_lock() {
+            Ok(machine) => machine,
+            Err(TryLockError::WouldBlock) => return Err(SharedError::WouldBlock),
+            Err(TryLockError::Poisoned(_)) => return Err(SharedError::Poisoned),
+        };
+
+        let prev = machine.send(event)?;
+        self.changed.notify_all();
+        Ok(prev)
+    }
+
+    /// Sends the events queued with `ContextMut::sender` or the `EventSender`s of the machine, see `Machine::pump`.
+    ///
+    /// # Returns
+    /// - Ok(Vec): The result of each queued event, in order.
+    /// - Err(SharedError): If called from an action or hook of the machine, or the lock is poisoned.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new_owned()
+    ///     .on_next(
+    ///         Builder::new("idle")
+    ///             .on("start")
+    ///             .go_to("loading")
+    ///             .action(|cx: ContextMut<&str, &str, ()>| {
+    ///                 cx.sender().unwrap().send("loaded").unwrap();
+    ///             }),
+    ///     )
+    ///     .on_next(Builder::new("loading").on("loaded").go_to("ready"))
+    ///     .start("idle");
+    ///
+    /// let shared = SharedMachine::new(sm);
+    /// shared.send("start").unwrap();
+    /// assert_eq!(shared.pump().unwrap(), [Ok("loading")]);
+    /// assert_eq!(shared.current().unwrap(), "ready");
+    /// ```
+    pub fn pump(&self) -> Result<Vec<Result<S, TransitionError>>, SharedError> {
+        let _dispatching = self.dispatching()?;
+        let mut machine = self.lock()?;
+        let results = machine.pump();
+
+        if results.iter().any(Result::is_ok) {
+            self.changed.notify_all();
+        }
+
+        Ok(results)
+    }
+
+    /// Blocks the current thread until the machine is in the given state,
+    /// returns immediately if the machine is already in the state.
+    ///
+    /// # Returns
+    /// - Ok(()): If the machine is in the state.
+    /// - Err(SharedError::Timeout): If the machine was not in the state before the timeout.
+    /// - Err(SharedError::Poisoned): If the lock is poisoned.
+    pub fn wait_for(&self, state: &S, timeout: Duration) -> Result<(), SharedError> {
+        self.wait_until(timeout, |machine| machine.current() == state)
+    }
+
+    /// Blocks the current thread until the machine is done,
+    /// returns immediately if the machine is already done.
+    pub fn wait_done(&self, timeout: Duration) -> Result<(), SharedError> {
+        self.wait_until(timeout, |machine| machine.is_done())
+    }
+
+    fn wait_until(
+        &self,
+        timeout: Duration,
+        mut done: impl FnMut(&Inner<S, E, Ctx, F>) -> bool,
+    ) -> Result<(), SharedError> {
+        let _dispatching = self.dispatching()?;
+        let machine = self.lock()?;
+
+        // The condition is checked under the lock, so spurious wakeups just wait again
+        let (machine, _) = self
+            .changed
+            .wait_timeout_while(machine, timeout, |machine| !done(machine))
+            .map_err(|_| SharedError::Poisoned)?;
+
+        if done(&machine) {
+            Ok(())
+        } else {
+            Err(SharedError::Timeout)
+        }
+    }
+
+    /// Returns a copy of the current state.
+    pub fn current(&self) -> Result<S, SharedError> {
+        let _dispatching = self.dispatching()?;
+        let machine = self.lock()?;
+        Ok(machine.current().clone())
+    }
+
+    /// Calls the given function with the context of the machine.
+    pub fn with_context<R>(&self, f: impl FnOnce(&Ctx) -> R) -> Result<R, SharedError> {
+        let _dispatching = self.dispatching()?;
+        let machine = self.lock()?;
+        Ok(f(machine.context()))
+    }
+
+    /// Returns `true` if the machine had done executing.
+    pub fn is_done(&self) -> Result<bool, SharedError> {
+        let _dispatching = self.dispatching()?;
+        let machine = self.lock()?;
+        Ok(machine.is_done())
+    }
+}
+
+impl<S, E, Ctx, F> Clone for SharedMachine<S, E, Ctx, F> {
+    fn clone(&self) -> Self {
+        SharedMachine {
+            inner: self.inner.clone(),
+            changed: self.changed.clone(),
+        }
+    }
+}
+
+impl<S, E, Ctx, F> Debug for SharedMachine<S, E, Ctx, F>
+where
+    S: Debug,
+    E: Debug,
+    Ctx: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SharedMachine")
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
+impl<S, E, Ctx, F> From<OwnedMachine<S, E, Ctx, F, Ready>> for SharedMachine<S, E, Ctx, F> {
+    fn from(machine: OwnedMachine<S, E, Ctx, F, Ready>) -> Self {
+        SharedMachine::new(machine)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine, SharedMachine};
+    use crate::error::{SharedError, TransitionError};
+    use std::sync::{Arc, OnceLock};
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    struct Active;
+
+    #[derive(PartialEq, Eq)]
+    enum CountEvent {
+        Increment,
+        Decrement,
+    }
+
+    fn counter() -> SharedMachine<Active, CountEvent, i32> {
+        Machine::with_context_owned(0)
+            .on_next(
+                Builder::self_transition(Active, CountEvent::Increment).action(
+                    |cx: ContextMut<Active, CountEvent, i32>| {
+                        *cx.context += 1;
+                    },
+                ),
+            )
+            .on_next(
+                Builder::self_transition(Active, CountEvent::Decrement).action(
+                    |cx: ContextMut<Active, CountEvent, i32>| {
+                        *cx.context -= 1;
+                    },
+                ),
+            )
+            .start(Active)
+            .into()
+    }
+
+    #[test]
+    fn concurrent_send_test() {
+        let shared = counter();
+
+        let handles = (0..8)
+            .map(|i| {
+                let shared = shared.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..100 {
+                        let event = if i % 2 == 0 {
+                            CountEvent::Increment
+                        } else {
+                            CountEvent::Decrement
+                        };
+
+                        shared.send(CountEvent::Increment).unwrap();
+                        shared.send(event).unwrap();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(shared.with_context(|ctx| *ctx).unwrap(), 800);
+        assert_eq!(shared.current().unwrap(), Active);
+        assert!(!shared.is_done().unwrap());
+    }
+
+    #[test]
+    fn try_send_would_block_test() {
+        let shared = counter();
+        let _guard = shared.inner.lock().unwrap();
+
+        assert_eq!(
+            shared.try_send(CountEvent::Increment),
+            Err(SharedError::WouldBlock)
+        );
+    }
+
+    #[test]
+    fn poisoned_test() {
+        let shared = counter();
+        let shared_clone = shared.clone();
+
+        std::thread::spawn(move || {
+            let _guard = shared_clone.inner.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join()
+        .unwrap_err();
+
+        assert_eq!(
+            shared.send(CountEvent::Increment),
+            Err(SharedError::Poisoned)
+        );
+        assert_eq!(shared.current(), Err(SharedError::Poisoned));
+    }
+
+    #[test]
+    fn transition_error_test() {
+        let shared = Machine::new_owned()
+            .on_next(Builder::new(0).on(()).go_to(1).is_final())
+            .start(0);
+
+        let shared = SharedMachine::new(shared);
+
+        assert_eq!(shared.send(()), Ok(0));
+        assert!(shared.is_done().unwrap());
+        assert_eq!(
+            shared.send(()),
+            Err(SharedError::Transition(TransitionError::Done))
+        );
+    }
+
+    #[test]
+    fn wait_for_test() {
+        let shared: SharedMachine<u32, (), ()> = Machine::new_owned()
+            .on_next(Builder::new(0).on(()).go_to(1))
+            .on_next(Builder::new(1).on(()).go_to(2).is_final())
+            .start(0)
+            .into();
+
+        // Already in the state
+        shared.wait_for(&0, Duration::ZERO).unwrap();
+        assert_eq!(
+            shared.wait_done(Duration::from_millis(10)),
+            Err(SharedError::Timeout)
+        );
+
+        let producer = shared.clone();
+        let handle = std::thread::spawn(move || {
+            for _ in 0..2 {
+                std::thread::sleep(Duration::from_millis(5));
+                producer.send(()).unwrap();
+            }
+        });
+
+        shared.wait_for(&2, Duration::from_secs(10)).unwrap();
+        shared.wait_done(Duration::from_secs(10)).unwrap();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn reentrant_send_test() {
+        let handle = Arc::new(OnceLock::<SharedMachine<u8, u8, Vec<SharedError>>>::new());
+        let inner = handle.clone();
+
+        let shared = Machine::with_context_owned(Vec::new())
+            .on_next(Builder::new(0).on(0).go_to(1).action(
+                move |cx: ContextMut<u8, u8, Vec<SharedError>>| {
+                    let shared = inner.get().unwrap();
+                    cx.context.extend(shared.send(1).err());
+                    cx.context.extend(shared.try_send(1).err());
+                },
+            ))
+            .on_next(Builder::new(1).on(1).go_to(0))
+            .start(0)
+            .into();
+
+        handle.set(shared).unwrap();
+        let shared = handle.get().unwrap();
+
+        assert_eq!(shared.send(0), Ok(0));
+        let reentrant = SharedError::Transition(TransitionError::ReentrantSend);
+        assert_eq!(
+            shared.with_context(|errors| errors.clone()).unwrap(),
+            [reentrant.clone(), reentrant]
+        );
+
+        // The machine is not dispatching after the transition
+        assert_eq!(shared.send(1), Ok(1));
+    }
+
+    #[test]
+    fn reentrant_read_test() {
+        let handle = Arc::new(OnceLock::<SharedMachine<u8, u8, Vec<SharedError>>>::new());
+        let inner = handle.clone();
+
+        let shared = Machine::with_context_owned(Vec::new())
+            .on_next(Builder::new(0).on(0).go_to(1).action(
+                move |cx: ContextMut<u8, u8, Vec<SharedError>>| {
+                    let shared = inner.get().unwrap();
+                    cx.context.extend(shared.current().err());
+                    cx.context.extend(shared.is_done().err());
+                    cx.context.extend(shared.with_context(|_| ()).err());
+                    cx.context.extend(shared.wait_for(&0, Duration::ZERO).err());
+                    cx.context.extend(shared.wait_done(Duration::ZERO).err());
+                    cx.context.extend(shared.pump().err());
+                },
+            ))
+            .start(0)
+            .into();
+
+        handle.set(shared).unwrap();
+        let shared = handle.get().unwrap();
+
+        assert_eq!(shared.send(0), Ok(0));
+        let errors = shared.with_context(|errors| errors.clone()).unwrap();
+        assert_eq!(
+            errors,
+            vec![SharedError::Transition(TransitionError::ReentrantSend); 6]
+        );
+    }
+
+    #[test]
+    fn pump_test() {
+        let shared: SharedMachine<u8, u8, ()> = Machine::new_owned()
+            .on_next(
+                Builder::new(0)
+                    .on(0)
+                    .go_to(1)
+                    .action(|cx: ContextMut<u8, u8, ()>| cx.sender().unwrap().send(1).unwrap()),
+            )
+            .on_next(Builder::new(1).on(1).go_to(2).is_final())
+            .start(0)
+            .into();
+
+        let waiter = shared.clone();
+        let handle = std::thread::spawn(move || waiter.wait_done(Duration::from_secs(10)));
+
+        assert_eq!(shared.send(0), Ok(0));
+        assert_eq!(shared.pump().unwrap(), [Ok(1)]);
+        assert_eq!(shared.current(), Ok(2));
+        handle.join().unwrap().unwrap();
+
+        // The event is not sent again
+        assert!(shared.pump().unwrap().is_empty());
+    }
+
+    #[test]
+    fn reentrant_flag_cleared_on_panic_test() {
+        let shared: SharedMachine<u8, u8, ()> = Machine::new_owned()
+            .on_next(
+                Builder::self_transition(0, 0).action(|_: ContextMut<u8, u8, ()>| panic!("boom")),
+            )
+            .start(0)
+            .into();
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| shared.send(0)));
+        assert!(result.is_err());
+
+        // The stale flag would report a reentrant send
+        assert_eq!(shared.send(0), Err(SharedError::Poisoned));
+    }
+}
@@ -0,0 +1,155 @@
+use super::{Machine, Ready};
+
+/// The result of simulating a sequence of events with `Machine::simulate`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationResult<S> {
+    /// The state reached after the accepted events.
+    pub state: S,
+
+    /// Whether a final transition was taken during the simulation.
+    pub is_final: bool,
+
+    /// The index of the first rejected event, if any.
+    pub rejected: Option<usize>,
+}
+
+impl<S> SimulationResult<S> {
+    /// Returns `true` if all the events were accepted.
+    pub fn is_accepted(&self) -> bool {
+        self.rejected.is_none()
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+{
+    /// Returns `true` if the given sequence of events would be accepted from the current state.
+    ///
+    /// This don't run any action or modify the machine.
+    pub fn accepts(&self, events: &[E]) -> bool {
+        self.simulate(events).is_accepted()
+    }
+
+    /// Walks the transitions of the given sequence of events from the current state,
+    /// without running any action or modifying the machine.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new(0).on('a').go_to(1))
+    ///     .on_next(Builder::new(1).on('b').go_to(2).is_final())
+    ///     .start(0);
+    ///
+    /// let result = sm.simulate(&['a', 'b', 'a']);
+    ///
+    /// assert_eq!(result.state, 2);
+    /// assert!(result.is_final);
+    /// assert_eq!(result.rejected, Some(2));
+    /// assert_eq!(*sm.current(), 0);
+    /// ```
+    pub fn simulate(&self, events: &[E]) -> SimulationResult<S> {
+        let mut state = self.current.as_ref().unwrap();
+        let mut done = self.done;
+        let mut is_final = false;
+        let mut rejected = None;
+
+        for (index, event) in events.iter().enumerate() {
+            if done {
+                rejected = Some(index);
+                break;
+            }
+
+            match self.transitions.get(event, state) {
+                Some(next) => {
+                    state = &next.next;
+
+                    if next.is_final {
+                        done = true;
+                        is_final = true;
+                    }
+                }
+                None => {
+                    rejected = Some(index);
+                    break;
+                }
+            }
+        }
+
+        SimulationResult {
+            state: state.clone(),
+            is_final,
+            rejected,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Locked,
+        Unlocked,
+        Broken,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Coin,
+        Push,
+        Kick,
+    }
+
+    #[test]
+    fn simulate_test() {
+        let mut value = 0;
+
+        {
+            let sm = Machine::new()
+                .on_next(
+                    Builder::new(State::Locked)
+                        .on(Event::Coin)
+                        .go_to(State::Unlocked)
+                        .action(|_: ContextMut<_, _, _>| value += 1),
+                )
+                .on_next(
+                    Builder::new(State::Unlocked)
+                        .on(Event::Push)
+                        .go_to(State::Locked),
+                )
+                .on_next(
+                    Builder::new(State::Locked)
+                        .on(Event::Kick)
+                        .go_to(State::Broken)
+                        .is_final(),
+                )
+                .on_transition(|_| panic!("on_transition must not be called"))
+                .start(State::Locked);
+
+            assert!(sm.accepts(&[]));
+            assert!(sm.accepts(&[Event::Coin, Event::Push, Event::Coin]));
+            assert!(!sm.accepts(&[Event::Push]));
+
+            let result = sm.simulate(&[Event::Coin, Event::Push, Event::Kick]);
+            assert_eq!(result.state, State::Broken);
+            assert!(result.is_final);
+            assert!(result.is_accepted());
+
+            let result = sm.simulate(&[Event::Coin, Event::Kick, Event::Push]);
+            assert_eq!(result.state, State::Unlocked);
+            assert!(!result.is_final);
+            assert_eq!(result.rejected, Some(1));
+
+            assert_eq!(*sm.current(), State::Locked);
+            assert!(!sm.is_done());
+        }
+
+        assert_eq!(value, 0);
+    }
+}
@@ -0,0 +1,411 @@
+use super::extensions::Extensions;
+use super::machine::{insert_next, split};
+use super::{Build, IntoTransitions, Machine};
+use crate::error::{BuildError, LimitKind};
+
+/// The maximum size of a state machine, set with `Machine::limits`.
+///
+/// Each limit is `None` by default, which doesn't limit the size.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+/// use restate::error::{BuildError, LimitKind};
+///
+/// let sm = Machine::new()
+///     .limits(Limits {
+///         max_states: Some(2),
+///         ..Limits::default()
+///     })
+///     .try_on_next(Builder::new("a").on(1).go_to("b"))
+///     .unwrap();
+///
+/// let err = sm.try_on_next(Builder::new("b").on(1).go_to("c")).unwrap_err();
+/// assert_eq!(
+///     err,
+///     BuildError::LimitExceeded {
+///         which: LimitKind::States,
+///         limit: 2,
+///         actual: 3
+///     }
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Limits {
+    /// The maximum number of distinct states, counting the source and target states of the transitions.
+    pub max_states: Option<usize>,
+
+    /// The maximum number of transitions, counting the guarded candidates and the ignored events.
+    pub max_transitions: Option<usize>,
+
+    /// The maximum number of distinct events.
+    pub max_events: Option<usize>,
+
+    /// The maximum number of events waiting in the queue of the machine,
+    /// which is shared by `Machine::post` and the `EventSender`s.
+    pub max_queued_events: Option<usize>,
+}
+
+// The size of a machine with `Limits`, counted on each insert so checking a transition doesn't scan the others.
+//
+// The states and events are only kept if they are limited, and stay counted after their transitions are removed.
+pub(crate) struct Sizes<S, E> {
+    pub(crate) limits: Limits,
+    transitions: usize,
+    states: Vec<S>,
+    events: Vec<E>,
+
+    // Selected in `Machine::limits`, where the states and events are known to be `Clone`.
+    clone_state: fn(&S) -> S,
+    clone_event: fn(&E) -> E,
+}
+
+// The states and the event that a transition adds to the `Sizes`.
+pub(crate) struct Added<S, E> {
+    states: [Option<S>; 2],
+    event: Option<E>,
+}
+
+impl<S, E> Sizes<S, E>
+where
+    S: PartialEq,
+    E: PartialEq,
+{
+    // Returns the states and the event of the transition that are not counted yet.
+    pub(crate) fn added(&self, event: &E, from: &S, to: &S) -> Added<S, E> {
+        let mut added = Added {
+            states: [None, None],
+            event: None,
+        };
+
+        if self.limits.max_states.is_some() {
+            let is_new = |state: &S| !self.states.contains(state);
+            added.states[0] = is_new(from).then(|| (self.clone_state)(from));
+            added.states[1] = (to != from && is_new(to)).then(|| (self.clone_state)(to));
+        }
+
+        if self.limits.max_events.is_some() && !self.events.contains(event) {
+            added.event = Some((self.clone_event)(event));
+        }
+
+        added
+    }
+
+    // Checks that adding the transition doesn't exceed the limits.
+    pub(crate) fn check(&self, added: &Added<S, E>) -> Result<(), BuildError> {
+        if let Some(limit) = self.limits.max_transitions {
+            exceeds(LimitKind::Transitions, limit, self.transitions + 1)?;
+        }
+
+        if let Some(limit) = self.limits.max_states {
+            let actual = self.states.len() + added.states.iter().flatten().count();
+            exceeds(LimitKind::States, limit, actual)?;
+        }
+
+        if let Some(limit) = self.limits.max_events {
+            let actual = self.events.len() + usize::from(added.event.is_some());
+            exceeds(LimitKind::Events, limit, actual)?;
+        }
+
+        Ok(())
+    }
+
+    // Counts a transition which was added.
+    pub(crate) fn add(&mut self, added: Added<S, E>) {
+        self.transitions += 1;
+        self.states.extend(added.states.into_iter().flatten());
+        self.events.extend(added.event);
+    }
+}
+
+impl<S: PartialEq, E: PartialEq, Ctx> Extensions<'_, S, E, Ctx> {
+    // Checks that adding the transition doesn't exceed the limits, returns what it adds to the sizes.
+    pub(crate) fn check_limits(
+        &self,
+        event: &E,
+        from: &S,
+        to: &S,
+    ) -> Result<Option<Added<S, E>>, BuildError> {
+        let Some(sizes) = &self.sizes else {
+            return Ok(None);
+        };
+
+        let added = sizes.added(event, from, to);
+        sizes.check(&added)?;
+        Ok(Some(added))
+    }
+
+    // Returns what adding the transition adds to the sizes, without checking the limits.
+    pub(crate) fn unchecked_limits(&self, event: &E, from: &S, to: &S) -> Option<Added<S, E>> {
+        let sizes = self.sizes.as_ref()?;
+        Some(sizes.added(event, from, to))
+    }
+
+    // Counts a transition added after `check_limits`.
+    pub(crate) fn count_added(&mut self, added: Option<Added<S, E>>) {
+        if let (Some(sizes), Some(added)) = (self.sizes.as_mut(), added) {
+            sizes.add(added);
+        }
+    }
+
+    // Stops counting the transitions which were removed.
+    pub(crate) fn count_removed(&mut self, count: usize) {
+        if let Some(sizes) = self.sizes.as_mut() {
+            sizes.transitions -= count;
+        }
+    }
+}
+
+fn exceeds(which: LimitKind, limit: usize, actual: usize) -> Result<(), BuildError> {
+    match actual > limit {
+        true => Err(BuildError::LimitExceeded {
+            which,
+            limit,
+            actual,
+        }),
+        false => Ok(()),
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C>
+where
+    S: PartialEq + Clone,
+    E: PartialEq + Clone,
+{
+    /// Limits the size of this state machine, the transitions exceeding the limits are rejected when added,
+    /// and the events exceeding `max_queued_events` when posted or queued in an `EventSender`.
+    ///
+    /// The limits are also checked by `add_transition` after the machine starts.
+    /// The states and events stay counted after their transitions are removed.
+    pub fn limits(mut self, limits: Limits) -> Self {
+        if let Some(capacity) = limits.max_queued_events {
+            self.extensions.sender.lock().capacity = Some(capacity);
+        }
+
+        let mut sizes = Sizes {
+            limits,
+            transitions: 0,
+            states: Vec::new(),
+            events: Vec::new(),
+            clone_state: S::clone,
+            clone_event: E::clone,
+        };
+
+        // The transitions added before are counted but not checked
+        for (from, event, first) in self.transitions.iter() {
+            for next in first.candidates() {
+                let added = sizes.added(event, from, &next.next);
+                sizes.add(added);
+            }
+        }
+
+        self.extensions.sizes = Some(sizes);
+        self
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized, C> Machine<'a, S, E, Ctx, (), Build, A, C>
+where
+    E: PartialEq,
+    S: PartialEq,
+{
+    /// Adds a transition from a state to other based on an event,
+    /// or one transition per source state for `Builder::from_states`.
+    ///
+    /// # Returns
+    /// - Ok(Self): If the transitions were added.
+    /// - Err(BuildError::DuplicateTransition): If a transition already exists for the event from the state,
+    ///   and any of them is not guarded.
+    /// - Err(BuildError::LimitExceeded): If adding a transition exceeds the `Machine::limits`.
+    pub fn try_on_next(
+        mut self,
+        transitions: impl IntoTransitions<'a, S, E, Ctx, A>,
+    ) -> Result<Self, BuildError> {
+        for transition in transitions.into_transitions() {
+            let (event, from, next) = split(transition);
+
+            let added = self.extensions.check_limits(&event, &from, &next.next)?;
+
+            if let Some(invariants) = self.extensions.invariants.as_mut() {
+                invariants.check_insert(self.transitions.states(), &from);
+            }
+
+            insert_next(&mut self.transitions, event, from, next)
+                .map_err(|_| BuildError::DuplicateTransition)?;
+
+            self.extensions.count_added(added);
+        }
+
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Limits;
+    use crate::blocking::{Builder, Machine};
+    use crate::error::{BuildError, LimitKind};
+    use std::time::Duration;
+
+    fn exceeded(which: LimitKind, limit: usize, actual: usize) -> BuildError {
+        BuildError::LimitExceeded {
+            which,
+            limit,
+            actual,
+        }
+    }
+
+    #[test]
+    fn unlimited_test() {
+        let mut sm = Machine::new();
+
+        for i in 0..100 {
+            sm = sm.try_on_next(Builder::new(i).on(i).go_to(i + 1)).unwrap();
+        }
+
+        let mut sm = sm.start(0);
+
+        for i in 0..100 {
+            sm.post(i).unwrap();
+        }
+
+        assert_eq!(sm.pending(), 100);
+        assert_eq!(sm.transitions().count(), 100);
+    }
+
+    #[test]
+    fn max_states_test() {
+        let sm = Machine::new()
+            .limits(Limits {
+                max_states: Some(3),
+                ..Limits::default()
+            })
+            .try_on_next(Builder::new('a').on(0).go_to('b'))
+            .and_then(|sm| sm.try_on_next(Builder::new('b').on(0).go_to('c')))
+            .and_then(|sm| sm.try_on_next(Builder::new('c').on(0).go_to('a')))
+            .unwrap();
+
+        let err = sm.try_on_next(Builder::new('c').on(1).go_to('d'));
+        assert_eq!(err.unwrap_err(), exceeded(LimitKind::States, 3, 4));
+    }
+
+    #[test]
+    fn max_transitions_test() {
+        let limits = Limits {
+            max_transitions: Some(2),
+            ..Limits::default()
+        };
+
+        let guarded = |to| {
+            Builder::new('a')
+                .on(0)
+                .go_to(to)
+                .guard_after(Duration::ZERO)
+        };
+        let sm = Machine::new()
+            .limits(limits)
+            .try_on_next(guarded('b'))
+            .and_then(|sm| sm.try_on_next(guarded('c')))
+            .unwrap();
+
+        let err = sm.try_on_next(guarded('d'));
+        assert_eq!(err.unwrap_err(), exceeded(LimitKind::Transitions, 2, 3));
+
+        let mut sm = Machine::new()
+            .limits(limits)
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .start('a');
+
+        sm.add_transition(Builder::new('b').on(0).go_to('a'))
+            .unwrap();
+        assert_eq!(
+            sm.add_transition(Builder::new('b').on(1).go_to('a')),
+            Err(exceeded(LimitKind::Transitions, 2, 3))
+        );
+    }
+
+    #[test]
+    fn max_events_test() {
+        let sm = Machine::new()
+            .limits(Limits {
+                max_events: Some(1),
+                ..Limits::default()
+            })
+            .try_on_next(Builder::new('a').on(0).go_to('b'))
+            .and_then(|sm| sm.try_on_next(Builder::new('b').on(0).go_to('c')))
+            .unwrap();
+
+        let err = sm.try_on_next(Builder::new('b').on(1).go_to('a'));
+        assert_eq!(err.unwrap_err(), exceeded(LimitKind::Events, 1, 2));
+    }
+
+    #[test]
+    fn counted_sizes_test() {
+        let limits = Limits {
+            max_transitions: Some(2),
+            max_states: Some(3),
+            ..Limits::default()
+        };
+
+        // The transitions added before the limits are counted
+        let mut sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .ignore('b', 1)
+            .limits(limits)
+            .start('a');
+
+        assert_eq!(
+            sm.add_transition(Builder::new('b').on(0).go_to('a')),
+            Err(exceeded(LimitKind::Transitions, 2, 3))
+        );
+
+        // A removed transition is not counted, but its states are
+        assert!(sm.remove_transition(&'b', &1));
+        sm.add_transition(Builder::new('b').on(0).go_to('c'))
+            .unwrap();
+
+        sm.remove_transition(&'b', &0);
+        assert_eq!(
+            sm.add_transition(Builder::new('b').on(0).go_to('d')),
+            Err(exceeded(LimitKind::States, 3, 4))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "the number of states exceeds the limit of 1")]
+    fn on_next_limit_test() {
+        let _ = Machine::new()
+            .limits(Limits {
+                max_states: Some(1),
+                ..Limits::default()
+            })
+            .on_next(Builder::new('a').on(0).go_to('b'));
+    }
+
+    #[test]
+    fn max_queued_events_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition('a', 0))
+            .limits(Limits {
+                max_queued_events: Some(2),
+                ..Limits::default()
+            })
+            .start('a');
+
+        let sender = sm.event_sender();
+
+        sm.post(0).unwrap();
+        sm.post_with_priority(0, 1).unwrap();
+        assert_eq!(sm.post(0), Err(0));
+        assert_eq!(sm.pending(), 2);
+
+        // The senders share the bound of the posted events
+        assert_eq!(sender.send(0), Err(0));
+
+        sm.process();
+        sender.send(0).unwrap();
+        sm.post(0).unwrap();
+        assert_eq!(sm.pending(), 2);
+    }
+}
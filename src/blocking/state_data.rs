@@ -0,0 +1,232 @@
+use super::{Build, ContextMut, Machine, Ready};
+use std::any::Any;
+
+/// When the data of a state is reset to its initial value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DataReset {
+    /// The data persists while the machine runs.
+    #[default]
+    Never,
+
+    /// The data is reset each time the machine leaves the state, self transitions don't reset the data.
+    OnExit,
+}
+
+type Init = Box<dyn Fn() -> Box<dyn Any + Send + Sync> + Send + Sync>;
+
+struct Entry<S> {
+    state: S,
+    value: Box<dyn Any + Send + Sync>,
+    init: Option<Init>,
+}
+
+// The data associated to each state of a machine.
+pub(crate) struct StateData<S> {
+    entries: Vec<Entry<S>>,
+}
+
+impl<S> StateData<S> {
+    pub(crate) fn new() -> Self {
+        StateData {
+            entries: Vec::new(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+impl<S: PartialEq> StateData<S> {
+    fn get<T: 'static>(&self, state: &S) -> Option<&T> {
+        self.entries
+            .iter()
+            .filter(|entry| &entry.state == state)
+            .find_map(|entry| entry.value.downcast_ref())
+    }
+
+    fn get_mut<T: 'static>(&mut self, state: &S) -> Option<&mut T> {
+        self.entries
+            .iter_mut()
+            .filter(|entry| &entry.state == state)
+            .find_map(|entry| entry.value.downcast_mut())
+    }
+
+    // Resets the data of a state the machine left.
+    pub(crate) fn exit(&mut self, state: &S) {
+        for entry in self.entries.iter_mut().filter(|e| &e.state == state) {
+            if let Some(init) = &entry.init {
+                entry.value = init();
+            }
+        }
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Associates a value to the given state, that persists while the machine runs.
+    ///
+    /// The value is accessible from the actions with `ContextMut::state_data` and `ContextMut::state_data_mut`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(
+    ///         Builder::self_transition("retrying", "fail").action(|mut cx: ContextMut<_, _, _>| {
+    ///             *cx.state_data_mut::<u32>().unwrap() += 1;
+    ///         }),
+    ///     )
+    ///     .state_data("retrying", 0_u32)
+    ///     .start("retrying");
+    ///
+    /// sm.send("fail").unwrap();
+    /// sm.send("fail").unwrap();
+    /// assert_eq!(sm.state_data::<u32>(&"retrying"), Some(&2));
+    /// ```
+    pub fn state_data<T>(self, state: S, initial_value: T) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        self.state_data_with(state, initial_value, DataReset::Never)
+    }
+
+    /// Associates a value to the given state, that is reset as specified by `reset`.
+    pub fn state_data_with<T>(mut self, state: S, initial_value: T, reset: DataReset) -> Self
+    where
+        T: Clone + Send + Sync + 'static,
+    {
+        let value = Box::new(initial_value.clone());
+        let init =
+            match reset {
+                DataReset::Never => None,
+                DataReset::OnExit => Some(Box::new(move || {
+                    Box::new(initial_value.clone()) as Box<dyn Any + Send + Sync>
+                }) as Init),
+            };
+
+        self.extensions
+            .state_data
+            .entries
+            .push(Entry { state, value, init });
+
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C>
+where
+    S: PartialEq,
+{
+    /// Returns the data of type `T` associated to the given state, if any.
+    pub fn state_data<T: 'static>(&self, state: &S) -> Option<&T> {
+        self.extensions.state_data.get(state)
+    }
+}
+
+impl<S, E, Ctx> ContextMut<'_, S, E, Ctx>
+where
+    S: PartialEq,
+{
+    /// Returns the data of type `T` associated to the state this transition ends, if any.
+    pub fn state_data<T: 'static>(&self) -> Option<&T> {
+        self.state_data.as_ref()?.get(self.to)
+    }
+
+    /// Returns a mutable reference to the data of type `T` associated to the state this transition ends, if any.
+    pub fn state_data_mut<T: 'static>(&mut self) -> Option<&mut T> {
+        self.state_data.as_mut()?.get_mut(self.to)
+    }
+
+    /// Returns the data of type `T` associated to the given state, if any.
+    pub fn state_data_of<T: 'static>(&self, state: &S) -> Option<&T> {
+        self.state_data.as_ref()?.get(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DataReset;
+    use crate::blocking::{Builder, ContextMut, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Connecting,
+        Retrying,
+        Connected,
+    }
+
+    fn retry(mut cx: ContextMut<State, &'static str, Vec<u32>>) {
+        let attempts = cx.state_data_mut::<u32>().unwrap();
+        *attempts += 1;
+        let attempts = *attempts;
+        cx.context.push(attempts);
+    }
+
+    fn machine(reset: DataReset) -> Machine<'static, State, &'static str, Vec<u32>, ()> {
+        Machine::with_context(Vec::new())
+            .on_next(
+                Builder::new(State::Connecting)
+                    .on("fail")
+                    .go_to(State::Retrying)
+                    .action(retry),
+            )
+            .on_next(Builder::self_transition(State::Retrying, "fail").action(retry))
+            .on_next(
+                Builder::new(State::Retrying)
+                    .on("retry")
+                    .go_to(State::Connecting),
+            )
+            .on_next(
+                Builder::new(State::Connecting)
+                    .on("ok")
+                    .go_to(State::Connected),
+            )
+            .state_data_with(State::Retrying, 0_u32, reset)
+    }
+
+    #[test]
+    fn state_data_persistent_test() {
+        let mut sm = machine(DataReset::Never).start(State::Connecting);
+
+        for event in ["fail", "fail", "retry", "fail"] {
+            sm.send(event).unwrap();
+        }
+
+        assert_eq!(sm.context(), &[1, 2, 3]);
+        assert_eq!(sm.state_data::<u32>(&State::Retrying), Some(&3));
+    }
+
+    #[test]
+    fn state_data_reset_on_exit_test() {
+        let mut sm = machine(DataReset::OnExit).start(State::Connecting);
+
+        for event in ["fail", "fail", "retry", "fail"] {
+            sm.send(event).unwrap();
+        }
+
+        assert_eq!(sm.context(), &[1, 2, 1]);
+    }
+
+    #[test]
+    fn state_data_missing_test() {
+        let mut sm = Machine::with_context(None)
+            .on_next(
+                Builder::new(State::Connecting)
+                    .on("ok")
+                    .go_to(State::Connected)
+                    .action(|cx: ContextMut<State, &str, Option<bool>>| {
+                        *cx.context = Some(cx.state_data::<u32>().is_none());
+                    }),
+            )
+            .state_data(State::Retrying, 0_u32)
+            .start(State::Connecting);
+
+        sm.send("ok").unwrap();
+        assert_eq!(*sm.context(), Some(true));
+        assert_eq!(sm.state_data::<u32>(&State::Connected), None);
+        assert_eq!(sm.state_data::<String>(&State::Retrying), None);
+        assert_eq!(sm.state_data::<u32>(&State::Retrying), Some(&0));
+    }
+}
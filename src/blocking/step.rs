@@ -0,0 +1,119 @@
+use super::machine::find_next;
+use super::{Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+
+/// The result of a successful transition, returned by `Machine::step`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionOutcome<S> {
+    /// The state before the transition.
+    pub previous: S,
+
+    /// The state after the transition.
+    pub current: S,
+
+    /// Whether the transition completed the state machine.
+    pub finished: bool,
+
+    /// Whether the transition started and ended in the same state.
+    pub was_self_transition: bool,
+
+    /// Whether the event was dropped without a transition, see `Machine::ignore`.
+    pub was_ignored: bool,
+
+    /// Whether the event was dropped because its idempotency key was seen, see `Machine::dedupe_by`.
+    pub was_duplicate: bool,
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Triggers a transition and returns the states before and after it.
+    ///
+    /// The actions and hooks run the same as with `send`, which only returns the previous state.
+    ///
+    /// # Returns
+    /// - Ok(TransitionOutcome<S>): The result of the transition.
+    /// - Err(TransitionError): If the transition was not successful
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_next(Builder::new("running").on("stop").go_to("stopped").is_final())
+    ///     .start("idle");
+    ///
+    /// let outcome = sm.step("start").unwrap();
+    /// assert_eq!(outcome.previous, "idle");
+    /// assert_eq!(outcome.current, "running");
+    /// assert!(!outcome.finished);
+    ///
+    /// assert!(sm.step("stop").unwrap().finished);
+    /// ```
+    pub fn step(&mut self, event: E) -> Result<TransitionOutcome<S>, TransitionError> {
+        let was_ignored = find_next(
+            &mut self.transitions,
+            self.extensions.match_by_discriminant,
+            &mut self.extensions.node_hint,
+            &event,
+            self.current.as_ref().unwrap(),
+        )
+        .is_some_and(|next| next.ignored);
+        let was_duplicate = self.is_duplicate(&event);
+
+        let previous = self.send_ref(&event)?;
+        let current = self.current().clone();
+
+        Ok(TransitionOutcome {
+            was_self_transition: previous == current,
+            finished: self.done,
+            was_ignored: was_ignored && !was_duplicate,
+            was_duplicate,
+            previous,
+            current,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransitionOutcome;
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::TransitionError;
+
+    fn count(cx: ContextMut<char, u8, i32>) {
+        *cx.context += 1;
+    }
+
+    #[test]
+    fn step_test() {
+        let mut sm = Machine::with_context(0)
+            .on_next(Builder::new('a').on(0).go_to('b').action(count))
+            .on_next(Builder::self_transition('b', 1).action(count))
+            .on_next(Builder::new('b').on(2).go_to('c').is_final().action(count))
+            .on_transition(|cx| assert!(*cx.context > 0))
+            .start('a');
+
+        let outcome = |previous, current, finished, was_self_transition| TransitionOutcome {
+            previous,
+            current,
+            finished,
+            was_self_transition,
+            was_ignored: false,
+            was_duplicate: false,
+        };
+
+        assert_eq!(sm.step(0), Ok(outcome('a', 'b', false, false)));
+        assert_eq!(sm.step(1), Ok(outcome('b', 'b', false, true)));
+        assert_eq!(sm.step(9), Err(TransitionError::InvalidTransition));
+        assert_eq!(sm.step(2), Ok(outcome('b', 'c', true, false)));
+        assert_eq!(sm.step(2), Err(TransitionError::Done));
+        assert_eq!(*sm.context(), 3);
+    }
+}
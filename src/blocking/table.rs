@@ -0,0 +1,215 @@
+use super::{BoxedAction, Build, Builder, Machine, OnAction};
+use crate::error::UnknownTransition;
+
+/// The structure of a state machine as data, a list of `(from, event, to, is_final)` rows.
+///
+/// A table doesn't contain the actions, hooks, guards nor limits of the machine,
+/// the actions can be attached after with `Machine::set_action`.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// let sm = Machine::new()
+///     .on_next(Builder::new("idle").on("start").go_to("running"))
+///     .on_next(Builder::new("running").on("stop").go_to("stopped").is_final());
+///
+/// let table = sm.to_table();
+/// assert_eq!(table.rows()[1], ("running", "stop", "stopped", true));
+/// assert!(Machine::from_table(table.clone()).diff(&sm).is_empty());
+///
+/// let mut copy = Machine::from_table(table);
+/// copy.set_action(&"idle", &"start", |cx: ContextMut<&str, &str, ()>| {
+///     println!("{} -> {}", cx.from, cx.to);
+/// })
+/// .unwrap();
+///
+/// assert_eq!(copy.to_table(), sm.to_table());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionTable<S, E> {
+    rows: Vec<(S, E, S, bool)>,
+}
+
+impl<S, E> TransitionTable<S, E> {
+    /// Returns an empty table.
+    pub fn new() -> Self {
+        TransitionTable { rows: Vec::new() }
+    }
+
+    /// Adds a row to this table.
+    pub fn push(&mut self, from: S, event: E, to: S, is_final: bool) {
+        self.rows.push((from, event, to, is_final));
+    }
+
+    /// Returns the rows of this table.
+    pub fn rows(&self) -> &[(S, E, S, bool)] {
+        &self.rows
+    }
+
+    /// Returns the number of rows.
+    pub fn len(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns `true` if there are no rows.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+}
+
+impl<S, E> Default for TransitionTable<S, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, E> FromIterator<(S, E, S, bool)> for TransitionTable<S, E> {
+    fn from_iter<I: IntoIterator<Item = (S, E, S, bool)>>(iter: I) -> Self {
+        TransitionTable {
+            rows: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<S, E> IntoIterator for TransitionTable<S, E> {
+    type Item = (S, E, S, bool);
+    type IntoIter = std::vec::IntoIter<(S, E, S, bool)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.into_iter()
+    }
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: Clone,
+    E: Clone,
+{
+    /// Returns the structure of this state machine as a table.
+    ///
+    /// The ignored events are not part of the table, and only the first of the guarded candidates is.
+    pub fn to_table(&self) -> TransitionTable<S, E> {
+        self.transitions
+            .iter()
+            .filter(|(_, _, next)| !next.ignored)
+            .map(|(from, event, next)| {
+                (
+                    from.clone(),
+                    event.clone(),
+                    next.next.clone(),
+                    next.is_final,
+                )
+            })
+            .collect()
+    }
+}
+
+impl<'a, S, E> Machine<'a, S, E, (), (), Build>
+where
+    E: PartialEq,
+    S: PartialEq,
+{
+    /// Returns a new `StateMachine` with the transitions of the table and no actions.
+    ///
+    /// # Panics
+    /// If the table has many rows for the same event from the same state.
+    pub fn from_table(table: TransitionTable<S, E>) -> Self {
+        Machine::new().on_table(table)
+    }
+}
+
+impl<'a, S, E, Ctx, A: ?Sized, C> Machine<'a, S, E, Ctx, (), Build, A, C>
+where
+    E: PartialEq,
+    S: PartialEq,
+{
+    /// Adds the transitions of the table without actions.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state.
+    pub fn on_table(self, table: TransitionTable<S, E>) -> Self {
+        table
+            .into_iter()
+            .fold(self, |machine, (from, event, to, is_final)| {
+                let builder = Builder::new(from).on(event).go_to(to);
+                match is_final {
+                    true => machine.on_next(builder.is_final()),
+                    false => machine.on_next(builder),
+                }
+            })
+    }
+}
+
+impl<'a, S, E, Ctx, F, Step, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Step, A, C>
+where
+    E: PartialEq,
+    S: PartialEq,
+{
+    /// Sets the action of the transition for the event from the state, replacing its current action.
+    ///
+    /// # Returns
+    /// - Ok(()): If the action was set.
+    /// - Err(UnknownTransition): If there is no transition for the event from the state.
+    pub fn set_action<G>(&mut self, from: &S, event: &E, action: G) -> Result<(), UnknownTransition>
+    where
+        G: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, G, S, E, Ctx>,
+    {
+        let next = self
+            .transitions
+            .get_mut(event, from)
+            .ok_or(UnknownTransition)?;
+
+        next.action = Some(A::boxed(action));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TransitionTable;
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::UnknownTransition;
+
+    fn count(cx: ContextMut<char, u8, i32>) {
+        *cx.context += 1;
+    }
+
+    #[test]
+    fn table_round_trip_test() {
+        let sm = Machine::with_context(0)
+            .on_next(Builder::new('a').on(0).go_to('b').action(count))
+            .on_next(Builder::new('b').on(1).go_to('c').is_final())
+            .on_next(Builder::self_transition('b', 2))
+            .ignore('c', 3);
+
+        let table = sm.to_table();
+        assert_eq!(
+            table.rows(),
+            [
+                ('a', 0, 'b', false),
+                ('b', 1, 'c', true),
+                ('b', 2, 'b', false)
+            ]
+        );
+
+        let mut copy = Machine::with_context(0).on_table(table.clone());
+        assert_eq!(copy.to_table(), table);
+
+        copy.set_action(&'a', &0, count).unwrap();
+        assert_eq!(copy.set_action(&'c', &0, count), Err(UnknownTransition));
+
+        let mut copy = copy.start('a');
+        copy.send(0).unwrap();
+        copy.send(1).unwrap();
+        assert_eq!(*copy.context(), 1);
+        assert!(copy.is_done());
+
+        let table = [(1, 'x', 2, false)]
+            .into_iter()
+            .collect::<TransitionTable<_, _>>();
+        assert_eq!(Machine::from_table(table).transitions().count(), 1);
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::Duration;
+
+// The time window in the current state where a transition is allowed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct TimeGuard {
+    pub(crate) after: Option<Duration>,
+    pub(crate) within: Option<Duration>,
+}
+
+impl TimeGuard {
+    // Returns `true` if the transition is allowed after being in the current state for `elapsed`.
+    pub(crate) fn allows(&self, elapsed: Duration) -> bool {
+        self.after.is_none_or(|after| elapsed >= after)
+            && self.within.is_none_or(|within| elapsed < within)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::clock::MockClock;
+    use crate::error::TransitionError;
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        AwaitingAck,
+        Acked,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Resend,
+        Ack,
+    }
+
+    #[test]
+    fn guard_after_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::self_transition(State::AwaitingAck, Event::Resend)
+                    .guard_after(Duration::from_secs(2)),
+            )
+            .with_clock(clock.clone())
+            .start(State::AwaitingAck);
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(sm.send(Event::Resend), Err(TransitionError::GuardRejected));
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(sm.send(Event::Resend), Ok(State::AwaitingAck));
+
+        // A self transition doesn't reset the time in the state
+        assert_eq!(sm.send(Event::Resend), Ok(State::AwaitingAck));
+    }
+
+    #[test]
+    fn guard_within_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new(State::AwaitingAck)
+                    .on(Event::Ack)
+                    .go_to(State::Acked)
+                    .guard_within(Duration::from_secs(5)),
+            )
+            .on_next(
+                Builder::new(State::Acked)
+                    .on(Event::Resend)
+                    .go_to(State::AwaitingAck),
+            )
+            .with_clock(clock.clone())
+            .start(State::AwaitingAck);
+
+        clock.advance(Duration::from_secs(4));
+        sm.send(Event::Ack).unwrap();
+
+        // Entering the state again resets the time
+        sm.send(Event::Resend).unwrap();
+        clock.advance(Duration::from_secs(4));
+        sm.send(Event::Ack).unwrap();
+
+        sm.send(Event::Resend).unwrap();
+        clock.advance(Duration::from_secs(5));
+        assert_eq!(sm.send(Event::Ack), Err(TransitionError::GuardRejected));
+        assert_eq!(*sm.current(), State::AwaitingAck);
+    }
+
+    #[test]
+    fn guarded_candidates_test() {
+        let clock = MockClock::new();
+        let answer = |to| Builder::new("ringing").on("answer").go_to(to);
+        let secs = Duration::from_secs;
+
+        let mut sm = Machine::new()
+            .on_next(answer("voicemail").guard_after(secs(20)))
+            .on_next(answer("talking").guard_within(secs(30)).priority(1))
+            .on_next(answer("missed").guard_after(secs(60)).priority(2))
+            .on_next(Builder::new("voicemail").on("hang").go_to("ringing"))
+            .on_next(Builder::new("talking").on("hang").go_to("ringing"))
+            .with_clock(clock.clone())
+            .start("ringing");
+
+        assert_eq!(sm.transitions().count(), 5);
+
+        // The overlapping guards are resolved by the priority
+        clock.advance(Duration::from_secs(25));
+        sm.send("answer").unwrap();
+        assert_eq!(*sm.current(), "talking");
+
+        sm.send("hang").unwrap();
+        clock.advance(Duration::from_secs(35));
+        sm.send("answer").unwrap();
+        assert_eq!(*sm.current(), "voicemail");
+
+        sm.send("hang").unwrap();
+        clock.advance(Duration::from_secs(65));
+        sm.send("answer").unwrap();
+        assert_eq!(*sm.current(), "missed");
+    }
+
+    #[test]
+    fn guarded_candidates_rejected_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new(State::AwaitingAck)
+                    .on(Event::Ack)
+                    .go_to(State::Acked)
+                    .guard_after(Duration::from_secs(10)),
+            )
+            .on_next(
+                Builder::self_transition(State::AwaitingAck, Event::Ack)
+                    .guard_within(Duration::from_secs(5)),
+            )
+            .with_clock(clock.clone())
+            .start(State::AwaitingAck);
+
+        clock.advance(Duration::from_secs(4));
+        assert_eq!(sm.send(Event::Ack), Ok(State::AwaitingAck));
+
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(sm.send(Event::Ack), Err(TransitionError::GuardRejected));
+
+        clock.advance(Duration::from_secs(3));
+        sm.send(Event::Ack).unwrap();
+        assert_eq!(*sm.current(), State::Acked);
+    }
+
+    #[test]
+    #[should_panic]
+    fn unguarded_candidate_test() {
+        let _ = Machine::new()
+            .on_next(
+                Builder::new(State::AwaitingAck)
+                    .on(Event::Ack)
+                    .go_to(State::Acked)
+                    .guard_after(Duration::from_secs(10)),
+            )
+            .on_next(Builder::self_transition(State::AwaitingAck, Event::Ack));
+    }
+}
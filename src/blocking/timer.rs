@@ -0,0 +1,291 @@
+use super::{Build, Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+use std::time::{Duration, Instant};
+
+// An event scheduled after dwelling in a state.
+struct Timer<S, E> {
+    state: S,
+    after: Duration,
+    event: E,
+    fired: bool,
+}
+
+// The timers of a machine, measured from the time the current state was entered.
+pub(crate) struct Timers<S, E> {
+    timers: Vec<Timer<S, E>>,
+}
+
+impl<S, E> Timers<S, E> {
+    pub(crate) fn new() -> Self {
+        Timers { timers: Vec::new() }
+    }
+
+    // Resets all the timers, when entering a state.
+    pub(crate) fn enter(&mut self) {
+        for timer in self.timers.iter_mut() {
+            timer.fired = false;
+        }
+    }
+
+    fn pending<'s>(&'s self, current: &'s S) -> impl Iterator<Item = &'s Timer<S, E>>
+    where
+        S: PartialEq,
+    {
+        self.timers
+            .iter()
+            .filter(move |t| !t.fired && &t.state == current)
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized> Machine<'a, S, E, Ctx, F, Build, A> {
+    /// Schedules an event to be sent when the machine stays in the given state for the given duration.
+    ///
+    /// The timers only fire when polled with `tick` or `poll_timers`. Leaving the state cancels the timer,
+    /// and entering the state again restarts it, a self transition doesn't restart it.
+    /// The timers and the time guards measure the time from the same entry to the current state.
+    pub fn after(mut self, state: S, duration: Duration, event: E) -> Self {
+        self.extensions.timers.timers.push(Timer {
+            state,
+            after: duration,
+            event,
+            fired: false,
+        });
+
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    S: PartialEq,
+{
+    /// Returns the instant when the next timer of the current state fires, if any.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let entered_at = self.extensions.entered_at;
+
+        self.extensions
+            .timers
+            .pending(self.current.as_ref().unwrap())
+            .map(|t| entered_at + t.after)
+            .min()
+    }
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    E: PartialEq + Clone,
+    S: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends the event of the earliest timer of the current state that expired at the given instant.
+    ///
+    /// At most one event is sent per call, and each timer fires once until the state is entered again.
+    /// While the machine is paused the expired timers don't fire, and they fire after resuming it.
+    ///
+    /// # Returns
+    /// - None: If no timer expired.
+    /// - Some(Result<S, TransitionError>): The result of sending the timer event.
+    pub fn tick(&mut self, now: Instant) -> Option<Result<S, TransitionError>> {
+        if self.extensions.paused {
+            return None;
+        }
+
+        let entered_at = self.extensions.entered_at;
+        let current = self.current.as_ref().unwrap();
+
+        let timer = self
+            .extensions
+            .timers
+            .timers
+            .iter_mut()
+            .filter(|t| !t.fired && &t.state == current && entered_at + t.after <= now)
+            .min_by_key(|t| t.after)?;
+
+        timer.fired = true;
+        let event = timer.event.clone();
+        Some(self.send(event))
+    }
+
+    /// Sends the event of the earliest expired timer of the current state, using the clock of the machine.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::clock::MockClock;
+    /// use std::time::Duration;
+    ///
+    /// let clock = MockClock::new();
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("waiting").on("timeout").go_to("retrying"))
+    ///     .after("waiting", Duration::from_secs(5), "timeout")
+    ///     .with_clock(clock.clone())
+    ///     .start("waiting");
+    ///
+    /// clock.advance(Duration::from_secs(4));
+    /// assert!(sm.poll_timers().is_none());
+    ///
+    /// clock.advance(Duration::from_secs(1));
+    /// assert_eq!(sm.poll_timers(), Some(Ok("waiting")));
+    /// assert_eq!(*sm.current(), "retrying");
+    /// ```
+    pub fn poll_timers(&mut self) -> Option<Result<S, TransitionError>> {
+        let now = self.extensions.clock.now();
+        self.tick(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::clock::{Clock, MockClock};
+    use crate::error::TransitionError;
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Sending,
+        WaitingForAck,
+        Done,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Sent,
+        Ack,
+        Timeout,
+    }
+
+    fn machine(
+        clock: &MockClock,
+    ) -> Machine<'static, State, Event, (), (), crate::blocking::Ready> {
+        Machine::new()
+            .on_next(
+                Builder::new(State::Sending)
+                    .on(Event::Sent)
+                    .go_to(State::WaitingForAck),
+            )
+            .on_next(
+                Builder::new(State::WaitingForAck)
+                    .on(Event::Ack)
+                    .go_to(State::Done),
+            )
+            .on_next(
+                Builder::new(State::WaitingForAck)
+                    .on(Event::Timeout)
+                    .go_to(State::Sending),
+            )
+            .after(State::WaitingForAck, Duration::from_secs(5), Event::Timeout)
+            .with_clock(clock.clone())
+            .start(State::Sending)
+    }
+
+    #[test]
+    fn timer_fires_test() {
+        let clock = MockClock::new();
+        let mut sm = machine(&clock);
+
+        clock.advance(Duration::from_secs(10));
+        assert!(sm.poll_timers().is_none());
+
+        sm.send(Event::Sent).unwrap();
+        assert_eq!(
+            sm.next_deadline(),
+            Some(clock.now() + Duration::from_secs(5))
+        );
+
+        clock.advance(Duration::from_secs(4));
+        assert!(sm.poll_timers().is_none());
+
+        clock.advance(Duration::from_secs(1));
+        assert_eq!(sm.poll_timers(), Some(Ok(State::WaitingForAck)));
+        assert_eq!(*sm.current(), State::Sending);
+        assert!(sm.next_deadline().is_none());
+    }
+
+    #[test]
+    fn timer_cancelled_on_exit_test() {
+        let clock = MockClock::new();
+        let mut sm = machine(&clock);
+
+        sm.send(Event::Sent).unwrap();
+        clock.advance(Duration::from_secs(3));
+        sm.send(Event::Ack).unwrap();
+
+        clock.advance(Duration::from_secs(10));
+        assert!(sm.poll_timers().is_none());
+        assert_eq!(*sm.current(), State::Done);
+    }
+
+    #[test]
+    fn timer_reset_on_reenter_test() {
+        let clock = MockClock::new();
+        let mut sm = machine(&clock);
+
+        sm.send(Event::Sent).unwrap();
+        clock.advance(Duration::from_secs(5));
+        assert!(sm.poll_timers().is_some());
+
+        // Entering again restarts the timer
+        clock.advance(Duration::from_secs(2));
+        sm.send(Event::Sent).unwrap();
+        clock.advance(Duration::from_secs(4));
+        assert!(sm.poll_timers().is_none());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(sm.poll_timers().is_some());
+    }
+
+    #[test]
+    fn timer_self_transition_test() {
+        let clock = MockClock::new();
+        let mut sm = Machine::new()
+            .on_next(Builder::self_transition(State::WaitingForAck, Event::Sent))
+            .on_next(
+                Builder::new(State::WaitingForAck)
+                    .on(Event::Ack)
+                    .go_to(State::Done)
+                    .guard_after(Duration::from_secs(3)),
+            )
+            .after(State::WaitingForAck, Duration::from_secs(5), Event::Timeout)
+            .with_clock(clock.clone())
+            .start(State::WaitingForAck);
+
+        // A self transition doesn't restart the timer nor the time guard
+        clock.advance(Duration::from_secs(3));
+        sm.send(Event::Sent).unwrap();
+        assert_eq!(
+            sm.next_deadline(),
+            Some(clock.now() + Duration::from_secs(2))
+        );
+        assert_eq!(sm.send(Event::Ack), Ok(State::WaitingForAck));
+    }
+
+    #[test]
+    fn timer_no_double_fire_test() {
+        let clock = MockClock::new();
+
+        // There is no transition for the timeout, so the state doesn't change
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new(State::Sending)
+                    .on(Event::Sent)
+                    .go_to(State::WaitingForAck),
+            )
+            .after(State::WaitingForAck, Duration::from_secs(1), Event::Timeout)
+            .with_clock(clock.clone())
+            .start(State::Sending);
+
+        sm.send(Event::Sent).unwrap();
+        clock.advance(Duration::from_secs(1));
+
+        assert_eq!(
+            sm.poll_timers(),
+            Some(Err(TransitionError::InvalidTransition))
+        );
+        clock.advance(Duration::from_secs(1));
+        assert!(sm.poll_timers().is_none());
+    }
+}
@@ -1,18 +1,33 @@
-use crate::blocking::OnAction;
+use super::limit::FireLimit;
+use super::time_guard::TimeGuard;
+use crate::blocking::{
+    ActionOnce, BoxedAction, ContextMut, LocalAction, OnAction, SendAction, StatelessAction,
+    SyncAction,
+};
 use private::*;
 use std::fmt::Debug;
 use std::marker::PhantomData;
+use std::time::Duration;
+
+// Marks the lifetime and context of a transition without affecting its auto traits.
+pub(crate) type Marker<'a, Ctx, T> = PhantomData<(&'a (), fn() -> Ctx, T)>;
 
 /// Represents a transition from an state to other state when an event arrives.
-pub struct Transition<'a, S, E, Ctx> {
+pub struct Transition<'a, S, E, Ctx, A: ?Sized = SendAction<'a, S, E, Ctx>> {
     pub(crate) from: S,
     pub(crate) to: S,
     pub(crate) event: E,
     pub(crate) is_final: bool,
-    pub(crate) action: Option<Box<dyn OnAction<S, E, Ctx> + Send + 'a>>,
+    pub(crate) action: Option<Box<A>>,
+    pub(crate) before: Option<Box<A>>,
+    pub(crate) guard: Option<TimeGuard>,
+    pub(crate) limit: Option<FireLimit>,
+    pub(crate) priority: u8,
+    pub(crate) label: Option<String>,
+    pub(crate) _marker: Marker<'a, Ctx, ()>,
 }
 
-impl<S, E, Ctx> Debug for Transition<'_, S, E, Ctx>
+impl<S, E, Ctx, A: ?Sized> Debug for Transition<'_, S, E, Ctx, A>
 where
     S: Debug,
     E: Debug,
@@ -24,6 +39,7 @@ where
             .field("to", &self.to)
             .field("event", &self.event)
             .field("is_final", &self.is_final)
+            .field("label", &self.label)
             .field("action", {
                 match self.action {
                     None => &"None",
@@ -35,36 +51,66 @@ where
 }
 
 /// Allows a type to be converted into a `Transition`.
-pub trait IntoTransition<'a, S, E, Ctx> {
+pub trait IntoTransition<'a, S, E, Ctx, A: ?Sized = SendAction<'a, S, E, Ctx>> {
     /// Converts this type into a `Transition`.
-    fn into_transition(self) -> Transition<'a, S, E, Ctx>;
+    fn into_transition(self) -> Transition<'a, S, E, Ctx, A>;
+}
+
+impl<'a, S, E, Ctx, A: ?Sized> IntoTransition<'a, S, E, Ctx, A> for Transition<'a, S, E, Ctx, A> {
+    fn into_transition(self) -> Transition<'a, S, E, Ctx, A> {
+        self
+    }
 }
 
 /// A `Transition` builder.
-pub struct Builder<'a, S, E, Ctx, TStep = Build> {
+///
+/// The type of the boxed actions `A` is inferred from the machine the transition is added to.
+pub struct Builder<'a, S, E, Ctx, TStep = Build, A: ?Sized = SendAction<'a, S, E, Ctx>> {
     from: Option<S>,
     to: Option<S>,
     event: Option<E>,
     is_final: bool,
-    action: Option<Box<dyn OnAction<S, E, Ctx> + Send + 'a>>,
-    _marker: PhantomData<TStep>,
+    action: Option<Box<A>>,
+    before: Option<Box<A>>,
+    guard: Option<TimeGuard>,
+    limit: Option<FireLimit>,
+    priority: u8,
+    label: Option<String>,
+    _marker: Marker<'a, Ctx, TStep>,
 }
 
-impl<'a, S, E, Ctx> Builder<'a, S, E, Ctx, Build> {
+/// A `Transition` builder for a `LocalMachine`, which actions are not required to be `Send`.
+pub type LocalBuilder<'a, S, E, Ctx, TStep = Build> =
+    Builder<'a, S, E, Ctx, TStep, LocalAction<'a, S, E, Ctx>>;
+
+/// A `Transition` builder for a `SyncMachine`, which actions are required to be `Send + Sync`.
+pub type SyncBuilder<'a, S, E, Ctx, TStep = Build> =
+    Builder<'a, S, E, Ctx, TStep, SyncAction<'a, S, E, Ctx>>;
+
+/// A `Transition` builder for a `StatelessMachine`, which actions are required to be `Fn + Send + Sync`.
+pub type StatelessBuilder<'a, S, E, Ctx, TStep = Build> =
+    Builder<'a, S, E, Ctx, TStep, StatelessAction<'a, S, E, Ctx>>;
+
+impl<'a, S, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, Build, A> {
     /// Constructs a transition that goes from and start to end state when the given event is emitted.
-    pub fn new(from: S) -> Builder<'a, S, E, Ctx, HasFrom> {
+    pub fn new(from: S) -> Builder<'a, S, E, Ctx, HasFrom, A> {
         Builder {
             from: Some(from),
             to: None,
             event: None,
             is_final: false,
             action: None,
+            before: None,
+            guard: None,
+            limit: None,
+            priority: 0,
+            label: None,
             _marker: PhantomData,
         }
     }
 
     /// Trigger a transition from and state to itself when the given event happens.
-    pub fn self_transition(state: S, event: E) -> Builder<'a, S, E, Ctx, CanBuild>
+    pub fn self_transition(state: S, event: E) -> Builder<'a, S, E, Ctx, CanBuild, A>
     where
         S: Clone,
     {
@@ -72,59 +118,253 @@ impl<'a, S, E, Ctx> Builder<'a, S, E, Ctx, Build> {
     }
 }
 
-impl<'a, S, E, Ctx> Builder<'a, S, E, Ctx, HasFrom> {
+impl<'a, S, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, HasFrom, A> {
     /// Sets the event that trigger this transition.
-    pub fn on(self, event: E) -> Builder<'a, S, E, Ctx, HasEvent> {
+    pub fn on(self, event: E) -> Builder<'a, S, E, Ctx, HasEvent, A> {
         Builder {
             from: self.from,
             to: None,
             event: Some(event),
             is_final: self.is_final,
             action: self.action,
+            before: self.before,
+            guard: self.guard,
+            limit: self.limit,
+            priority: self.priority,
+            label: self.label,
             _marker: PhantomData,
         }
     }
 }
 
-impl<'a, S, E, Ctx> Builder<'a, S, E, Ctx, HasEvent> {
+impl<'a, S, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, HasEvent, A> {
     /// Sets the type where the transition goes to.
-    pub fn go_to(self, state: S) -> Builder<'a, S, E, Ctx, CanBuild> {
+    pub fn go_to(self, state: S) -> Builder<'a, S, E, Ctx, CanBuild, A> {
         Builder {
             from: self.from,
             to: Some(state),
             event: self.event,
             is_final: self.is_final,
             action: self.action,
+            before: self.before,
+            guard: self.guard,
+            limit: self.limit,
+            priority: self.priority,
+            label: self.label,
             _marker: PhantomData,
         }
     }
 }
 
-impl<'a, S, E, Ctx> Builder<'a, S, E, Ctx, CanBuild> {
+impl<'a, S, E, Ctx, A: ?Sized> Builder<'a, S, E, Ctx, CanBuild, A> {
     /// Ensure this transition completes the state machine.
     pub fn is_final(mut self) -> Self {
         self.is_final = true;
         self
     }
 
-    /// Sets an action to execute this transition happen.
-    pub fn action<F>(mut self, f: F) -> Self
+    /// Sets an action to execute this transition happen, this is the same as `after`.
+    ///
+    /// The action must be `Send` unless the transition is for a `LocalMachine`.
+    pub fn action<F>(self, f: F) -> Self
+    where
+        F: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, F, S, E, Ctx>,
+    {
+        self.after(f)
+    }
+
+    /// Sets an action to execute before the machine changes to the next state.
+    ///
+    /// The action runs after the checks of the transition and before the `after` action,
+    /// if it cancels the transition the machine never leaves the current state.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context(Vec::new())
+    ///     .on_next(
+    ///         Builder::new("idle")
+    ///             .on("start")
+    ///             .go_to("running")
+    ///             .before(|cx: ContextMut<&str, &str, Vec<String>>| {
+    ///                 cx.context.push(format!("leaving {}", cx.from));
+    ///             })
+    ///             .after(|cx: ContextMut<&str, &str, Vec<String>>| {
+    ///                 cx.context.push(format!("entered {}", cx.to));
+    ///             }),
+    ///     )
+    ///     .start("idle");
+    ///
+    /// sm.send("start").unwrap();
+    /// assert_eq!(*sm.context(), ["leaving idle", "entered running"]);
+    /// ```
+    pub fn before<F>(mut self, f: F) -> Self
     where
-        F: OnAction<S, E, Ctx> + Send + 'a,
+        F: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, F, S, E, Ctx>,
     {
-        self.action = Some(Box::new(f));
+        self.before = Some(A::boxed(f));
+        self
+    }
+
+    /// Sets an action to execute after the machine changed to the next state,
+    /// and before calling the `on_transition` of the machine.
+    pub fn after<F>(mut self, f: F) -> Self
+    where
+        F: OnAction<S, E, Ctx> + 'a,
+        A: BoxedAction<'a, F, S, E, Ctx>,
+    {
+        self.action = Some(A::boxed(f));
+        self
+    }
+
+    /// Sets an action that only runs the first time this transition happens,
+    /// the next times the transition happens without running it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// // A value that cannot be cloned
+    /// struct Connection;
+    ///
+    /// let connection = Connection;
+    ///
+    /// let mut sm = Machine::with_context(None)
+    ///     .on_next(Builder::new("idle").on("connect").go_to("connected").action_once(
+    ///         move |cx: ContextMut<&str, &str, Option<Connection>>| {
+    ///             *cx.context = Some(connection);
+    ///         },
+    ///     ))
+    ///     .on_next(Builder::new("connected").on("drop").go_to("idle"))
+    ///     .start("idle");
+    ///
+    /// sm.send("connect").unwrap();
+    /// assert!(sm.context().is_some());
+    ///
+    /// sm.send("drop").unwrap();
+    /// sm.send("connect").unwrap();
+    /// assert_eq!(*sm.current(), "connected");
+    /// ```
+    pub fn action_once<F>(self, f: F) -> Self
+    where
+        F: FnOnce(ContextMut<S, E, Ctx>) + 'a,
+        A: BoxedAction<'a, ActionOnce<F>, S, E, Ctx>,
+    {
+        self.action(ActionOnce::new(f))
+    }
+
+    /// Sets an action that only runs the first time this transition happens,
+    /// the next times the transition is rejected with `TransitionError::ActionSpent`.
+    pub fn action_once_strict<F>(self, f: F) -> Self
+    where
+        F: FnOnce(ContextMut<S, E, Ctx>) + 'a,
+        A: BoxedAction<'a, ActionOnce<F>, S, E, Ctx>,
+    {
+        self.action(ActionOnce::strict(f))
+    }
+
+    /// Only allows this transition after being in the current state for at least the given duration.
+    ///
+    /// The time is measured with the clock of the machine,
+    /// and only a transition to other state resets it.
+    pub fn guard_after(mut self, duration: Duration) -> Self {
+        self.guard.get_or_insert_with(TimeGuard::default).after = Some(duration);
+        self
+    }
+
+    /// Only allows this transition before being in the current state for the given duration.
+    ///
+    /// The time is measured with the clock of the machine,
+    /// and only a transition to other state resets it.
+    pub fn guard_within(mut self, duration: Duration) -> Self {
+        self.guard.get_or_insert_with(TimeGuard::default).within = Some(duration);
+        self
+    }
+
+    /// Sets the order in which this transition is tried among the guarded transitions for the same event from the same state,
+    /// the transitions with higher priority are tried first and the ones with the same priority in the order they were added.
+    ///
+    /// By default the priority is `0`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use std::time::Duration;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("ringing").on("answer").go_to("missed").guard_after(Duration::ZERO))
+    ///     .on_next(
+    ///         Builder::new("ringing")
+    ///             .on("answer")
+    ///             .go_to("talking")
+    ///             .guard_within(Duration::from_secs(30))
+    ///             .priority(1),
+    ///     )
+    ///     .start("ringing");
+    ///
+    /// sm.send("answer").unwrap();
+    /// assert_eq!(*sm.current(), "talking");
+    /// ```
+    pub fn priority(mut self, priority: u8) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets a human readable label for this transition, used in the exported diagrams and in the panic messages.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running").label("boot"));
+    ///
+    /// assert_eq!(sm.transitions().next().unwrap().label(), Some("boot"));
+    /// ```
+    pub fn label(mut self, label: &str) -> Self {
+        self.label = Some(label.to_owned());
+        self
+    }
+
+    /// Only allows this transition to happen the given number of times,
+    /// after that the event is rejected with `TransitionError::TransitionExhausted`.
+    pub fn max_fires(mut self, n: u32) -> Self {
+        self.limit.get_or_insert_with(FireLimit::default).max_fires = Some(n);
+        self
+    }
+
+    /// Rejects this transition with `TransitionError::CoolingDown` until the given duration passed since it last happened.
+    ///
+    /// The time is measured with the clock of the machine.
+    pub fn cooldown(mut self, duration: Duration) -> Self {
+        self.limit.get_or_insert_with(FireLimit::default).cooldown = Some(duration);
         self
     }
 }
 
-impl<'a, S, E, Ctx> IntoTransition<'a, S, E, Ctx> for Builder<'a, S, E, Ctx, CanBuild> {
-    fn into_transition(self) -> Transition<'a, S, E, Ctx> {
+impl<'a, S, E, Ctx, A: ?Sized> IntoTransition<'a, S, E, Ctx, A>
+    for Builder<'a, S, E, Ctx, CanBuild, A>
+{
+    fn into_transition(self) -> Transition<'a, S, E, Ctx, A> {
         Transition {
             from: self.from.unwrap(),
             to: self.to.unwrap(),
             event: self.event.unwrap(),
             action: self.action,
+            before: self.before,
             is_final: self.is_final,
+            guard: self.guard,
+            limit: self.limit,
+            priority: self.priority,
+            label: self.label,
+            _marker: PhantomData,
         }
     }
 }
@@ -135,18 +375,12 @@ pub(crate) mod private {
     #[derive(Debug, Clone)]
     pub struct Build;
 
-    #[derive(Debug, Clone)]
-    pub struct Ready;
-
     #[derive(Debug, Clone)]
     pub struct HasFrom;
 
     #[derive(Debug, Clone)]
     pub struct HasEvent;
 
-    #[derive(Debug, Clone)]
-    pub struct HasTo;
-
     #[derive(Debug, Clone)]
     pub struct CanBuild;
 }
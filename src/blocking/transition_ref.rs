@@ -0,0 +1,147 @@
+use super::Machine;
+
+/// A transition of a state machine, returned by `Machine::transitions`.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TransitionRef<'a, S, E> {
+    from: &'a S,
+    event: &'a E,
+    to: &'a S,
+    is_final: bool,
+    has_guard: bool,
+    is_ignored: bool,
+    label: Option<&'a str>,
+}
+
+impl<S, E> Clone for TransitionRef<'_, S, E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<S, E> Copy for TransitionRef<'_, S, E> {}
+
+impl<'a, S, E> TransitionRef<'a, S, E> {
+    /// Returns the state where the transition starts.
+    pub fn from(&self) -> &'a S {
+        self.from
+    }
+
+    /// Returns the event that triggers the transition.
+    pub fn event(&self) -> &'a E {
+        self.event
+    }
+
+    /// Returns the state where the transition ends.
+    pub fn to(&self) -> &'a S {
+        self.to
+    }
+
+    /// Returns `true` if the transition completes the state machine.
+    pub fn is_final(&self) -> bool {
+        self.is_final
+    }
+
+    /// Returns `true` if the transition is only allowed within a time window, see `Builder::guard_after` and `Builder::guard_within`.
+    pub fn has_guard(&self) -> bool {
+        self.has_guard
+    }
+
+    /// Returns `true` if the event is dropped without a transition, see `Machine::ignore`.
+    pub fn is_ignored(&self) -> bool {
+        self.is_ignored
+    }
+
+    /// Returns the label of the transition, see `Builder::label`.
+    pub fn label(&self) -> Option<&'a str> {
+        self.label
+    }
+}
+
+/// An iterator over the transitions of a state machine, returned by `Machine::transitions`.
+pub struct Transitions<'a, S, E> {
+    iter: Box<dyn Iterator<Item = TransitionRef<'a, S, E>> + 'a>,
+}
+
+impl<'a, S, E> Iterator for Transitions<'a, S, E> {
+    type Item = TransitionRef<'a, S, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C> {
+    /// Returns an iterator over the transitions of this state machine.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let sm = Machine::new()
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_next(Builder::new("running").on("stop").go_to("stopped").is_final());
+    ///
+    /// let finals = sm.transitions().filter(|t| t.is_final()).map(|t| *t.to());
+    /// assert_eq!(finals.collect::<Vec<_>>(), ["stopped"]);
+    /// ```
+    pub fn transitions(&self) -> Transitions<'_, S, E> {
+        let iter = self.transitions.iter().flat_map(|(from, event, first)| {
+            first.candidates().map(move |next| TransitionRef {
+                from,
+                event,
+                to: &next.next,
+                is_final: next.is_final,
+                has_guard: next.guard.is_some(),
+                is_ignored: next.ignored,
+                label: next.label.as_deref(),
+            })
+        });
+
+        Transitions {
+            iter: Box::new(iter),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Transitions;
+    use crate::blocking::{Builder, Machine};
+    use std::time::Duration;
+
+    // The iterator can be named in the fields of other types
+    struct Report<'a> {
+        transitions: Transitions<'a, char, u8>,
+    }
+
+    #[test]
+    fn transitions_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new('a').on(0).go_to('b'))
+            .on_next(
+                Builder::new('b')
+                    .on(1)
+                    .go_to('c')
+                    .is_final()
+                    .guard_within(Duration::from_secs(1)),
+            )
+            .start('a');
+
+        let report = Report {
+            transitions: sm.transitions(),
+        };
+
+        let transitions = report
+            .transitions
+            .map(|t| (*t.from(), *t.event(), *t.to(), t.is_final(), t.has_guard()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            transitions,
+            [('a', 0, 'b', false, false), ('b', 1, 'c', true, true)]
+        );
+        assert_eq!(sm.states().collect::<Vec<_>>(), [&'a', &'b']);
+        assert_eq!(sm.events().collect::<Vec<_>>(), [&0, &1]);
+    }
+}
@@ -0,0 +1,171 @@
+use super::{Build, Machine, Ready};
+use crate::error::UndoError;
+use std::collections::VecDeque;
+
+// A state of the machine before a transition.
+struct Snapshot<S, Ctx> {
+    state: S,
+    context: Ctx,
+}
+
+// The snapshots taken before the last transitions, the most recent at the back.
+pub(crate) struct UndoHistory<S, Ctx> {
+    depth: usize,
+    snapshots: VecDeque<Snapshot<S, Ctx>>,
+    clone_context: fn(&Ctx) -> Ctx,
+}
+
+impl<S, Ctx> UndoHistory<S, Ctx> {
+    // Returns a copy of the context to take a snapshot with.
+    pub(crate) fn copy(&self, context: &Ctx) -> Ctx {
+        (self.clone_context)(context)
+    }
+
+    pub(crate) fn push(&mut self, state: S, context: Ctx) {
+        if self.depth == 0 {
+            return;
+        }
+
+        if self.snapshots.len() == self.depth {
+            self.snapshots.pop_front();
+        }
+
+        self.snapshots.push_back(Snapshot { state, context });
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.snapshots.clear();
+    }
+}
+
+impl<'a, S, E, Ctx, F, A: ?Sized> Machine<'a, S, E, Ctx, F, Build, A>
+where
+    S: Clone,
+    Ctx: Clone,
+{
+    /// Keeps a copy of the state and the context before each of the last `depth` transitions, so they can be undone.
+    pub fn with_undo(mut self, depth: usize) -> Self {
+        self.extensions.undo = Some(UndoHistory {
+            depth,
+            snapshots: VecDeque::with_capacity(depth),
+            clone_context: Ctx::clone,
+        });
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized> Machine<'_, S, E, Ctx, F, Ready, A> {
+    /// Reverts the last transition, restoring the state, the context and whether the machine was done.
+    ///
+    /// The actions are not reversed, the context is replaced with the copy taken before the transition,
+    /// other data like timers or state data is not restored.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context(0)
+    ///     .on_next(Builder::new("draft").on("publish").go_to("published").is_final().action(
+    ///         |cx: ContextMut<&str, &str, i32>| {
+    ///             *cx.context += 1;
+    ///         },
+    ///     ))
+    ///     .with_undo(10)
+    ///     .start("draft");
+    ///
+    /// sm.send("publish").unwrap();
+    /// assert!(sm.is_done());
+    ///
+    /// sm.undo().unwrap();
+    /// assert_eq!(*sm.current(), "draft");
+    /// assert_eq!(*sm.context(), 0);
+    /// assert!(!sm.is_done());
+    /// ```
+    pub fn undo(&mut self) -> Result<(), UndoError> {
+        let history = self.extensions.undo.as_mut().ok_or(UndoError::Disabled)?;
+        let snapshot = history.snapshots.pop_back().ok_or(UndoError::Empty)?;
+
+        self.current = Some(snapshot.state);
+        self.context = snapshot.context;
+
+        // A done machine rejects all the events, so it was never done before a transition
+        self.done = false;
+        self.extensions.finished = None;
+        Ok(())
+    }
+
+    /// Returns `true` if there is a transition that can be undone.
+    pub fn can_undo(&self) -> bool {
+        self.undo_depth() > 0
+    }
+
+    /// Returns the number of transitions that can be undone.
+    pub fn undo_depth(&self) -> usize {
+        self.extensions
+            .undo
+            .as_ref()
+            .map(|history| history.snapshots.len())
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::error::UndoError;
+
+    fn push(cx: ContextMut<u32, char, Vec<char>>) {
+        cx.context.push(*cx.event);
+    }
+
+    #[test]
+    fn undo_test() {
+        let mut sm = Machine::with_context(vec![])
+            .on_next(Builder::new(0).on('a').go_to(1).action(push))
+            .on_next(Builder::new(1).on('b').go_to(2).action(push))
+            .on_next(Builder::new(2).on('c').go_to(3).action(push))
+            .on_next(Builder::new(1).on('d').go_to(4).action(push))
+            .with_undo(5)
+            .start(0);
+
+        sm.send('a').unwrap();
+        sm.send('b').unwrap();
+        sm.send('c').unwrap();
+        assert_eq!(sm.undo_depth(), 3);
+
+        sm.undo().unwrap();
+        sm.undo().unwrap();
+        assert_eq!(*sm.current(), 1);
+        assert_eq!(*sm.context(), vec!['a']);
+
+        // Sending again discards the undone transitions
+        sm.send('d').unwrap();
+        assert_eq!(sm.undo_depth(), 2);
+        assert_eq!(*sm.context(), vec!['a', 'd']);
+
+        sm.undo().unwrap();
+        sm.undo().unwrap();
+        assert_eq!(*sm.current(), 0);
+        assert_eq!(sm.undo(), Err(UndoError::Empty));
+        assert!(!sm.can_undo());
+    }
+
+    #[test]
+    fn undo_depth_test() {
+        let mut sm = Machine::new()
+            .on_next(Builder::new(0).on('a').go_to(1))
+            .on_next(Builder::new(1).on('a').go_to(0))
+            .with_undo(2)
+            .start(0);
+
+        for _ in 0..5 {
+            sm.send('a').unwrap();
+        }
+
+        assert_eq!(sm.undo_depth(), 2);
+
+        let mut sm = Machine::<u32, char, (), ()>::new().start(0);
+        assert_eq!(sm.undo(), Err(UndoError::Disabled));
+    }
+}
@@ -0,0 +1,55 @@
+use super::{Build, Machine, UnhandledContext};
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Sets a callback called each time an event doesn't trigger any transition,
+    /// before `send` returns the error.
+    ///
+    /// The callback is also called when the machine is already done, in that case `is_done` is `true`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// let mut sm = Machine::with_context(Vec::new())
+    ///     .on_next(Builder::new("idle").on("start").go_to("running"))
+    ///     .on_unhandled(|cx: UnhandledContext<&str, &str, Vec<String>>| {
+    ///         cx.context.push(format!("{} on {}", cx.event, cx.state));
+    ///     })
+    ///     .start("idle");
+    ///
+    /// assert!(sm.send("stop").is_err());
+    /// assert_eq!(sm.context(), &["stop on idle"]);
+    /// ```
+    pub fn on_unhandled<U>(mut self, on_unhandled: U) -> Self
+    where
+        U: FnMut(UnhandledContext<S, E, Ctx>) + Send + Sync + 'a,
+    {
+        self.extensions.on_unhandled = Some(Box::new(on_unhandled));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine, UnhandledContext};
+    use crate::error::TransitionError;
+
+    #[test]
+    fn on_unhandled_test() {
+        let mut sm = Machine::with_context(Vec::new())
+            .on_next(Builder::new(1).on('a').go_to(2))
+            .on_next(Builder::new(2).on('b').go_to(3).is_final())
+            .on_unhandled(|cx: UnhandledContext<i32, char, Vec<(i32, char, bool)>>| {
+                cx.context.push((*cx.state, *cx.event, cx.is_done));
+            })
+            .start(1);
+
+        assert_eq!(sm.send('b'), Err(TransitionError::InvalidTransition));
+        sm.send('a').unwrap();
+        sm.send('b').unwrap();
+        assert_eq!(sm.send('a'), Err(TransitionError::Done));
+
+        assert_eq!(sm.context(), &[(1, 'b', false), (3, 'a', true)]);
+    }
+}
@@ -0,0 +1,238 @@
+use super::{Build, Machine};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+
+/// A suspicious pair of states found by `Machine::validate`,
+/// usually caused by a `PartialEq` implementation that ignores some fields.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationWarning {
+    /// Two states are equal but their `Debug` representations are different,
+    /// the transitions of the later one were added to the earlier one.
+    EqualStatesDiffer {
+        /// The `Debug` representation of the state known by the machine.
+        existing: String,
+
+        /// The `Debug` representation of the equal state.
+        other: String,
+    },
+
+    /// Two states are equal but their hashes are different.
+    HashMismatch {
+        /// The `Debug` representation of the state known by the machine.
+        existing: String,
+
+        /// The `Debug` representation of the equal state.
+        other: String,
+    },
+}
+
+// Compares two states that are equal, and records the suspicious pairs.
+type Check<S> = fn(&S, &S, &mut Vec<ValidationWarning>);
+
+// The checks of the states added to the machine, and the warnings found while building it.
+pub(crate) struct Invariants<S> {
+    check: Check<S>,
+    warnings: Vec<ValidationWarning>,
+}
+
+impl<S: PartialEq> Invariants<S> {
+    // Checks a state against the equal one the machine already has, if any.
+    pub(crate) fn check_insert<'s>(&mut self, mut states: impl Iterator<Item = &'s S>, from: &S)
+    where
+        S: 's,
+    {
+        if let Some(existing) = states.find(|s| *s == from) {
+            (self.check)(existing, from, &mut self.warnings);
+        }
+    }
+}
+
+fn check_debug<S: Debug>(existing: &S, other: &S, warnings: &mut Vec<ValidationWarning>) {
+    let (existing, other) = (format!("{existing:?}"), format!("{other:?}"));
+
+    if existing != other {
+        warnings.push(ValidationWarning::EqualStatesDiffer { existing, other });
+    }
+}
+
+fn check_hash<S: Debug + Hash>(existing: &S, other: &S, warnings: &mut Vec<ValidationWarning>) {
+    check_debug(existing, other, warnings);
+
+    let hash = |s: &S| {
+        let mut hasher = DefaultHasher::new();
+        s.hash(&mut hasher);
+        hasher.finish()
+    };
+
+    if hash(existing) != hash(other) {
+        warnings.push(ValidationWarning::HashMismatch {
+            existing: format!("{existing:?}"),
+            other: format!("{other:?}"),
+        });
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Build, A, C>
+where
+    S: Debug + Hash,
+{
+    /// Checks each state added after this call against the equal states the machine already has,
+    /// the suspicious pairs are returned by `Machine::validate`.
+    ///
+    /// A state equal to other with a different `Debug` representation or hash usually means
+    /// its `PartialEq` implementation ignores some fields, and the transitions of both states were merged.
+    /// The checks only run in debug builds.
+    pub fn validate_invariants(mut self) -> Self {
+        if cfg!(debug_assertions) {
+            self.extensions.invariants = Some(Invariants {
+                check: check_hash::<S>,
+                warnings: Vec::new(),
+            });
+        }
+
+        self
+    }
+}
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: PartialEq + Debug,
+{
+    /// Returns the suspicious pairs of equal states of this machine.
+    ///
+    /// The target states are compared with the other states of the transitions,
+    /// and with `validate_invariants` also the states where the transitions start are compared when they are added.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(Debug, Clone, Hash)]
+    /// struct Level(&'static str, u32);
+    ///
+    /// // Ignores the number of the level
+    /// impl PartialEq for Level {
+    ///     fn eq(&self, other: &Self) -> bool {
+    ///         self.0 == other.0
+    ///     }
+    /// }
+    ///
+    /// let sm = Machine::new()
+    ///     .validate_invariants()
+    ///     .on_next(Builder::new(Level("low", 1)).on("up").go_to(Level("high", 1)))
+    ///     .on_next(Builder::new(Level("low", 2)).on("down").go_to(Level("low", 1)));
+    ///
+    /// assert!(!sm.validate().is_empty());
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationWarning> {
+        let (check, mut warnings) = match &self.extensions.invariants {
+            Some(invariants) => (invariants.check, invariants.warnings.clone()),
+            None => (check_debug::<S> as Check<S>, Vec::new()),
+        };
+
+        let froms = self.transitions.states();
+        let targets = self.transitions.iter().map(|(_, _, next)| &next.next);
+        let mut known: Vec<&S> = Vec::new();
+
+        for state in froms.chain(targets) {
+            match known.iter().find(|s| **s == state) {
+                Some(existing) => check(existing, state, &mut warnings),
+                None => known.push(state),
+            }
+        }
+
+        // The same pair can be found when it is added and in the targets
+        let mut unique = Vec::with_capacity(warnings.len());
+        for warning in warnings {
+            if !unique.contains(&warning) {
+                unique.push(warning);
+            }
+        }
+
+        unique
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ValidationWarning;
+    use crate::blocking::{Builder, Machine};
+    use std::hash::{Hash, Hasher};
+
+    #[derive(Debug, Clone)]
+    struct Running {
+        speed: u32,
+    }
+
+    // All the speeds are equal, but they are hashed
+    impl PartialEq for Running {
+        fn eq(&self, _: &Self) -> bool {
+            true
+        }
+    }
+
+    impl Hash for Running {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.speed.hash(state);
+        }
+    }
+
+    #[test]
+    fn validate_invariants_test() {
+        let sm = Machine::new()
+            .validate_invariants()
+            .on_next(Builder::self_transition(Running { speed: 1 }, "faster"))
+            .on_next(Builder::self_transition(Running { speed: 2 }, "slower"));
+
+        let warnings = sm.validate();
+
+        assert_eq!(
+            warnings,
+            [
+                ValidationWarning::EqualStatesDiffer {
+                    existing: String::from("Running { speed: 1 }"),
+                    other: String::from("Running { speed: 2 }"),
+                },
+                ValidationWarning::HashMismatch {
+                    existing: String::from("Running { speed: 1 }"),
+                    other: String::from("Running { speed: 2 }"),
+                },
+            ]
+        );
+    }
+
+    #[derive(Debug, Clone)]
+    struct Level(&'static str, u32);
+
+    impl PartialEq for Level {
+        fn eq(&self, other: &Self) -> bool {
+            self.0 == other.0
+        }
+    }
+
+    #[test]
+    fn validate_targets_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new(Level("a", 0)).on(0).go_to(Level("b", 1)))
+            .on_next(Builder::new(Level("b", 0)).on(0).go_to(Level("a", 0)))
+            .start(Level("a", 0));
+
+        assert_eq!(sm.current().1, 0);
+
+        assert_eq!(
+            sm.validate(),
+            [ValidationWarning::EqualStatesDiffer {
+                existing: String::from("Level(\"b\", 0)"),
+                other: String::from("Level(\"b\", 1)"),
+            }]
+        );
+
+        let sm = Machine::new()
+            .validate_invariants()
+            .on_next(Builder::new(1).on(0).go_to(2))
+            .on_next(Builder::new(2).on(0).go_to(1));
+        assert!(sm.validate().is_empty());
+    }
+}
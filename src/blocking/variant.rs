@@ -0,0 +1,118 @@
+use super::{Build, Machine, Ready};
+use std::mem;
+
+impl<'a, S, E, Ctx, F, A: ?Sized, C> Machine<'a, S, E, Ctx, F, Build, A, C> {
+    /// Matches the transitions by the enum variant of the current state, ignoring its payload.
+    ///
+    /// A transition registered from `State::Retrying { attempts: 0 }` is triggered from any
+    /// `State::Retrying`, a transition from the exact state takes precedence.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    ///
+    /// #[derive(Debug, Clone, PartialEq)]
+    /// enum State {
+    ///     Idle,
+    ///     Retrying { attempts: u32 },
+    /// }
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new(State::Retrying { attempts: 0 }).on("cancel").go_to(State::Idle))
+    ///     .match_by_discriminant()
+    ///     .start(State::Retrying { attempts: 3 });
+    ///
+    /// assert!(sm.send("cancel").is_ok());
+    /// assert_eq!(*sm.current(), State::Idle);
+    /// ```
+    pub fn match_by_discriminant(mut self) -> Self {
+        self.extensions.match_by_discriminant = true;
+        self
+    }
+}
+
+impl<S, E, Ctx, F, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Ready, A, C> {
+    /// Returns `true` if the current state satisfies the predicate.
+    pub fn is_in(&self, f: impl Fn(&S) -> bool) -> bool {
+        f(self.current.as_ref().unwrap())
+    }
+
+    /// Returns `true` if the current state is the same enum variant as `probe`, ignoring the payload.
+    pub fn is_variant(&self, probe: &S) -> bool {
+        mem::discriminant(self.current.as_ref().unwrap()) == mem::discriminant(probe)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::error::TransitionError;
+
+    #[derive(Debug, Clone, PartialEq)]
+    enum State {
+        Idle,
+        Retrying { attempts: u32 },
+        Failed,
+    }
+
+    #[test]
+    fn is_in_and_is_variant_test() {
+        let sm = Machine::<State, (), _, _, _>::new().start(State::Retrying { attempts: 2 });
+
+        assert!(sm.is_variant(&State::Retrying { attempts: 0 }));
+        assert!(!sm.is_variant(&State::Idle));
+        assert!(sm.is_in(|s| matches!(s, State::Retrying { attempts } if *attempts > 1)));
+    }
+
+    #[test]
+    fn strict_match_test() {
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new(State::Retrying { attempts: 0 })
+                    .on("cancel")
+                    .go_to(State::Idle),
+            )
+            .start(State::Retrying { attempts: 3 });
+
+        assert_eq!(sm.send("cancel"), Err(TransitionError::InvalidTransition));
+    }
+
+    #[test]
+    fn match_by_discriminant_test() {
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new(State::Retrying { attempts: 0 })
+                    .on("fail")
+                    .go_to(State::Idle),
+            )
+            .on_next(
+                Builder::new(State::Retrying { attempts: 3 })
+                    .on("fail")
+                    .go_to(State::Failed),
+            )
+            .match_by_discriminant()
+            .start(State::Retrying { attempts: 1 });
+
+        assert_eq!(sm.send("fail"), Ok(State::Retrying { attempts: 1 }));
+        assert_eq!(*sm.current(), State::Idle);
+
+        // The exact state takes precedence
+        let mut sm = Machine::new()
+            .on_next(
+                Builder::new(State::Retrying { attempts: 0 })
+                    .on("fail")
+                    .go_to(State::Idle),
+            )
+            .on_next(
+                Builder::new(State::Retrying { attempts: 3 })
+                    .on("fail")
+                    .go_to(State::Failed),
+            )
+            .match_by_discriminant()
+            .start(State::Retrying { attempts: 3 });
+
+        sm.send("fail").unwrap();
+        assert_eq!(*sm.current(), State::Failed);
+    }
+}
@@ -0,0 +1,161 @@
+use super::{Machine, OnAction, OnTransition, Ready};
+use crate::random::Rng;
+
+impl<S, E, Ctx, F, Step, A: ?Sized, C> Machine<'_, S, E, Ctx, F, Step, A, C>
+where
+    S: PartialEq,
+    E: PartialEq,
+{
+    /// Sets the weight used to pick the transition for the event from the given state in random simulations,
+    /// by default all the transitions have a weight of 1 and a weight of 0 is never picked.
+    ///
+    /// Returns `false` if there is no transition for the event from the state.
+    pub fn set_weight(&mut self, from: &S, event: &E, weight: u32) -> bool {
+        match self.transitions.get_mut(event, from) {
+            Some(next) => {
+                next.weight = weight;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+impl<S, E, Ctx, F, A> Machine<'_, S, E, Ctx, F, Ready, A>
+where
+    S: PartialEq + Clone,
+    E: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Picks one of the events valid from the current state, with a probability proportional to the weight of its transition.
+    ///
+    /// Returns `None` if the machine is done or there is no transition with a weight greater than 0.
+    pub fn choose_event<R: Rng>(&self, rng: &mut R) -> Option<&E> {
+        if self.done {
+            return None;
+        }
+
+        let outgoing = || self.transitions.outgoing(self.current.as_ref().unwrap());
+        let total = outgoing()
+            .map(|(_, next)| u64::from(next.weight))
+            .sum::<u64>();
+
+        if total == 0 {
+            return None;
+        }
+
+        let mut target = rng.next_u64() % total;
+        for (event, next) in outgoing() {
+            let weight = u64::from(next.weight);
+            if target < weight {
+                return Some(event);
+            }
+
+            target -= weight;
+        }
+
+        unreachable!()
+    }
+
+    /// Sends events picked with `choose_event` until the machine is done, there are no events to pick,
+    /// an event is rejected or `max_steps` events were sent.
+    ///
+    /// Returns the transitions made as `(from, event, to)`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::*;
+    /// use restate::random::SeededRng;
+    ///
+    /// let mut sm = Machine::new()
+    ///     .on_next(Builder::new("browsing").on("add").go_to("cart"))
+    ///     .on_next(Builder::new("browsing").on("leave").go_to("gone").is_final())
+    ///     .on_next(Builder::new("cart").on("back").go_to("browsing"))
+    ///     .start("browsing");
+    ///
+    /// sm.set_weight(&"browsing", &"add", 7);
+    /// sm.set_weight(&"browsing", &"leave", 3);
+    ///
+    /// let trace = sm.simulate_random(&mut SeededRng::new(1), 100);
+    /// assert_eq!(trace.last().unwrap().2, "gone");
+    /// ```
+    pub fn simulate_random<R: Rng>(&mut self, rng: &mut R, max_steps: usize) -> Vec<(S, E, S)> {
+        let mut trace = Vec::new();
+
+        while trace.len() < max_steps {
+            let Some(event) = self.choose_event(rng).cloned() else {
+                break;
+            };
+
+            match self.send(event.clone()) {
+                Ok(from) => trace.push((from, event, self.current().clone())),
+                Err(_) => break,
+            }
+        }
+
+        trace
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::random::SeededRng;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Page {
+        Browsing,
+        Cart,
+        Left,
+    }
+
+    fn shop() -> Machine<'static, Page, char, (), (), crate::blocking::Ready> {
+        let mut sm = Machine::new()
+            .on_next(Builder::new(Page::Browsing).on('a').go_to(Page::Cart))
+            .on_next(Builder::new(Page::Browsing).on('l').go_to(Page::Left))
+            .on_next(Builder::new(Page::Cart).on('b').go_to(Page::Browsing))
+            .on_next(Builder::new(Page::Cart).on('x').go_to(Page::Left))
+            .start(Page::Browsing);
+
+        sm.set_weight(&Page::Browsing, &'a', 7);
+        sm.set_weight(&Page::Browsing, &'l', 3);
+        sm
+    }
+
+    #[test]
+    fn simulate_random_trace_test() {
+        let mut sm = shop();
+        let trace = sm.simulate_random(&mut SeededRng::new(3), 10);
+        let events = trace.iter().map(|(_, e, _)| *e).collect::<String>();
+
+        // The same seed always produces the same trace, ending when no transition leaves the state
+        let mut other = shop();
+        assert_eq!(other.simulate_random(&mut SeededRng::new(3), 10), trace);
+        assert_eq!(trace.last().unwrap().2, Page::Left);
+        assert_eq!(events, "ax");
+        assert_eq!(
+            trace,
+            [
+                (Page::Browsing, 'a', Page::Cart),
+                (Page::Cart, 'x', Page::Left)
+            ]
+        );
+        assert!(sm.choose_event(&mut SeededRng::new(0)).is_none());
+    }
+
+    #[test]
+    fn zero_weight_test() {
+        let mut sm = shop();
+        sm.set_weight(&Page::Browsing, &'l', 0);
+        sm.set_weight(&Page::Cart, &'x', 0);
+
+        let mut rng = SeededRng::new(9);
+        let trace = sm.simulate_random(&mut rng, 50);
+
+        assert_eq!(trace.len(), 50);
+        assert!(trace.iter().all(|(_, _, to)| *to != Page::Left));
+        assert!(!sm.set_weight(&Page::Left, &'a', 1));
+    }
+}
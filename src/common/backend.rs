@@ -0,0 +1,110 @@
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+
+/// The data structure used to find the transitions of a state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MapBackend {
+    /// A linear scan over the states, only requires `PartialEq`.
+    Scan,
+
+    /// A `BTreeMap` from each state to its transitions, requires `Ord`.
+    Ordered,
+
+    /// A `HashMap` from each state to its transitions, requires `Hash + Eq`.
+    Hashed,
+}
+
+#[derive(Debug, Clone)]
+enum Keys<S> {
+    Ordered(BTreeMap<S, usize>),
+    Hashed(HashMap<S, usize>),
+}
+
+// An index from the states to their position in the transition map.
+//
+// The lookup functions are selected when the index is created, where the bounds of the state are known,
+// so the map can use it with only `PartialEq` states.
+#[derive(Debug, Clone)]
+pub(crate) struct StateLookup<S> {
+    keys: Keys<S>,
+    find: fn(&Keys<S>, &S) -> Option<usize>,
+    insert: fn(&mut Keys<S>, &S, usize),
+    remove: fn(&mut Keys<S>, &S),
+}
+
+impl<S> StateLookup<S> {
+    pub(crate) fn ordered() -> Self
+    where
+        S: Ord + Clone,
+    {
+        StateLookup {
+            keys: Keys::Ordered(BTreeMap::new()),
+            find: |keys, state| match keys {
+                Keys::Ordered(map) => map.get(state).copied(),
+                Keys::Hashed(_) => unreachable!(),
+            },
+            insert: |keys, state, index| {
+                if let Keys::Ordered(map) = keys {
+                    map.insert(state.clone(), index);
+                }
+            },
+            remove: |keys, state| {
+                if let Keys::Ordered(map) = keys {
+                    map.remove(state);
+                }
+            },
+        }
+    }
+
+    pub(crate) fn hashed() -> Self
+    where
+        S: Hash + Eq + Clone,
+    {
+        StateLookup {
+            keys: Keys::Hashed(HashMap::new()),
+            find: |keys, state| match keys {
+                Keys::Hashed(map) => map.get(state).copied(),
+                Keys::Ordered(_) => unreachable!(),
+            },
+            insert: |keys, state, index| {
+                if let Keys::Hashed(map) = keys {
+                    map.insert(state.clone(), index);
+                }
+            },
+            remove: |keys, state| {
+                if let Keys::Hashed(map) = keys {
+                    map.remove(state);
+                }
+            },
+        }
+    }
+
+    pub(crate) fn backend(&self) -> MapBackend {
+        match self.keys {
+            Keys::Ordered(_) => MapBackend::Ordered,
+            Keys::Hashed(_) => MapBackend::Hashed,
+        }
+    }
+
+    pub(crate) fn find(&self, state: &S) -> Option<usize> {
+        (self.find)(&self.keys, state)
+    }
+
+    pub(crate) fn insert(&mut self, state: &S, index: usize) {
+        (self.insert)(&mut self.keys, state, index)
+    }
+
+    // Removes the state, and shifts the positions of the states after it.
+    pub(crate) fn remove(&mut self, state: &S, index: usize) {
+        (self.remove)(&mut self.keys, state);
+
+        let positions: Box<dyn Iterator<Item = &mut usize>> = match &mut self.keys {
+            Keys::Ordered(map) => Box::new(map.values_mut()),
+            Keys::Hashed(map) => Box::new(map.values_mut()),
+        };
+
+        for position in positions.filter(|p| **p > index) {
+            *position -= 1;
+        }
+    }
+}
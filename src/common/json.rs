@@ -0,0 +1,348 @@
+#![allow(dead_code)]
+
+// A minimal JSON reader and writer, used by the definition loaders and exporters.
+
+use std::fmt::Write;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    /// Writes this value with two spaces of indentation.
+    pub fn to_pretty_string(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        match self {
+            Value::Null => out.push_str("null"),
+            Value::Bool(b) => write!(out, "{b}").unwrap(),
+            Value::Number(n) => write!(out, "{n}").unwrap(),
+            Value::String(s) => write_string(out, s),
+            Value::Array(items) if items.is_empty() => out.push_str("[]"),
+            Value::Array(items) => {
+                out.push_str("[\n");
+                for (i, item) in items.iter().enumerate() {
+                    push_indent(out, indent + 1);
+                    item.write(out, indent + 1);
+                    out.push_str(if i + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                push_indent(out, indent);
+                out.push(']');
+            }
+            Value::Object(entries) if entries.is_empty() => out.push_str("{}"),
+            Value::Object(entries) => {
+                out.push_str("{\n");
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    push_indent(out, indent + 1);
+                    write_string(out, key);
+                    out.push_str(": ");
+                    value.write(out, indent + 1);
+                    out.push_str(if i + 1 < entries.len() { ",\n" } else { "\n" });
+                }
+                push_indent(out, indent);
+                out.push('}');
+            }
+        }
+    }
+}
+
+fn push_indent(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+}
+
+/// Parses a JSON document, returning a message with the position of the error if it's not valid.
+pub fn parse(input: &str) -> Result<Value, String> {
+    let mut parser = Parser {
+        chars: input.char_indices().peekable(),
+        input,
+        depth: 0,
+    };
+
+    let value = parser.value()?;
+    parser.whitespace();
+
+    match parser.chars.next() {
+        None => Ok(value),
+        Some((pos, _)) => Err(format!("unexpected trailing characters at {pos}")),
+    }
+}
+
+// The maximum nesting of arrays and objects, deeper documents are rejected instead of overflowing the stack.
+const MAX_DEPTH: usize = 128;
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    input: &'a str,
+
+    // The number of arrays and objects containing the value being parsed.
+    depth: usize,
+}
+
+impl Parser<'_> {
+    fn whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn error<T>(&mut self, expected: &str) -> Result<T, String> {
+        match self.chars.peek() {
+            Some((pos, c)) => Err(format!("expected {expected} at {pos}, found `{c}`")),
+            None => Err(format!("expected {expected}, found end of input")),
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.whitespace();
+        match self.chars.next_if(|(_, c)| *c == expected) {
+            Some(_) => Ok(()),
+            None => self.error(&format!("`{expected}`")),
+        }
+    }
+
+    fn value(&mut self) -> Result<Value, String> {
+        self.whitespace();
+
+        match self.chars.peek().copied() {
+            Some((pos, '{' | '[')) if self.depth == MAX_DEPTH => {
+                Err(format!("nesting deeper than {MAX_DEPTH} at {pos}"))
+            }
+            Some((_, c @ ('{' | '['))) => {
+                self.depth += 1;
+                let value = match c {
+                    '{' => self.object(),
+                    _ => self.array(),
+                };
+
+                self.depth -= 1;
+                value
+            }
+            Some((_, '"')) => self.string().map(Value::String),
+            Some((_, 't')) => self.keyword("true", Value::Bool(true)),
+            Some((_, 'f')) => self.keyword("false", Value::Bool(false)),
+            Some((_, 'n')) => self.keyword("null", Value::Null),
+            Some((_, c)) if c == '-' || c.is_ascii_digit() => self.number(),
+            _ => self.error("a value"),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+        for expected in keyword.chars() {
+            if self.chars.next_if(|(_, c)| *c == expected).is_none() {
+                return self.error(keyword);
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.chars.peek().map(|(pos, _)| *pos).unwrap();
+        let mut end = start;
+
+        while let Some((pos, c)) = self
+            .chars
+            .next_if(|(_, c)| c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))
+        {
+            end = pos + c.len_utf8();
+        }
+
+        self.input[start..end]
+            .parse()
+            .map(Value::Number)
+            .map_err(|_| format!("invalid number at {start}"))
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(out),
+                Some((pos, '\\')) => match self.chars.next().map(|(_, c)| c) {
+                    Some('"') => out.push('"'),
+                    Some('\\') => out.push('\\'),
+                    Some('/') => out.push('/'),
+                    Some('b') => out.push('\u{8}'),
+                    Some('f') => out.push('\u{c}'),
+                    Some('n') => out.push('\n'),
+                    Some('r') => out.push('\r'),
+                    Some('t') => out.push('\t'),
+                    Some('u') => {
+                        let code = (0..4)
+                            .filter_map(|_| self.chars.next().and_then(|(_, c)| c.to_digit(16)))
+                            .fold(0, |acc, digit| acc * 16 + digit);
+                        out.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    _ => return Err(format!("invalid escape at {pos}")),
+                },
+                Some((_, c)) => out.push(c),
+                None => return Err(String::from("unterminated string")),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        self.expect('[')?;
+        let mut items = Vec::new();
+
+        self.whitespace();
+        if self.chars.next_if(|(_, c)| *c == ']').is_some() {
+            return Ok(Value::Array(items));
+        }
+
+        loop {
+            items.push(self.value()?);
+            self.whitespace();
+
+            match self.chars.next_if(|(_, c)| *c == ',' || *c == ']') {
+                Some((_, ',')) => continue,
+                Some(_) => return Ok(Value::Array(items)),
+                None => return self.error("`,` or `]`"),
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        self.expect('{')?;
+        let mut entries = Vec::new();
+
+        self.whitespace();
+        if self.chars.next_if(|(_, c)| *c == '}').is_some() {
+            return Ok(Value::Object(entries));
+        }
+
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            entries.push((key, self.value()?));
+            self.whitespace();
+
+            match self.chars.next_if(|(_, c)| *c == ',' || *c == '}') {
+                Some((_, ',')) => continue,
+                Some(_) => return Ok(Value::Object(entries)),
+                None => return self.error("`,` or `}`"),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse, Value};
+
+    #[test]
+    fn parse_test() {
+        let value = parse(r#" {"a": [1, -2.5e1, true, null], "b\n": "x\"A"} "#).unwrap();
+
+        assert_eq!(
+            value,
+            Value::Object(vec![
+                (
+                    "a".into(),
+                    Value::Array(vec![
+                        Value::Number(1.0),
+                        Value::Number(-25.0),
+                        Value::Bool(true),
+                        Value::Null
+                    ])
+                ),
+                ("b\n".into(), Value::String("x\"A".into())),
+            ])
+        );
+
+        assert!(parse("[1, 2").is_err());
+        assert!(parse("{} x").is_err());
+        assert!(parse(r#"{"a" 1}"#).is_err());
+    }
+
+    #[test]
+    fn depth_test() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        assert!(parse(&nested(128)).is_ok());
+        assert_eq!(
+            parse(&nested(129)),
+            Err(String::from("nesting deeper than 128 at 128"))
+        );
+        assert!(parse(&"[{\"a\":".repeat(200_000)).is_err());
+    }
+
+    #[test]
+    fn write_test() {
+        let value = Value::Object(vec![
+            ("id".into(), Value::String("a\"b".into())),
+            ("on".into(), Value::Object(vec![])),
+            ("list".into(), Value::Array(vec![Value::Bool(false)])),
+        ]);
+
+        let expected = r#"{
+  "id": "a\"b",
+  "on": {},
+  "list": [
+    false
+  ]
+}"#;
+
+        assert_eq!(value.to_pretty_string(), expected);
+        assert_eq!(parse(expected).unwrap(), value);
+    }
+}
@@ -1 +1,4 @@
-pub mod map;
+pub mod backend;
+
+#[cfg(any(feature = "loader", feature = "xstate"))]
+pub mod json;
@@ -1,12 +1,50 @@
 use std::fmt::{Debug, Display};
 
 /// An error ocurred during a transition.
+#[derive(Clone, PartialEq, Eq)]
 pub enum TransitionError {
     // If the state machine is done.
     Done,
 
     // If the transition is not defined.
     InvalidTransition,
+
+    // If the event could not be mapped to an event of the machine.
+    Unmapped,
+
+    // If the action cancelled the transition.
+    Cancelled,
+
+    // If a callback panicked while the machine catches panics, contains the panic message.
+    ActionPanicked(String),
+
+    // If a callback panicked before, and the machine was not cleared.
+    Poisoned,
+
+    // If the machine is paused, the event can be sent again after resuming it.
+    Paused,
+
+    // If an event was sent to a machine from one of its actions or hooks, while it handles other event,
+    // or a `SharedMachine` was read from them.
+    ReentrantSend,
+
+    // If the one-shot action of the transition already ran, and it is strict.
+    ActionSpent,
+
+    // If a guard of the transition rejected the event.
+    GuardRejected,
+
+    // If the transition already happened the maximum number of times.
+    TransitionExhausted,
+
+    // If the transition happened recently and its cooldown has not passed.
+    CoolingDown,
+
+    // If the transition could not be recorded in the journal, contains the reason.
+    Journal(String),
+
+    // If the machine was redirected to a state that is not a state of the machine.
+    UnknownState,
 }
 
 impl std::error::Error for TransitionError {}
@@ -16,6 +54,20 @@ impl Debug for TransitionError {
         match self {
             Self::Done => write!(f, "state machine is done"),
             Self::InvalidTransition => write!(f, "invalid transition"),
+            Self::Unmapped => write!(f, "event cannot be mapped"),
+            Self::Cancelled => write!(f, "transition was cancelled"),
+            Self::ActionPanicked(message) => write!(f, "action panicked: {message}"),
+            Self::Poisoned => write!(f, "state machine is poisoned"),
+            Self::Paused => write!(f, "state machine is paused"),
+            Self::ReentrantSend => {
+                write!(f, "event was sent while the machine handles other event")
+            }
+            Self::ActionSpent => write!(f, "the action of the transition already ran"),
+            Self::GuardRejected => write!(f, "transition was rejected by a guard"),
+            Self::TransitionExhausted => write!(f, "transition cannot happen again"),
+            Self::CoolingDown => write!(f, "transition is cooling down"),
+            Self::Journal(reason) => write!(f, "transition cannot be recorded: {reason}"),
+            Self::UnknownState => write!(f, "state is not a state of the machine"),
         }
     }
 }
@@ -25,3 +77,348 @@ impl Display for TransitionError {
         <Self as Debug>::fmt(self, f)
     }
 }
+
+/// An error ocurred while accessing a `SharedMachine`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SharedError {
+    // If the transition failed.
+    Transition(TransitionError),
+
+    // If the machine is locked and the operation would block.
+    WouldBlock,
+
+    // If a thread panicked while holding the machine lock.
+    Poisoned,
+
+    // If the machine did not reach the waited condition in time.
+    Timeout,
+}
+
+impl std::error::Error for SharedError {}
+
+impl Debug for SharedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Transition(err) => write!(f, "{err}"),
+            Self::WouldBlock => write!(f, "state machine is locked"),
+            Self::Poisoned => write!(f, "state machine lock is poisoned"),
+            Self::Timeout => write!(f, "timed out waiting for the state machine"),
+        }
+    }
+}
+
+impl Display for SharedError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+impl From<TransitionError> for SharedError {
+    fn from(value: TransitionError) -> Self {
+        SharedError::Transition(value)
+    }
+}
+
+/// An error ocurred while adding a transition to a state machine.
+#[derive(Clone, PartialEq, Eq)]
+pub enum BuildError {
+    // If a transition already exists for the event from the state.
+    DuplicateTransition,
+
+    // If adding the transition exceeds one of the limits of the machine.
+    LimitExceeded {
+        which: LimitKind,
+        limit: usize,
+        actual: usize,
+    },
+
+    // If the machine uses a feature not supported by what is being built from it.
+    Unsupported {
+        feature: &'static str,
+    },
+}
+
+impl std::error::Error for BuildError {}
+
+impl Debug for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateTransition => write!(f, "a transition already exists for the event"),
+            Self::LimitExceeded {
+                which,
+                limit,
+                actual,
+            } => write!(
+                f,
+                "the number of {which} exceeds the limit of {limit}, it would be {actual}"
+            ),
+            Self::Unsupported { feature } => write!(f, "{feature} are not supported"),
+        }
+    }
+}
+
+impl Display for BuildError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// The size of a state machine that exceeded its limit, see `BuildError::LimitExceeded`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// The number of distinct states.
+    States,
+
+    /// The number of transitions.
+    Transitions,
+
+    /// The number of distinct events.
+    Events,
+}
+
+impl Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::States => write!(f, "states"),
+            Self::Transitions => write!(f, "transitions"),
+            Self::Events => write!(f, "events"),
+        }
+    }
+}
+
+/// An error ocurred while undoing a transition.
+#[derive(Clone, PartialEq, Eq)]
+pub enum UndoError {
+    // If the machine was not built with `with_undo`.
+    Disabled,
+
+    // If there is no transition to undo.
+    Empty,
+}
+
+impl std::error::Error for UndoError {}
+
+impl Debug for UndoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Disabled => write!(f, "undo is not enabled"),
+            Self::Empty => write!(f, "there is no transition to undo"),
+        }
+    }
+}
+
+impl Display for UndoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// An error ocurred when there is no transition for the event from the state, returned by `Machine::set_action`.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct UnknownTransition;
+
+impl std::error::Error for UnknownTransition {}
+
+impl Debug for UnknownTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "there is no transition for the event from the state")
+    }
+}
+
+impl Display for UnknownTransition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// An error ocurred while running a state machine to completion.
+#[derive(Clone, PartialEq, Eq)]
+pub enum RunError<S, E> {
+    // If an event could not be handled.
+    Rejected {
+        state: S,
+        event: E,
+        error: TransitionError,
+    },
+
+    // If the events ran out before the machine was done.
+    Incomplete {
+        state: S,
+    },
+
+    // If there were events left after the machine was done.
+    Leftover {
+        state: S,
+        event: E,
+    },
+}
+
+impl<S: Debug, E: Debug> std::error::Error for RunError<S, E> {}
+
+impl<S: Debug, E: Debug> Debug for RunError<S, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rejected {
+                state,
+                event,
+                error,
+            } => write!(f, "event {event:?} rejected in state {state:?}: {error}"),
+            Self::Incomplete { state } => {
+                write!(f, "events ran out in non final state {state:?}")
+            }
+            Self::Leftover { state, event } => {
+                write!(f, "event {event:?} left after finishing in state {state:?}")
+            }
+        }
+    }
+}
+
+impl<S: Debug, E: Debug> Display for RunError<S, E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// An error ocurred while sending a value converted into an event, returned by `Machine::try_send_into`.
+#[derive(Clone, PartialEq, Eq)]
+pub enum SendIntoError<C> {
+    // If the value could not be converted into an event, contains the conversion error.
+    Conversion(C),
+
+    // If the transition failed.
+    Transition(TransitionError),
+}
+
+impl<C: Debug> std::error::Error for SendIntoError<C> {}
+
+impl<C: Debug> Debug for SendIntoError<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Conversion(err) => write!(f, "value cannot be converted into an event: {err:?}"),
+            Self::Transition(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl<C: Debug> Display for SendIntoError<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+impl<C> From<TransitionError> for SendIntoError<C> {
+    fn from(value: TransitionError) -> Self {
+        SendIntoError::Transition(value)
+    }
+}
+
+/// An error ocurred while loading a state machine from a definition.
+#[cfg(feature = "loader")]
+#[derive(Clone, PartialEq, Eq)]
+pub enum LoadError {
+    // If the definition is not valid, contains the reason.
+    Parse(String),
+
+    // If a transition uses an action that was not registered.
+    UnknownAction(String),
+
+    // If a transition goes to a state that is not declared.
+    UnknownState(String),
+
+    // If a state cannot be converted to the state type.
+    InvalidState(String),
+
+    // If an event cannot be converted to the event type.
+    InvalidEvent(String),
+
+    // If there is more than one transition for the event from the state.
+    DuplicateTransition { from: String, event: String },
+
+    // If the machine exceeds the limits of the loader.
+    Build(BuildError),
+}
+
+#[cfg(feature = "loader")]
+impl std::error::Error for LoadError {}
+
+#[cfg(feature = "loader")]
+impl Debug for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Parse(reason) => write!(f, "invalid definition: {reason}"),
+            Self::UnknownAction(name) => write!(f, "unknown action `{name}`"),
+            Self::UnknownState(name) => write!(f, "unknown state `{name}`"),
+            Self::InvalidState(name) => write!(f, "invalid state `{name}`"),
+            Self::InvalidEvent(name) => write!(f, "invalid event `{name}`"),
+            Self::DuplicateTransition { from, event } => {
+                write!(f, "duplicated transition from `{from}` on `{event}`")
+            }
+            Self::Build(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+#[cfg(feature = "loader")]
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
+
+/// An error ocurred while restoring a state machine from a journal.
+#[cfg(feature = "journal")]
+#[derive(Clone, PartialEq, Eq)]
+pub enum RestoreError<S> {
+    // If the journal could not be read, contains the reason.
+    Io(String),
+
+    // If the record at the index could not be decoded.
+    Corrupted {
+        index: usize,
+    },
+
+    // If the event of the record at the index was rejected.
+    Rejected {
+        index: usize,
+        error: TransitionError,
+    },
+
+    // If the replayed transition of the record at the index is not the recorded one.
+    Diverged {
+        index: usize,
+        expected: (S, S),
+        actual: (S, S),
+    },
+}
+
+#[cfg(feature = "journal")]
+impl<S: Debug> std::error::Error for RestoreError<S> {}
+
+#[cfg(feature = "journal")]
+impl<S: Debug> Debug for RestoreError<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(reason) => write!(f, "journal cannot be read: {reason}"),
+            Self::Corrupted { index } => write!(f, "journal record {index} is corrupted"),
+            Self::Rejected { index, error } => {
+                write!(f, "journal record {index} was rejected: {error}")
+            }
+            Self::Diverged {
+                index,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "journal record {index} diverged, expected {:?} -> {:?} but was {:?} -> {:?}",
+                expected.0, expected.1, actual.0, actual.1
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "journal")]
+impl<S: Debug> Display for RestoreError<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        <Self as Debug>::fmt(self, f)
+    }
+}
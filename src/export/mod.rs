@@ -0,0 +1,10 @@
+/// PlantUML state diagrams.
+pub mod plantuml;
+
+/// SCXML documents.
+#[cfg(feature = "scxml")]
+pub mod scxml;
+
+/// XState machine configs.
+#[cfg(feature = "xstate")]
+pub mod xstate;
@@ -0,0 +1,208 @@
+use crate::graph::Graph;
+use std::fmt::Write;
+
+/// Renders the given graph as a PlantUML state diagram.
+///
+/// The `current` state, if any, is used as initial state and marked with the `<<current>>` stereotype.
+/// The ignored events are drawn as dashed self loops, the name of the graph is the title of the diagram
+/// and the labels of the transitions follow their events.
+/// Nodes and edges are emitted in the order they appear in the graph, so the output is stable.
+pub fn render<S, E>(
+    graph: &Graph<S, E>,
+    current: Option<&S>,
+    state_label: impl Fn(&S) -> String,
+    event_label: impl Fn(&E) -> String,
+) -> String
+where
+    S: PartialEq,
+{
+    let mut out = String::new();
+    let current = current.and_then(|s| graph.node_index(s));
+
+    out.push_str("@startuml\n");
+
+    if let Some(name) = graph.name() {
+        writeln!(out, "title {}", escape(name)).unwrap();
+    }
+
+    if current.is_some() {
+        out.push_str("skinparam state {\n");
+        out.push_str("  BackgroundColor<<current>> LightBlue\n");
+        out.push_str("}\n");
+    }
+
+    for (index, state) in graph.nodes() {
+        let label = escape(&state_label(state));
+        write!(out, "state \"{label}\" as s{}", index.index()).unwrap();
+
+        if Some(index) == current {
+            out.push_str(" <<current>>");
+        }
+
+        out.push('\n');
+    }
+
+    if let Some(index) = current {
+        writeln!(out, "[*] --> s{}", index.index()).unwrap();
+    }
+
+    let mut finals = Vec::new();
+
+    for (from, to, edge) in graph.edges() {
+        let mut label = escape(&event_label(&edge.event));
+
+        if let Some(transition) = &edge.label {
+            write!(label, " ({})", escape(transition)).unwrap();
+        }
+
+        let arrow = if edge.is_ignored {
+            "-[dashed]->"
+        } else {
+            "-->"
+        };
+        writeln!(out, "s{} {arrow} s{} : {label}", from.index(), to.index()).unwrap();
+
+        if edge.is_final && !finals.contains(&to) {
+            finals.push(to);
+        }
+    }
+
+    for index in finals {
+        writeln!(out, "s{} --> [*]", index.index()).unwrap();
+    }
+
+    out.push_str("@enduml\n");
+    out
+}
+
+// PlantUML cannot escape quotes or line breaks inside labels, so those are replaced.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("&quot;"),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running { speed: u32 },
+        Stopped,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Start,
+        Stop,
+    }
+
+    #[test]
+    fn to_plantuml_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Running { speed: 1 }),
+            )
+            .on_next(
+                Builder::new(State::Running { speed: 1 })
+                    .on(Event::Stop)
+                    .go_to(State::Stopped)
+                    .is_final(),
+            )
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Stop)
+                    .go_to(State::Stopped)
+                    .is_final(),
+            );
+
+        let expected = r#"@startuml
+state "Idle" as s0
+state "Running { speed: 1 }" as s1
+state "Stopped" as s2
+s0 --> s1 : Start
+s0 --> s2 : Stop
+s1 --> s2 : Stop
+s2 --> [*]
+@enduml
+"#;
+
+        assert_eq!(sm.to_plantuml(), expected);
+    }
+
+    #[test]
+    fn to_plantuml_with_labeler_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new("a").on(()).go_to("b\"c"))
+            .start("b\"c");
+
+        let expected = r#"@startuml
+skinparam state {
+  BackgroundColor<<current>> LightBlue
+}
+state "A" as s0
+state "B&quot;C" as s1 <<current>>
+[*] --> s1
+s0 --> s1 : ()
+@enduml
+"#;
+
+        assert_eq!(sm.to_plantuml_with(|s| s.to_uppercase()), expected);
+    }
+
+    #[test]
+    fn to_plantuml_labels_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Stopped)
+                    .label("skip \"run\""),
+            )
+            .named("job");
+
+        let expected = r#"@startuml
+title job
+state "Idle" as s0
+state "Stopped" as s1
+s0 --> s1 : Start (skip &quot;run&quot;)
+@enduml
+"#;
+
+        assert_eq!(sm.to_plantuml(), expected);
+    }
+
+    #[test]
+    fn to_plantuml_ignored_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Stopped),
+            )
+            .ignore(State::Idle, Event::Stop);
+
+        let expected = r#"@startuml
+state "Idle" as s0
+state "Stopped" as s1
+s0 --> s1 : Start
+s0 -[dashed]-> s0 : Stop
+@enduml
+"#;
+
+        assert_eq!(sm.to_plantuml(), expected);
+    }
+}
@@ -0,0 +1,207 @@
+use crate::graph::Graph;
+use std::fmt::Write;
+
+/// Renders the given graph as a SCXML document.
+///
+/// The `initial` state, if any, is set as the `initial` attribute of the document.
+/// States that are only reached by final transitions are emitted as `<final>` elements,
+/// the transitions out of those states are omitted because the machine is done when reaching them.
+/// The ignored events are emitted as transitions without target,
+/// and the name of the graph, if any, is set as the `name` attribute of the document.
+pub fn render<S, E>(
+    graph: &Graph<S, E>,
+    initial: Option<&S>,
+    state_label: impl Fn(&S) -> String,
+    event_label: impl Fn(&E) -> String,
+) -> String
+where
+    S: PartialEq,
+{
+    let mut out = String::new();
+    let labels = graph
+        .nodes()
+        .map(|(_, state)| escape(&state_label(state)))
+        .collect::<Vec<_>>();
+
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<scxml xmlns=\"http://www.w3.org/2005/07/scxml\" version=\"1.0\"");
+
+    if let Some(name) = graph.name() {
+        write!(out, " name=\"{}\"", escape(name)).unwrap();
+    }
+
+    if let Some(index) = initial.and_then(|s| graph.node_index(s)) {
+        write!(out, " initial=\"{}\"", labels[index.index()]).unwrap();
+    }
+
+    out.push_str(">\n");
+
+    for (index, _) in graph.nodes() {
+        let id = &labels[index.index()];
+        let mut incoming = graph.edges().filter(|(_, to, _)| *to == index).peekable();
+        let is_final = incoming.peek().is_some() && incoming.all(|(_, _, edge)| edge.is_final);
+
+        if is_final {
+            writeln!(out, "  <final id=\"{id}\"/>").unwrap();
+            continue;
+        }
+
+        let mut outgoing = graph
+            .edges()
+            .filter(|(from, _, _)| *from == index)
+            .peekable();
+
+        if outgoing.peek().is_none() {
+            writeln!(out, "  <state id=\"{id}\"/>").unwrap();
+            continue;
+        }
+
+        writeln!(out, "  <state id=\"{id}\">").unwrap();
+
+        for (_, to, edge) in outgoing {
+            let event = escape(&event_label(&edge.event));
+
+            if edge.is_ignored {
+                writeln!(out, "    <transition event=\"{event}\"/>").unwrap();
+                continue;
+            }
+
+            let target = &labels[to.index()];
+            writeln!(
+                out,
+                "    <transition event=\"{event}\" target=\"{target}\"/>"
+            )
+            .unwrap();
+        }
+
+        out.push_str("  </state>\n");
+    }
+
+    out.push_str("</scxml>\n");
+    out
+}
+
+// Escapes the characters that are not allowed inside XML attributes.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' => out.push_str("&#10;"),
+            '\r' => out.push_str("&#13;"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::escape;
+    use crate::blocking::{Builder, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running { speed: u32 },
+        Stopped,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Start,
+        Stop,
+    }
+
+    #[test]
+    fn to_scxml_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Running { speed: 1 }),
+            )
+            .on_next(
+                Builder::new(State::Running { speed: 1 })
+                    .on(Event::Stop)
+                    .go_to(State::Stopped)
+                    .is_final(),
+            )
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Stop)
+                    .go_to(State::Stopped)
+                    .is_final(),
+            )
+            .start(State::Idle);
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" initial="Idle">
+  <state id="Idle">
+    <transition event="Start" target="Running { speed: 1 }"/>
+    <transition event="Stop" target="Stopped"/>
+  </state>
+  <state id="Running { speed: 1 }">
+    <transition event="Stop" target="Stopped"/>
+  </state>
+  <final id="Stopped"/>
+</scxml>
+"#;
+
+        assert_eq!(sm.to_scxml(), expected);
+    }
+
+    #[test]
+    fn to_scxml_escape_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new("State<Foo>").on("a&b").go_to("\"quoted\""))
+            .on_next(Builder::new("\"quoted\"").on("back").go_to("State<Foo>"))
+            .named("Machine<Foo>");
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0" name="Machine&lt;Foo&gt;">
+  <state id="State&lt;Foo&gt;">
+    <transition event="a&amp;b" target="&quot;quoted&quot;"/>
+  </state>
+  <state id="&quot;quoted&quot;">
+    <transition event="back" target="State&lt;Foo&gt;"/>
+  </state>
+</scxml>
+"#;
+
+        assert_eq!(
+            sm.to_scxml_with(|s| s.to_string(), |e| e.to_string()),
+            expected
+        );
+        assert_eq!(escape("it's\n"), "it&apos;s&#10;");
+    }
+
+    #[test]
+    fn to_scxml_ignored_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Stopped),
+            )
+            .ignore(State::Idle, Event::Stop);
+
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<scxml xmlns="http://www.w3.org/2005/07/scxml" version="1.0">
+  <state id="Idle">
+    <transition event="Start" target="Stopped"/>
+    <transition event="Stop"/>
+  </state>
+  <state id="Stopped"/>
+</scxml>
+"#;
+
+        assert_eq!(sm.to_scxml(), expected);
+    }
+}
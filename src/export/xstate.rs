@@ -0,0 +1,276 @@
+use crate::common::json::Value;
+use crate::graph::Graph;
+
+/// Renders the given graph as a XState machine config in JSON.
+///
+/// The `initial` state, if any, is set as the `initial` key of the config.
+/// The targets of final transitions are marked with `"type": "final"`,
+/// and the events with many candidate transitions from a state have an array of targets.
+/// The ignored events are emitted as transitions without target,
+/// and the labels of the transitions as their `description`.
+/// The states and transitions are emitted in the order they appear in the graph, so the output is stable.
+pub fn render<S, E>(
+    graph: &Graph<S, E>,
+    id: &str,
+    initial: Option<&S>,
+    state_label: impl Fn(&S) -> String,
+    event_label: impl Fn(&E) -> String,
+) -> String
+where
+    S: PartialEq,
+{
+    let labels = graph
+        .nodes()
+        .map(|(_, state)| state_label(state))
+        .collect::<Vec<_>>();
+
+    let mut config = vec![(String::from("id"), Value::String(id.to_owned()))];
+
+    if let Some(index) = initial.and_then(|s| graph.node_index(s)) {
+        config.push((
+            String::from("initial"),
+            Value::String(labels[index.index()].clone()),
+        ));
+    }
+
+    let states = graph
+        .nodes()
+        .map(|(index, _)| {
+            let mut on: Vec<(String, Vec<Target>)> = Vec::new();
+
+            for (_, to, edge) in graph.edges().filter(|(from, _, _)| *from == index) {
+                let label = event_label(&edge.event);
+                let target = Target {
+                    state: (!edge.is_ignored).then(|| labels[to.index()].clone()),
+                    description: edge.label.clone(),
+                };
+
+                match on.iter_mut().find(|(l, _)| *l == label) {
+                    Some((_, targets)) => targets.push(target),
+                    None => on.push((label, vec![target])),
+                }
+            }
+
+            // The candidate transitions of an event are emitted as an array of targets
+            let on = on
+                .into_iter()
+                .map(|(label, mut targets)| {
+                    let value = match targets.as_mut_slice() {
+                        [Target {
+                            state: Some(state),
+                            description: None,
+                        }] => Value::String(std::mem::take(state)),
+                        [target] => std::mem::take(target).into_value(),
+                        _ => Value::Array(targets.into_iter().map(Target::into_value).collect()),
+                    };
+
+                    (label, value)
+                })
+                .collect::<Vec<_>>();
+
+            let is_final = graph
+                .edges()
+                .any(|(_, to, edge)| to == index && edge.is_final);
+
+            let mut state = Vec::new();
+
+            if !on.is_empty() {
+                state.push((String::from("on"), Value::Object(on)));
+            }
+
+            if is_final {
+                state.push((String::from("type"), Value::String(String::from("final"))));
+            }
+
+            (labels[index.index()].clone(), Value::Object(state))
+        })
+        .collect::<Vec<_>>();
+
+    config.push((String::from("states"), Value::Object(states)));
+
+    let mut out = Value::Object(config).to_pretty_string();
+    out.push('\n');
+    out
+}
+
+// A transition of an event, without state if the event is ignored.
+#[derive(Default)]
+struct Target {
+    state: Option<String>,
+    description: Option<String>,
+}
+
+impl Target {
+    fn into_value(self) -> Value {
+        let state = self
+            .state
+            .map(|state| (String::from("target"), Value::String(state)));
+        let description = self
+            .description
+            .map(|description| (String::from("description"), Value::String(description)));
+
+        Value::Object(state.into_iter().chain(description).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+    use crate::common::json;
+    use std::time::Duration;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running,
+        Stopped,
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Event {
+        Start,
+        Stop,
+    }
+
+    #[test]
+    fn to_xstate_json_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Running),
+            )
+            .on_next(
+                Builder::new(State::Running)
+                    .on(Event::Stop)
+                    .go_to(State::Stopped)
+                    .is_final(),
+            )
+            .start(State::Idle);
+
+        let expected = r#"{
+  "id": "machine",
+  "initial": "Idle",
+  "states": {
+    "Idle": {
+      "on": {
+        "Start": "Running"
+      }
+    },
+    "Running": {
+      "on": {
+        "Stop": "Stopped"
+      }
+    },
+    "Stopped": {
+      "type": "final"
+    }
+  }
+}
+"#;
+
+        assert_eq!(sm.to_xstate_json(), expected);
+    }
+
+    #[test]
+    fn to_xstate_json_escape_test() {
+        let sm = Machine::new().on_next(Builder::new("say \"hi\"").on("a\\b").go_to("line\nbreak"));
+
+        let output = sm.to_xstate_json_with("quote\"d", |s| s.to_string(), |e| e.to_string());
+        let value = json::parse(&output).unwrap();
+
+        assert_eq!(value.get("id").and_then(|v| v.as_str()), Some("quote\"d"));
+        assert!(value.get("initial").is_none());
+
+        let on = value
+            .get("states")
+            .and_then(|v| v.get("say \"hi\""))
+            .and_then(|v| v.get("on"))
+            .and_then(|v| v.get("a\\b"));
+        assert_eq!(on.and_then(|v| v.as_str()), Some("line\nbreak"));
+    }
+
+    #[test]
+    fn to_xstate_json_candidates_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new("a")
+                    .on("go")
+                    .go_to("b")
+                    .guard_after(Duration::ZERO),
+            )
+            .on_next(
+                Builder::new("a")
+                    .on("go")
+                    .go_to("c")
+                    .guard_within(Duration::MAX),
+            );
+
+        let output = sm.to_xstate_json_with("machine", |s| s.to_string(), |e| e.to_string());
+        let value = json::parse(&output).unwrap();
+
+        let targets = value
+            .get("states")
+            .and_then(|v| v.get("a"))
+            .and_then(|v| v.get("on"))
+            .and_then(|v| v.get("go"))
+            .and_then(|v| v.as_array())
+            .unwrap()
+            .iter()
+            .map(|v| v.get("target").and_then(|t| t.as_str()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(targets, [Some("b"), Some("c")]);
+    }
+
+    #[test]
+    fn to_xstate_json_labels_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new("a").on("go").go_to("b").label("forward"))
+            .on_next(Builder::new("b").on("go").go_to("a"))
+            .named("shuttle");
+
+        let value = json::parse(&sm.to_xstate_json()).unwrap();
+        let states = value.get("states");
+        let go = |state| {
+            states
+                .and_then(|v| v.get(state))
+                .and_then(|v| v.get("on"))
+                .and_then(|v| v.get("\"go\""))
+        };
+
+        assert_eq!(value.get("id").and_then(|v| v.as_str()), Some("shuttle"));
+        assert_eq!(
+            go("\"a\"").and_then(|v| v.get("description")),
+            Some(&json::Value::String(String::from("forward")))
+        );
+        assert_eq!(
+            go("\"a\"").and_then(|v| v.get("target")),
+            Some(&json::Value::String(String::from("\"b\"")))
+        );
+        assert_eq!(go("\"b\"").and_then(|v| v.as_str()), Some("\"a\""));
+    }
+
+    #[test]
+    fn to_xstate_json_ignored_test() {
+        let sm = Machine::new()
+            .on_next(Builder::new("a").on("go").go_to("b"))
+            .ignore("a", "stay");
+
+        let output = sm.to_xstate_json_with("machine", |s| s.to_string(), |e| e.to_string());
+        let value = json::parse(&output).unwrap();
+        let on = value
+            .get("states")
+            .and_then(|v| v.get("a"))
+            .and_then(|v| v.get("on"));
+
+        assert_eq!(
+            on.and_then(|v| v.get("go")).and_then(|v| v.as_str()),
+            Some("b")
+        );
+        assert_eq!(
+            on.and_then(|v| v.get("stay")),
+            Some(&json::Value::Object(Vec::new()))
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use crate::blocking::{ContextMut, OnAction};
+use crate::error::TransitionError;
+use std::cell::Cell;
+
+/// A transition of a `FixedMachine` as `(from, event, to, is_final)`.
+pub type FixedTransition<S, E> = (S, E, S, bool);
+
+/// A state machine which transitions are stored in an array known at compile time.
+///
+/// The machine never allocates, finding a transition is a scan over the array,
+/// which for small tables of `Copy` states and events is faster than the lookups of the other machines.
+/// Instead of an action per transition, a single action receives all the transitions and can match on them.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::fixed::FixedMachine;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Light {
+///     Off,
+///     On,
+/// }
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// enum Switch {
+///     Toggle,
+/// }
+///
+/// const LIGHT: FixedMachine<Light, Switch, 2> = FixedMachine::new(
+///     [
+///         (Light::Off, Switch::Toggle, Light::On, false),
+///         (Light::On, Switch::Toggle, Light::Off, false),
+///     ],
+///     Light::Off,
+/// );
+///
+/// let mut sm = LIGHT;
+/// assert_eq!(sm.send(Switch::Toggle), Ok(Light::Off));
+/// assert_eq!(*sm.current(), Light::On);
+/// ```
+#[derive(Debug, Clone)]
+pub struct FixedMachine<S, E, const N: usize, Ctx = (), A = ()> {
+    table: [FixedTransition<S, E>; N],
+    current: S,
+    done: bool,
+    context: Ctx,
+    action: A,
+}
+
+impl<S, E, const N: usize> FixedMachine<S, E, N> {
+    /// Constructs a state machine with the given transitions, starting in the given state.
+    pub const fn new(table: [FixedTransition<S, E>; N], initial: S) -> Self {
+        FixedMachine::with_context(table, initial, ())
+    }
+}
+
+impl<S, E, const N: usize, Ctx> FixedMachine<S, E, N, Ctx> {
+    /// Constructs a state machine with the given transitions and context, starting in the given state.
+    pub const fn with_context(table: [FixedTransition<S, E>; N], initial: S, context: Ctx) -> Self {
+        FixedMachine {
+            table,
+            current: initial,
+            done: false,
+            context,
+            action: (),
+        }
+    }
+}
+
+impl<S, E, const N: usize, Ctx, A> FixedMachine<S, E, N, Ctx, A> {
+    /// Sets the action executed on every transition, it replaces the previous one.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::blocking::ContextMut;
+    /// use restate::fixed::FixedMachine;
+    ///
+    /// let mut sm = FixedMachine::with_context([(0, 'a', 1, false), (1, 'b', 0, false)], 0, 0)
+    ///     .action(|cx: ContextMut<i32, char, i32>| match cx.event {
+    ///         'a' => *cx.context += 1,
+    ///         _ => *cx.context -= 10,
+    ///     });
+    ///
+    /// sm.send('a').unwrap();
+    /// sm.send('b').unwrap();
+    /// assert_eq!(*sm.context(), -9);
+    /// ```
+    pub fn action<F>(self, action: F) -> FixedMachine<S, E, N, Ctx, F>
+    where
+        F: OnAction<S, E, Ctx>,
+    {
+        FixedMachine {
+            table: self.table,
+            current: self.current,
+            done: self.done,
+            context: self.context,
+            action,
+        }
+    }
+
+    /// Returns the current state.
+    pub fn current(&self) -> &S {
+        &self.current
+    }
+
+    /// Returns the context used for this state machine.
+    pub fn context(&self) -> &Ctx {
+        &self.context
+    }
+
+    /// Returns a mutable reference to the context used for this state machine.
+    pub fn context_mut(&mut self) -> &mut Ctx {
+        &mut self.context
+    }
+
+    /// Returns `true` if this state machine had done executing.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+}
+
+impl<S, E, const N: usize, Ctx, A> FixedMachine<S, E, N, Ctx, A>
+where
+    S: Copy + PartialEq,
+    E: PartialEq,
+    A: OnAction<S, E, Ctx>,
+{
+    /// Triggers a transition.
+    ///
+    /// # Returns
+    /// - Ok(S): The previous state.
+    /// - Err(TransitionError): If the transition was not successful
+    pub fn send(&mut self, event: E) -> Result<S, TransitionError> {
+        if self.done {
+            return Err(TransitionError::Done);
+        }
+
+        let (_, _, next, is_final) = *self
+            .table
+            .iter()
+            .find(|(from, e, _, _)| *from == self.current && *e == event)
+            .ok_or(TransitionError::InvalidTransition)?;
+
+        let abort = Cell::new(None);
+        let finality = Cell::new(is_final);
+
+        self.action.call(ContextMut {
+            from: &self.current,
+            to: &next,
+            event: &event,
+            context: &mut self.context,
+            state_data: None,
+            abort: &abort,
+            is_final: &finality,
+            sender: None,
+        });
+
+        // If the action cancelled the transition, the machine stays in the previous state
+        if let Some(abort) = abort.get() {
+            return Err(abort.into());
+        }
+
+        self.done = finality.get();
+        Ok(std::mem::replace(&mut self.current, next))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FixedMachine;
+    use crate::blocking::ContextMut;
+    use crate::error::TransitionError;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running,
+        Stopped,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Event {
+        Start,
+        Tick,
+        Stop,
+    }
+
+    const TABLE: [(State, Event, State, bool); 3] = [
+        (State::Idle, Event::Start, State::Running, false),
+        (State::Running, Event::Tick, State::Running, false),
+        (State::Running, Event::Stop, State::Stopped, true),
+    ];
+
+    const MACHINE: FixedMachine<State, Event, 3> = FixedMachine::new(TABLE, State::Idle);
+
+    #[test]
+    fn send_test() {
+        let mut sm = MACHINE;
+
+        assert_eq!(sm.send(Event::Start), Ok(State::Idle));
+        assert_eq!(
+            sm.send(Event::Start),
+            Err(TransitionError::InvalidTransition)
+        );
+        assert_eq!(sm.send(Event::Tick), Ok(State::Running));
+        assert!(!sm.is_done());
+
+        assert_eq!(sm.send(Event::Stop), Ok(State::Running));
+        assert_eq!(*sm.current(), State::Stopped);
+        assert!(sm.is_done());
+        assert_eq!(sm.send(Event::Tick), Err(TransitionError::Done));
+    }
+
+    #[test]
+    fn action_test() {
+        let mut sm = FixedMachine::with_context(TABLE, State::Idle, 0).action(
+            |cx: ContextMut<State, Event, i32>| match cx.event {
+                Event::Tick if *cx.context >= 2 => cx.cancel(),
+                Event::Tick => *cx.context += 1,
+                Event::Stop => cx.set_final(false),
+                Event::Start => {}
+            },
+        );
+
+        sm.send(Event::Start).unwrap();
+        sm.send(Event::Tick).unwrap();
+        sm.send(Event::Tick).unwrap();
+        assert_eq!(sm.send(Event::Tick), Err(TransitionError::Cancelled));
+        assert_eq!(*sm.context(), 2);
+
+        sm.send(Event::Stop).unwrap();
+        assert_eq!(*sm.current(), State::Stopped);
+        assert!(!sm.is_done());
+    }
+}
@@ -0,0 +1,282 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+
+/// The index of a node in a `Graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NodeIndex(usize);
+
+impl NodeIndex {
+    /// Returns the index as `usize`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The weight of an edge, the event that triggers a transition.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edge<E> {
+    /// The event that triggers the transition.
+    pub event: E,
+
+    /// Whether the transition completes the state machine.
+    pub is_final: bool,
+
+    /// Whether the event is dropped without a transition, the edge is a self loop.
+    pub is_ignored: bool,
+
+    /// The label of the transition, see `Builder::label`.
+    pub label: Option<String>,
+}
+
+/// A directed graph where the nodes are the states of a machine and the edges its transitions.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+///
+/// let sm = Machine::new()
+///     .on_next(Builder::new("a").on(1).go_to("b"))
+///     .on_next(Builder::new("b").on(2).go_to("c").is_final())
+///     .start("a");
+///
+/// let graph = sm.to_graph();
+/// let a = graph.node_index(&"a").unwrap();
+/// let c = graph.node_index(&"c").unwrap();
+///
+/// assert_eq!(graph.node_count(), 3);
+/// assert_eq!(graph.edge_count(), 2);
+/// assert_eq!(graph.shortest_path(a, c).unwrap().len(), 3);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Graph<S, E> {
+    name: Option<String>,
+    nodes: Vec<S>,
+    edges: Vec<(NodeIndex, NodeIndex, Edge<E>)>,
+}
+
+impl<S, E> Graph<S, E> {
+    /// Returns an empty graph.
+    pub fn new() -> Self {
+        Graph {
+            name: None,
+            nodes: Vec::new(),
+            edges: Vec::new(),
+        }
+    }
+
+    /// Sets the name of the graph, used as the title of the exported diagrams.
+    pub fn set_name(&mut self, name: impl Into<String>) {
+        self.name = Some(name.into());
+    }
+
+    /// Returns the name of the graph, if any.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// Adds a node and returns its index.
+    pub fn add_node(&mut self, state: S) -> NodeIndex {
+        self.nodes.push(state);
+        NodeIndex(self.nodes.len() - 1)
+    }
+
+    /// Adds an edge between two nodes.
+    ///
+    /// # Panics
+    /// If any of the indices is out of bounds.
+    pub fn add_edge(&mut self, from: NodeIndex, to: NodeIndex, edge: Edge<E>) {
+        assert!(from.0 < self.nodes.len(), "node index out of bounds");
+        assert!(to.0 < self.nodes.len(), "node index out of bounds");
+        self.edges.push((from, to, edge));
+    }
+
+    /// Returns the number of nodes.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the number of edges.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Returns the state of the given node.
+    pub fn node_weight(&self, index: NodeIndex) -> Option<&S> {
+        self.nodes.get(index.0)
+    }
+
+    /// Returns an iterator over the nodes and its states.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeIndex, &S)> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, s)| (NodeIndex(i), s))
+    }
+
+    /// Returns an iterator over the edges as `(from, to, edge)`.
+    pub fn edges(&self) -> impl Iterator<Item = (NodeIndex, NodeIndex, &Edge<E>)> {
+        self.edges.iter().map(|(from, to, edge)| (*from, *to, edge))
+    }
+
+    /// Returns an iterator over the nodes reachable with one transition from the given node.
+    pub fn neighbors(&self, index: NodeIndex) -> impl Iterator<Item = NodeIndex> + '_ {
+        self.edges
+            .iter()
+            .filter(move |(from, _, _)| *from == index)
+            .map(|(_, to, _)| *to)
+    }
+
+    /// Returns a new graph with the same structure, mapping the states and events with the given functions.
+    pub fn map<S2, E2>(
+        self,
+        mut map_state: impl FnMut(S) -> S2,
+        mut map_event: impl FnMut(E) -> E2,
+    ) -> Graph<S2, E2> {
+        Graph {
+            name: self.name,
+            nodes: self.nodes.into_iter().map(&mut map_state).collect(),
+            edges: self
+                .edges
+                .into_iter()
+                .map(|(from, to, edge)| {
+                    let edge = Edge {
+                        event: map_event(edge.event),
+                        is_final: edge.is_final,
+                        is_ignored: edge.is_ignored,
+                        label: edge.label,
+                    };
+
+                    (from, to, edge)
+                })
+                .collect(),
+        }
+    }
+
+    /// Returns the shortest sequence of nodes from `from` to `to`, both included.
+    pub fn shortest_path(&self, from: NodeIndex, to: NodeIndex) -> Option<Vec<NodeIndex>> {
+        let mut parents = vec![None; self.nodes.len()];
+        let mut visited = vec![false; self.nodes.len()];
+        let mut queue = VecDeque::from([from]);
+        visited[from.0] = true;
+
+        while let Some(cur) = queue.pop_front() {
+            if cur == to {
+                let mut path = vec![cur];
+                while let Some(parent) = parents[path.last().unwrap().0] {
+                    path.push(parent);
+                }
+
+                path.reverse();
+                return Some(path);
+            }
+
+            for next in self.neighbors(cur) {
+                if !visited[next.0] {
+                    visited[next.0] = true;
+                    parents[next.0] = Some(cur);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+impl<S, E> Graph<S, E>
+where
+    S: PartialEq,
+{
+    /// Returns the index of the node with the given state.
+    pub fn node_index(&self, state: &S) -> Option<NodeIndex> {
+        self.nodes.iter().position(|s| s == state).map(NodeIndex)
+    }
+
+    // Returns the index of the node with the given state, adding it if missing.
+    pub(crate) fn get_or_add_node(&mut self, state: &S) -> NodeIndex
+    where
+        S: Clone,
+    {
+        match self.node_index(state) {
+            Some(index) => index,
+            None => self.add_node(state.clone()),
+        }
+    }
+}
+
+impl<S, E> Default for Graph<S, E> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocking::{Builder, Machine};
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Loading,
+        Loaded,
+        Failed,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Load,
+        Success,
+        Error,
+        Retry,
+    }
+
+    #[test]
+    fn to_graph_test() {
+        let sm = Machine::new()
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Load)
+                    .go_to(State::Loading),
+            )
+            .on_next(
+                Builder::new(State::Loading)
+                    .on(Event::Success)
+                    .go_to(State::Loaded)
+                    .is_final(),
+            )
+            .on_next(
+                Builder::new(State::Loading)
+                    .on(Event::Error)
+                    .go_to(State::Failed),
+            )
+            .on_next(
+                Builder::new(State::Failed)
+                    .on(Event::Retry)
+                    .go_to(State::Loading),
+            )
+            .start(State::Idle);
+
+        let graph = sm.to_graph();
+
+        // `Loaded` is a target-only state
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+
+        let final_edges = graph.edges().filter(|(_, _, e)| e.is_final).count();
+        assert_eq!(final_edges, 1);
+
+        let idle = graph.node_index(&State::Idle).unwrap();
+        let loaded = graph.node_index(&State::Loaded).unwrap();
+        let failed = graph.node_index(&State::Failed).unwrap();
+
+        let path = graph.shortest_path(failed, loaded).unwrap();
+        let path = path
+            .into_iter()
+            .map(|i| graph.node_weight(i).unwrap().clone())
+            .collect::<Vec<_>>();
+
+        assert_eq!(path, vec![State::Failed, State::Loading, State::Loaded]);
+        assert!(graph.shortest_path(loaded, idle).is_none());
+    }
+}
@@ -1,38 +1,38 @@
 //! # restate
-//! 
+//!
 //! restate is a Rust library that provides a simple way of defining and using finite state machines.
-//! 
+//!
 //! ## Installation
-//! 
+//!
 //! Add the following to your Cargo.toml file:
-//! 
+//!
 //! ```toml
 //! [dependencies]
 //! restate = "0.1.0-alpha"
 //! ```
-//! 
+//!
 //! Or use
-//! 
+//!
 //! ```bash
 //! cargo add restate
 //! ```
-//! 
+//!
 //! ## Usage
-//! 
+//!
 //! restate can be used to define state machines that can transition between different states based on events. Here is an example:
-//! 
+//!
 //! ```rust
 //! use restate::blocking::*;
-//! 
+//!
 //! #[derive(Debug, Clone, PartialEq, Eq)]
 //! struct Active;
-//! 
+//!
 //! #[derive(PartialEq, Eq)]
 //! enum CountEvent {
 //!     Increment,
 //!     Decrement,
 //! }
-//! 
+//!
 //! let mut sm = Machine::with_context(0)
 //!     .on_next(
 //!         Builder::self_transition(Active, CountEvent::Increment).action(
@@ -49,15 +49,15 @@
 //!         ),
 //!     )
 //!     .start(Active);
-//! 
+//!
 //! sm.send(CountEvent::Increment).unwrap();
 //! sm.send(CountEvent::Increment).unwrap();
 //! sm.send(CountEvent::Increment).unwrap();
 //! sm.send(CountEvent::Decrement).unwrap();
-//! 
+//!
 //! assert_eq!(*sm.context(), 2);
 //! ```
-//! 
+//!
 //! This example creates a state machine with a single state Active and two events Increment and Decrement. It then adds a self-transition for each event that increments or decrements an integer in the machine's context. Finally, it starts the machine with the Active state, sends some events to it, and checks the final value of the context.
 
 /// Provides a blocking version of the state machine.
@@ -66,5 +66,30 @@ pub mod blocking;
 /// Errors types for the crate.
 pub mod error;
 
+/// Graph representation of a state machine.
+pub mod graph;
+
+/// Exports state machines to other formats.
+pub mod export;
+
+/// Sources of time used by the state machines.
+pub mod clock;
+
+/// Random number generation used for simulations.
+pub mod random;
+
+/// The map of states and transitions used by the state machines.
+pub mod map;
+
+/// A state machine without allocations, for transitions known at compile time.
+pub mod fixed;
+
+/// Analyses over the transitions of state machines.
+pub mod analysis;
+
+/// Utilities for testing state machines.
+#[cfg(feature = "testing")]
+pub mod testing;
+
 //
 pub(crate) mod common;
@@ -0,0 +1,931 @@
+use crate::common::backend::{MapBackend, StateLookup};
+use std::ops::{Deref, DerefMut};
+use std::slice;
+
+// A list of nodes or transitions, that is frozen into a boxed slice when the machine starts.
+// Changing the length of a frozen list turns it back into a `Vec`.
+#[derive(Debug, Clone)]
+enum Slots<T> {
+    Growable(Vec<T>),
+    Frozen(Box<[T]>),
+}
+
+impl<T> Slots<T> {
+    #[cfg(test)]
+    fn capacity(&self) -> usize {
+        match self {
+            Slots::Growable(vec) => vec.capacity(),
+            Slots::Frozen(slice) => slice.len(),
+        }
+    }
+
+    fn is_frozen(&self) -> bool {
+        matches!(self, Slots::Frozen(_))
+    }
+
+    fn freeze(&mut self) {
+        if let Slots::Growable(vec) = self {
+            *self = Slots::Frozen(std::mem::take(vec).into_boxed_slice());
+        }
+    }
+
+    // Returns the list as a `Vec`, unfreezing it if needed.
+    fn vec_mut(&mut self) -> &mut Vec<T> {
+        if let Slots::Frozen(slice) = self {
+            *self = Slots::Growable(std::mem::take(slice).into_vec());
+        }
+
+        match self {
+            Slots::Growable(vec) => vec,
+            Slots::Frozen(_) => unreachable!(),
+        }
+    }
+
+    fn into_vec(self) -> Vec<T> {
+        match self {
+            Slots::Growable(vec) => vec,
+            Slots::Frozen(slice) => slice.into_vec(),
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for Slots<T> {
+    fn from(vec: Vec<T>) -> Self {
+        Slots::Growable(vec)
+    }
+}
+
+impl<T> Deref for Slots<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Slots::Growable(vec) => vec,
+            Slots::Frozen(slice) => slice,
+        }
+    }
+}
+
+impl<T> DerefMut for Slots<T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match self {
+            Slots::Growable(vec) => vec,
+            Slots::Frozen(slice) => slice,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct To<TEvent, T> {
+    event: TEvent,
+    to: T,
+}
+
+#[derive(Debug, Clone)]
+struct Node<TState, TEvent, T> {
+    from: TState,
+    next: Slots<To<TEvent, T>>,
+}
+
+// By default the `TransitionMap` is O(n) in most of the operations,
+// an optional index over the states makes the lookups O(log n) or O(1).
+
+/// A map that store the states and its transitions to other states when a event happens.
+///
+/// There is at most one transition for each event from a state, the values are usually the target states.
+/// The methods receive the event before the state where the transition starts.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::map::TransitionMap;
+///
+/// let mut map = TransitionMap::new();
+/// map.insert("start", "idle", "running");
+/// map.insert("stop", "running", "idle");
+///
+/// assert_eq!(map.len(), 2);
+/// assert_eq!(map.get(&"start", &"idle"), Some(&"running"));
+/// assert!(map.contains(&"stop", &"running"));
+/// assert!(map.try_insert("start", "idle", "paused").is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct TransitionMap<TState, TEvent, T> {
+    nodes: Slots<Node<TState, TEvent, T>>,
+
+    // The initial capacity of the transitions of each new node.
+    transitions_per_state: usize,
+
+    // The position of each state in `nodes`, the nodes are scanned if there is no index.
+    index: Option<StateLookup<TState>>,
+}
+
+impl<TState, TEvent, T> TransitionMap<TState, TEvent, T> {
+    /// Returns an empty map.
+    pub fn new() -> Self {
+        TransitionMap {
+            nodes: Vec::new().into(),
+            transitions_per_state: 0,
+            index: None,
+        }
+    }
+
+    /// Returns a map with space for the given number of states,
+    /// each new state will also have space for `transitions_per_state` transitions.
+    pub fn with_capacity(states: usize, transitions_per_state: usize) -> Self {
+        TransitionMap {
+            nodes: Vec::with_capacity(states).into(),
+            transitions_per_state,
+            index: None,
+        }
+    }
+
+    /// Returns the backend used to find the states.
+    pub fn backend(&self) -> MapBackend {
+        self.index
+            .as_ref()
+            .map(|index| index.backend())
+            .unwrap_or(MapBackend::Scan)
+    }
+
+    /// Uses the given index to find the states.
+    pub(crate) fn set_index(&mut self, mut index: StateLookup<TState>) {
+        for (pos, node) in self.nodes.iter().enumerate() {
+            index.insert(&node.from, pos);
+        }
+
+        self.index = Some(index);
+    }
+
+    /// Returns a map with the same states and events, and each value converted with the given function.
+    pub fn map_values<U>(self, mut f: impl FnMut(T) -> U) -> TransitionMap<TState, TEvent, U> {
+        let frozen = self.nodes.is_frozen();
+        let nodes = self
+            .nodes
+            .into_vec()
+            .into_iter()
+            .map(|node| Node {
+                from: node.from,
+                next: node
+                    .next
+                    .into_vec()
+                    .into_iter()
+                    .map(|next| To {
+                        event: next.event,
+                        to: f(next.to),
+                    })
+                    .collect::<Vec<_>>()
+                    .into(),
+            })
+            .collect::<Vec<_>>();
+
+        let mut map = TransitionMap {
+            nodes: nodes.into(),
+            transitions_per_state: self.transitions_per_state,
+            index: self.index,
+        };
+
+        if frozen {
+            map.freeze();
+        }
+
+        map
+    }
+
+    /// Returns the number of transitions.
+    pub fn len(&self) -> usize {
+        self.nodes.iter().map(|node| node.next.len()).sum()
+    }
+
+    /// Returns `true` if there are no transitions.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Shrinks the capacity of the map and all its states as much as possible.
+    pub fn shrink_to_fit(&mut self) {
+        if let Slots::Growable(nodes) = &mut self.nodes {
+            nodes.shrink_to_fit();
+        }
+
+        for node in self.nodes.iter_mut() {
+            if let Slots::Growable(next) = &mut node.next {
+                next.shrink_to_fit();
+            }
+        }
+    }
+
+    // Converts the nodes and their transitions into boxed slices, which are smaller and don't have spare capacity.
+    // Any later change that adds or removes transitions unfreezes the affected lists.
+    pub(crate) fn freeze(&mut self) {
+        self.nodes.freeze();
+
+        for node in self.nodes.iter_mut() {
+            node.next.freeze();
+        }
+    }
+
+    #[cfg(test)]
+    fn is_frozen(&self) -> bool {
+        self.nodes.is_frozen()
+    }
+
+    /// Returns an iterator over the events of each transition, an event is returned once per state it leaves.
+    pub fn events(&self) -> Events<'_, TState, TEvent, T> {
+        Events {
+            iter: self.nodes.iter(),
+            cur: None,
+        }
+    }
+
+    /// Returns an iterator over the states that have transitions.
+    pub fn states(&self) -> States<'_, TState, TEvent, T> {
+        States {
+            iter: self.nodes.iter(),
+        }
+    }
+
+    /// Returns an iterator over the transitions as `(from, event, to)`.
+    pub fn iter(&self) -> Iter<'_, TState, TEvent, T> {
+        Iter {
+            iter: self.nodes.iter(),
+            cur: None,
+        }
+    }
+
+    // Adds a transition known to be absent and returns its value.
+    fn push(&mut self, node: Option<usize>, event: TEvent, from: TState, to: T) -> &mut T {
+        let index = match node {
+            Some(index) => {
+                self.nodes[index].next.vec_mut().push(To { event, to });
+                index
+            }
+            None => {
+                // Insert node
+                let mut next = Vec::with_capacity(self.transitions_per_state.max(1));
+                next.push(To { event, to });
+
+                if let Some(index) = self.index.as_mut() {
+                    index.insert(&from, self.nodes.len());
+                }
+
+                self.nodes.vec_mut().push(Node {
+                    from,
+                    next: next.into(),
+                });
+                self.nodes.len() - 1
+            }
+        };
+
+        &mut self.nodes[index].next.last_mut().unwrap().to
+    }
+
+    /// Removes the transitions for which the predicate returns `false`, receiving `(from, event, value)`.
+    pub fn retain(&mut self, mut f: impl FnMut(&TState, &TEvent, &mut T) -> bool) {
+        // Backwards, so removing a state doesn't move the ones not visited yet
+        for index in (0..self.nodes.len()).rev() {
+            for pos in (0..self.nodes[index].next.len()).rev() {
+                let node = &mut self.nodes[index];
+                let next = &mut node.next[pos];
+
+                if !f(&node.from, &next.event, &mut next.to) {
+                    self.remove_at(index, pos);
+                }
+            }
+        }
+    }
+
+    // Removes the transition at the given position of the node, and the node if it is left empty.
+    fn remove_at(&mut self, index: usize, pos: usize) -> T {
+        let next = self.nodes[index].next.vec_mut();
+        let removed = next.remove(pos);
+
+        if next.is_empty() {
+            let node = self.nodes.vec_mut().remove(index);
+
+            if let Some(state_index) = self.index.as_mut() {
+                state_index.remove(&node.from, index);
+            }
+        }
+
+        removed.to
+    }
+}
+
+impl<TState, TEvent, T> Default for TransitionMap<TState, TEvent, T> {
+    fn default() -> Self {
+        TransitionMap::new()
+    }
+}
+
+impl<TState, TEvent, T> TransitionMap<TState, TEvent, T>
+where
+    TState: PartialEq,
+{
+    /// Returns an iterator over the transitions from the given state as `(event, to)`.
+    pub fn outgoing(&self, from: &TState) -> Outgoing<'_, TEvent, T> {
+        let iter = self
+            .position(from)
+            .map(|index| self.nodes[index].next.iter());
+        Outgoing { iter }
+    }
+
+    // Returns the position of the node of the state.
+    fn position(&self, from: &TState) -> Option<usize> {
+        match &self.index {
+            Some(index) => index.find(from),
+            None => self.nodes.iter().position(|node| &node.from == from),
+        }
+    }
+}
+
+impl<TState, TEvent, T> TransitionMap<TState, TEvent, T>
+where
+    TState: PartialEq,
+    TEvent: PartialEq,
+{
+    /// Inserts the transition for the event from the state.
+    ///
+    /// # Panics
+    /// If a transition already exists for the event from the state, use `try_insert` to get the value back instead.
+    pub fn insert(&mut self, event: TEvent, from: TState, to: T) {
+        // We can only trigger 1 transition per event,
+        // so if the transition already exists for that event we panic
+        if self.try_insert(event, from, to).is_err() {
+            panic!("a transition already exists for the event");
+        }
+    }
+
+    /// Inserts the transition if there is no transition for the event from the state,
+    /// otherwise returns the value back.
+    pub fn try_insert(&mut self, event: TEvent, from: TState, to: T) -> Result<(), T> {
+        match self.entry(event, from) {
+            Entry::Occupied(_) => Err(to),
+            Entry::Vacant(entry) => {
+                entry.insert(to);
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the entry of the transition for the event from the state, to insert or update it in place.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use restate::map::TransitionMap;
+    ///
+    /// let mut attempts = TransitionMap::new();
+    /// for _ in 0..3 {
+    ///     *attempts.entry("retry", "failed").or_insert(0) += 1;
+    /// }
+    ///
+    /// assert_eq!(attempts.get(&"retry", &"failed"), Some(&3));
+    /// ```
+    pub fn entry(&mut self, event: TEvent, from: TState) -> Entry<'_, TState, TEvent, T> {
+        let node = self.position(&from);
+        let pos =
+            node.and_then(|index| self.nodes[index].next.iter().position(|x| x.event == event));
+
+        match (node, pos) {
+            (Some(index), Some(pos)) => Entry::Occupied(OccupiedEntry {
+                map: self,
+                index,
+                pos,
+            }),
+            _ => Entry::Vacant(VacantEntry {
+                map: self,
+                node,
+                event,
+                from,
+            }),
+        }
+    }
+
+    /// Returns `true` if there is a transition for the event from the state.
+    pub fn contains(&self, event: &TEvent, from: &TState) -> bool {
+        self.get(event, from).is_some()
+    }
+
+    /// Inserts the transition, replacing and returning the value of the existing transition
+    /// for the event from the state if any.
+    pub fn insert_or_replace(&mut self, event: TEvent, from: TState, to: T) -> Option<T> {
+        match self.get_mut(&event, &from) {
+            Some(value) => Some(std::mem::replace(value, to)),
+            None => {
+                self.insert(event, from, to);
+                None
+            }
+        }
+    }
+
+    /// Removes the transition for the event from the state and returns its value.
+    pub fn remove(&mut self, event: &TEvent, from: &TState) -> Option<T> {
+        let index = self.position(from)?;
+        let pos = self.nodes[index]
+            .next
+            .iter()
+            .position(|x| &x.event == event)?;
+
+        Some(self.remove_at(index, pos))
+    }
+
+    /// Returns the value of the transition for the event from the state.
+    pub fn get(&self, event: &TEvent, from: &TState) -> Option<&T> {
+        let index = self.position(from)?;
+        self.nodes[index]
+            .next
+            .iter()
+            .find(|next| &next.event == event)
+            .map(|next| &next.to)
+    }
+
+    /// Returns a mutable reference to the value of the transition for the event from the state.
+    pub fn get_mut(&mut self, event: &TEvent, from: &TState) -> Option<&mut T> {
+        let index = self.position(from)?;
+        self.nodes[index]
+            .next
+            .iter_mut()
+            .find(|next| &next.event == event)
+            .map(|next| &mut next.to)
+    }
+
+    /// Returns the transition for the event from the state,
+    /// using and updating `hint` as the last known position of the state.
+    pub fn get_mut_hinted(
+        &mut self,
+        event: &TEvent,
+        from: &TState,
+        hint: &mut Option<usize>,
+    ) -> Option<&mut T> {
+        // The hint is checked because the position of the states can change when the map is modified
+        let index = match *hint {
+            Some(index) if self.nodes.get(index).is_some_and(|node| &node.from == from) => index,
+            _ => {
+                let index = self.position(from)?;
+                *hint = Some(index);
+                index
+            }
+        };
+
+        self.nodes[index]
+            .next
+            .iter_mut()
+            .find(|next| &next.event == event)
+            .map(|next| &mut next.to)
+    }
+
+    /// Returns the transition for the event from the first state that matches the predicate.
+    pub fn get_mut_by<P>(&mut self, event: &TEvent, mut predicate: P) -> Option<&mut T>
+    where
+        P: FnMut(&TState) -> bool,
+    {
+        self.nodes
+            .iter_mut()
+            .filter(|node| predicate(&node.from))
+            .flat_map(|node| node.next.iter_mut())
+            .find(|next| &next.event == event)
+            .map(|next| &mut next.to)
+    }
+}
+
+/// A transition in a `TransitionMap`, returned by `TransitionMap::entry`.
+pub enum Entry<'a, S, E, T> {
+    /// The map has a transition for the event from the state.
+    Occupied(OccupiedEntry<'a, S, E, T>),
+
+    /// The map doesn't have a transition for the event from the state.
+    Vacant(VacantEntry<'a, S, E, T>),
+}
+
+impl<'a, S, E, T> Entry<'a, S, E, T> {
+    /// Inserts the given value if the transition doesn't exist, and returns the value of the transition.
+    pub fn or_insert(self, default: T) -> &'a mut T {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the value returned by the function if the transition doesn't exist, and returns the value of the transition.
+    pub fn or_insert_with(self, default: impl FnOnce() -> T) -> &'a mut T {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Calls the function with the value of the transition if it exists.
+    pub fn and_modify(mut self, f: impl FnOnce(&mut T)) -> Self {
+        if let Entry::Occupied(entry) = &mut self {
+            f(entry.get_mut());
+        }
+
+        self
+    }
+}
+
+/// An existing transition in a `TransitionMap`.
+pub struct OccupiedEntry<'a, S, E, T> {
+    map: &'a mut TransitionMap<S, E, T>,
+    index: usize,
+    pos: usize,
+}
+
+impl<'a, S, E, T> OccupiedEntry<'a, S, E, T> {
+    /// Returns the value of the transition.
+    pub fn get(&self) -> &T {
+        &self.map.nodes[self.index].next[self.pos].to
+    }
+
+    /// Returns a mutable reference to the value of the transition.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.map.nodes[self.index].next[self.pos].to
+    }
+
+    /// Returns a mutable reference to the value of the transition bound to the lifetime of the map.
+    pub fn into_mut(self) -> &'a mut T {
+        &mut self.map.nodes[self.index].next[self.pos].to
+    }
+
+    /// Replaces the value of the transition and returns the old one.
+    pub fn insert(&mut self, value: T) -> T {
+        std::mem::replace(self.get_mut(), value)
+    }
+
+    /// Removes the transition and returns its value.
+    pub fn remove(self) -> T {
+        self.map.remove_at(self.index, self.pos)
+    }
+}
+
+/// A missing transition in a `TransitionMap`.
+pub struct VacantEntry<'a, S, E, T> {
+    map: &'a mut TransitionMap<S, E, T>,
+    node: Option<usize>,
+    event: E,
+    from: S,
+}
+
+impl<'a, S, E, T> VacantEntry<'a, S, E, T> {
+    /// Inserts the transition with the given value and returns the value.
+    pub fn insert(self, value: T) -> &'a mut T {
+        self.map.push(self.node, self.event, self.from, value)
+    }
+}
+
+/// An iterator over the states.
+#[derive(Debug, Clone)]
+pub struct States<'a, S, E, T> {
+    iter: slice::Iter<'a, Node<S, E, T>>,
+}
+
+impl<'a, S, E, T> Iterator for States<'a, S, E, T> {
+    type Item = &'a S;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|node| &node.from)
+    }
+}
+
+/// An iterator over the events.
+#[derive(Debug, Clone)]
+pub struct Events<'a, S, E, T> {
+    iter: slice::Iter<'a, Node<S, E, T>>,
+    cur: Option<slice::Iter<'a, To<E, T>>>,
+}
+
+impl<'a, S, E, T> Iterator for Events<'a, S, E, T> {
+    type Item = &'a E;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(cur) = self.cur.as_mut().and_then(|x| x.next()) {
+            return Some(&cur.event);
+        }
+
+        match self.iter.next() {
+            Some(node) => {
+                self.cur = Some(node.next.iter());
+                self.next()
+            }
+            None => None,
+        }
+    }
+}
+
+/// An iterator over the transitions as `(from, event, to)`.
+#[derive(Debug, Clone)]
+pub struct Iter<'a, S, E, T> {
+    iter: slice::Iter<'a, Node<S, E, T>>,
+    cur: Option<(&'a S, slice::Iter<'a, To<E, T>>)>,
+}
+
+impl<'a, S, E, T> Iterator for Iter<'a, S, E, T> {
+    type Item = (&'a S, &'a E, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((from, cur)) = self.cur.as_mut() {
+            if let Some(next) = cur.next() {
+                return Some((from, &next.event, &next.to));
+            }
+        }
+
+        match self.iter.next() {
+            Some(node) => {
+                self.cur = Some((&node.from, node.next.iter()));
+                self.next()
+            }
+            None => None,
+        }
+    }
+}
+
+/// An iterator over the transitions from a state as `(event, to)`.
+#[derive(Debug, Clone)]
+pub struct Outgoing<'a, E, T> {
+    iter: Option<slice::Iter<'a, To<E, T>>>,
+}
+
+impl<'a, E, T> Iterator for Outgoing<'a, E, T> {
+    type Item = (&'a E, &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .as_mut()
+            .and_then(|iter| iter.next())
+            .map(|next| (&next.event, &next.to))
+    }
+}
+
+impl<'a, S, E, T> IntoIterator for &'a TransitionMap<S, E, T> {
+    type Item = (&'a S, &'a E, &'a T);
+    type IntoIter = Iter<'a, S, E, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<S, E, T> IntoIterator for TransitionMap<S, E, T>
+where
+    S: Clone,
+{
+    type Item = (S, E, T);
+    type IntoIter = IntoIter<S, E, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            iter: self.nodes.into_vec().into_iter(),
+            cur: None,
+        }
+    }
+}
+
+/// An owning iterator over the transitions as `(from, event, to)`.
+#[derive(Debug)]
+pub struct IntoIter<S, E, T> {
+    iter: std::vec::IntoIter<Node<S, E, T>>,
+    cur: Option<(S, std::vec::IntoIter<To<E, T>>)>,
+}
+
+impl<S, E, T> Iterator for IntoIter<S, E, T>
+where
+    S: Clone,
+{
+    type Item = (S, E, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some((from, cur)) = self.cur.as_mut() {
+            if let Some(next) = cur.next() {
+                return Some((from.clone(), next.event, next.to));
+            }
+        }
+
+        match self.iter.next() {
+            Some(node) => {
+                self.cur = Some((node.from, node.next.into_vec().into_iter()));
+                self.next()
+            }
+            None => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Entry, TransitionMap};
+
+    #[test]
+    fn with_capacity_test() {
+        let mut map = TransitionMap::with_capacity(4, 3);
+        assert!(map.nodes.capacity() >= 4);
+
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+        map.insert(1, "b", "a");
+
+        assert!(map.nodes[0].next.capacity() >= 3);
+        assert_eq!(map.get(&1, &"a"), Some(&"b"));
+        assert_eq!(map.get(&2, &"a"), Some(&"c"));
+        assert_eq!(map.get(&1, &"b"), Some(&"a"));
+        assert_eq!(map.get(&2, &"b"), None);
+    }
+
+    #[test]
+    fn shrink_to_fit_test() {
+        let mut map = TransitionMap::with_capacity(10, 10);
+        map.insert(1, "a", "b");
+        map.shrink_to_fit();
+
+        assert_eq!(map.nodes.capacity(), 1);
+        assert_eq!(map.nodes[0].next.capacity(), 1);
+        assert_eq!(map.get(&1, &"a"), Some(&"b"));
+    }
+
+    #[test]
+    fn freeze_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+        map.insert(1, "b", "a");
+        map.freeze();
+
+        assert!(map.is_frozen());
+        assert_eq!(map.nodes.capacity(), 2);
+        assert_eq!(map.get(&2, &"a"), Some(&"c"));
+        assert_eq!(map.states().count(), 2);
+        assert_eq!(map.events().count(), 3);
+
+        // The values can change without unfreezing the map
+        *map.get_mut(&1, &"b").unwrap() = "c";
+        assert!(map.is_frozen());
+
+        map.insert(3, "c", "a");
+        assert!(!map.is_frozen());
+        assert!(map.nodes[0].next.is_frozen());
+        assert_eq!(map.remove(&2, &"a"), Some("c"));
+        assert!(!map.nodes[0].next.is_frozen());
+        assert_eq!(map.len(), 3);
+
+        let items = map.into_iter().collect::<Vec<_>>();
+        assert_eq!(items, vec![("a", 1, "b"), ("b", 1, "c"), ("c", 3, "a")]);
+    }
+
+    #[test]
+    fn iter_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+        map.insert(1, "b", "a");
+
+        let items = map.iter().collect::<Vec<_>>();
+        assert_eq!(
+            items,
+            vec![(&"a", &1, &"b"), (&"a", &2, &"c"), (&"b", &1, &"a")]
+        );
+    }
+
+    #[test]
+    fn outgoing_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+        map.insert(1, "b", "a");
+
+        let items = map.outgoing(&"a").collect::<Vec<_>>();
+        assert_eq!(items, vec![(&1, &"b"), (&2, &"c")]);
+        assert_eq!(map.outgoing(&"c").count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_duplicate_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(1, "a", "c");
+    }
+
+    #[test]
+    fn remove_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+
+        assert_eq!(map.remove(&1, &"a"), Some("b"));
+        assert_eq!(map.remove(&1, &"a"), None);
+        assert_eq!(map.get(&2, &"a"), Some(&"c"));
+
+        assert_eq!(map.remove(&2, &"a"), Some("c"));
+        assert_eq!(map.states().count(), 0);
+        assert_eq!(map.try_insert(1, "a", "d"), Ok(()));
+        assert_eq!(map.try_insert(1, "a", "e"), Err("e"));
+    }
+
+    #[test]
+    fn retain_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+        map.insert(1, "b", "c");
+        map.insert(3, "c", "a");
+
+        map.retain(|from, event, _| *from == "a" && *event == 2 || *from == "c");
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&2, &"a"), Some(&"c"));
+        assert_eq!(map.get(&3, &"c"), Some(&"a"));
+        assert_eq!(map.states().collect::<Vec<_>>(), [&"a", &"c"]);
+    }
+
+    #[test]
+    fn len_test() {
+        let mut map = TransitionMap::default();
+        assert!(map.is_empty());
+
+        map.insert(1, "a", "b");
+        map.insert(2, "a", "c");
+        map.insert(1, "b", "a");
+
+        assert_eq!(map.len(), 3);
+        assert!(map.contains(&2, &"a"));
+        assert!(!map.contains(&2, &"b"));
+        assert_eq!((&map).into_iter().count(), 3);
+
+        map.remove(&1, &"b");
+        assert_eq!(map.len(), 2);
+        assert!(!map.is_empty());
+    }
+
+    #[test]
+    fn entry_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", 10);
+
+        *map.entry(1, "a").or_insert(0) += 1;
+        *map.entry(2, "a").or_insert(0) += 1;
+        *map.entry(1, "b").and_modify(|x| *x = 100).or_insert(5) += 1;
+        assert_eq!(
+            map.iter().collect::<Vec<_>>(),
+            vec![(&"a", &1, &11), (&"a", &2, &1), (&"b", &1, &6)]
+        );
+
+        match map.entry(1, "b") {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(entry.insert(7), 6);
+                assert_eq!(entry.remove(), 7);
+            }
+            Entry::Vacant(_) => unreachable!(),
+        }
+
+        assert_eq!(map.states().count(), 1);
+    }
+
+    #[test]
+    fn insert_or_replace_test() {
+        let mut map = TransitionMap::new();
+        assert_eq!(map.insert_or_replace(1, "a", "b"), None);
+        assert_eq!(map.insert_or_replace(1, "a", "c"), Some("b"));
+        assert_eq!(map.get(&1, &"a"), Some(&"c"));
+        assert_eq!(map.iter().count(), 1);
+    }
+
+    #[test]
+    fn index_test() {
+        use crate::common::backend::{MapBackend, StateLookup};
+
+        let indexes = [StateLookup::ordered(), StateLookup::hashed()];
+
+        for index in indexes {
+            let mut map = TransitionMap::new();
+            map.insert(1, "a", "b");
+            map.insert(1, "b", "c");
+            map.set_index(index);
+            map.insert(1, "c", "a");
+            assert_ne!(map.backend(), MapBackend::Scan);
+
+            // Removing a state shifts the position of the states after it
+            assert_eq!(map.remove(&1, &"a"), Some("b"));
+            assert_eq!(map.get(&1, &"b"), Some(&"c"));
+            assert_eq!(map.get(&1, &"c"), Some(&"a"));
+            assert_eq!(map.outgoing(&"c").count(), 1);
+
+            map.insert(2, "a", "c");
+            assert_eq!(map.get(&2, &"a"), Some(&"c"));
+            assert_eq!(map.states().collect::<Vec<_>>(), vec![&"b", &"c", &"a"]);
+        }
+    }
+
+    #[test]
+    fn get_mut_hinted_test() {
+        let mut map = TransitionMap::new();
+        map.insert(1, "a", "b");
+        map.insert(1, "b", "a");
+
+        let mut hint = None;
+        assert_eq!(map.get_mut_hinted(&1, &"b", &mut hint), Some(&mut "a"));
+        assert_eq!(hint, Some(1));
+
+        // An outdated hint is ignored
+        map.remove(&1, &"a");
+        assert_eq!(map.get_mut_hinted(&1, &"b", &mut hint), Some(&mut "a"));
+        assert_eq!(hint, Some(0));
+        assert_eq!(map.get_mut_hinted(&1, &"c", &mut hint), None);
+    }
+}
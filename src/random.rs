@@ -0,0 +1,65 @@
+/// A source of random numbers.
+pub trait Rng {
+    /// Returns the next random `u64`.
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a random index in the range `0..len`.
+    ///
+    /// # Panics
+    /// If `len` is zero.
+    fn gen_index(&mut self, len: usize) -> usize {
+        assert!(len > 0, "cannot generate an index for an empty range");
+        (self.next_u64() % len as u64) as usize
+    }
+}
+
+impl<F> Rng for F
+where
+    F: FnMut() -> u64,
+{
+    fn next_u64(&mut self) -> u64 {
+        (self)()
+    }
+}
+
+/// A fast deterministic random number generator, not suitable for cryptographic use.
+#[derive(Debug, Clone)]
+pub struct SeededRng {
+    state: u64,
+}
+
+impl SeededRng {
+    /// Returns a generator that always produces the same numbers for the same seed.
+    pub fn new(seed: u64) -> Self {
+        SeededRng { state: seed }
+    }
+}
+
+impl Rng for SeededRng {
+    // SplitMix64
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Rng, SeededRng};
+
+    #[test]
+    fn seeded_rng_test() {
+        let a = (0..10)
+            .scan(SeededRng::new(7), |rng, _| Some(rng.next_u64()))
+            .collect::<Vec<_>>();
+        let b = (0..10)
+            .scan(SeededRng::new(7), |rng, _| Some(rng.next_u64()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(a, b);
+        assert!((0..100).all(|_| SeededRng::new(1).gen_index(3) < 3));
+    }
+}
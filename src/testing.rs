@@ -0,0 +1,486 @@
+use crate::blocking::{Machine, OnAction, OnTransition, Ready};
+use crate::error::TransitionError;
+use crate::random::Rng;
+use std::fmt::{Debug, Write};
+
+/// A transition that happened during a random walk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransitionRecord<S, E> {
+    /// The state where the transition started.
+    pub from: S,
+
+    /// The event that triggered the transition.
+    pub event: E,
+
+    /// The state where the transition ended.
+    pub to: S,
+
+    /// Whether the machine is done after the transition.
+    pub is_final: bool,
+}
+
+/// Drives a machine sending random events that are valid from the current state.
+///
+/// The walk stops when the machine is done, when there is no valid events or after the step limit.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+/// use restate::random::SeededRng;
+/// use restate::testing::RandomWalker;
+///
+/// let mut sm = Machine::with_context(0)
+///     .on_next(Builder::self_transition((), 1).action(|cx: ContextMut<(), i32, i32>| {
+///         *cx.context += 1;
+///     }))
+///     .on_next(Builder::self_transition((), -1).action(|cx: ContextMut<(), i32, i32>| {
+///         *cx.context -= 1;
+///     }))
+///     .start(());
+///
+/// let mut walker = RandomWalker::new(&mut sm, SeededRng::new(42)).max_steps(100);
+///
+/// while walker.step().is_some() {
+///     assert!(walker.machine().context().abs() <= 100);
+/// }
+///
+/// assert_eq!(walker.steps(), 100);
+/// ```
+pub struct RandomWalker<'m, 'a, S, E, Ctx, F, R, A: ?Sized> {
+    machine: &'m mut Machine<'a, S, E, Ctx, F, Ready, A>,
+    rng: R,
+    max_steps: Option<usize>,
+    steps: usize,
+}
+
+impl<'m, 'a, S, E, Ctx, F, R, A: ?Sized> RandomWalker<'m, 'a, S, E, Ctx, F, R, A> {
+    /// Returns a walker over the given machine.
+    pub fn new(machine: &'m mut Machine<'a, S, E, Ctx, F, Ready, A>, rng: R) -> Self {
+        RandomWalker {
+            machine,
+            rng,
+            max_steps: None,
+            steps: 0,
+        }
+    }
+
+    /// Sets the max number of transitions of the walk.
+    pub fn max_steps(mut self, max_steps: usize) -> Self {
+        self.max_steps = Some(max_steps);
+        self
+    }
+
+    /// Returns the number of transitions made.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Returns the machine being walked.
+    pub fn machine(&self) -> &Machine<'a, S, E, Ctx, F, Ready, A> {
+        self.machine
+    }
+}
+
+impl<S, E, Ctx, F, R, A> RandomWalker<'_, '_, S, E, Ctx, F, R, A>
+where
+    S: PartialEq + Clone,
+    E: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    R: Rng,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    /// Sends a random valid event to the machine and returns the transition made,
+    /// or `None` if the walk is over.
+    ///
+    /// If the machine rejects the event, other event is attempted.
+    pub fn step(&mut self) -> Option<TransitionRecord<S, E>> {
+        if self.machine.is_done() || self.max_steps.is_some_and(|max| self.steps >= max) {
+            return None;
+        }
+
+        let from = self.machine.current().clone();
+        let mut candidates = self
+            .machine
+            .transitions
+            .outgoing(&from)
+            .map(|(event, _)| event.clone())
+            .collect::<Vec<_>>();
+
+        while !candidates.is_empty() {
+            let index = self.rng.gen_index(candidates.len());
+            let event = candidates.swap_remove(index);
+
+            if self.machine.send(event.clone()).is_ok() {
+                self.steps += 1;
+
+                return Some(TransitionRecord {
+                    from,
+                    event,
+                    to: self.machine.current().clone(),
+                    is_final: self.machine.is_done(),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+impl<S, E, Ctx, F, R, A> Iterator for RandomWalker<'_, '_, S, E, Ctx, F, R, A>
+where
+    S: PartialEq + Clone,
+    E: PartialEq + Clone,
+    F: OnTransition<S, E, Ctx>,
+    R: Rng,
+    A: OnAction<S, E, Ctx> + ?Sized,
+{
+    type Item = TransitionRecord<S, E>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.step()
+    }
+}
+
+/// Returns a random sequence of at most `max_len` events that is accepted from the current state of the machine.
+///
+/// The sequence is generated from the transitions, without running any action.
+pub fn random_event_sequence<S, E, Ctx, F, A: ?Sized>(
+    machine: &Machine<'_, S, E, Ctx, F, Ready, A>,
+    rng: &mut impl Rng,
+    max_len: usize,
+) -> Vec<E>
+where
+    S: PartialEq,
+    E: Clone,
+{
+    let mut events = Vec::new();
+    let mut state = machine.current.as_ref().unwrap();
+    let mut done = machine.done;
+
+    while !done && events.len() < max_len {
+        let outgoing = machine.transitions.outgoing(state).collect::<Vec<_>>();
+        if outgoing.is_empty() {
+            break;
+        }
+
+        let (event, next) = outgoing[rng.gen_index(outgoing.len())];
+        events.push(event.clone());
+        state = &next.next;
+        done = next.is_final;
+    }
+
+    events
+}
+
+// A transition expected by a `TransitionAsserter`.
+struct Hop<S, E> {
+    event: E,
+    to: S,
+    is_final: bool,
+}
+
+/// Sends a sequence of events to a machine and asserts the state after each one.
+///
+/// All the events are sent even after a mismatch, so a failure shows the whole expected and actual trace.
+/// A hop expects the machine to be done after it only if it is marked as final.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::blocking::*;
+/// use restate::testing::TransitionAsserter;
+///
+/// let mut sm = Machine::new()
+///     .on_next(Builder::new("idle").on("start").go_to("running"))
+///     .on_next(Builder::new("running").on("stop").go_to("stopped").is_final())
+///     .start("idle");
+///
+/// TransitionAsserter::new("idle")
+///     .hop("start", "running")
+///     .final_hop("stop", "stopped")
+///     .assert(&mut sm);
+/// ```
+pub struct TransitionAsserter<S, E> {
+    initial: S,
+    hops: Vec<Hop<S, E>>,
+}
+
+impl<S, E> TransitionAsserter<S, E> {
+    /// Returns an asserter expecting the machine to be in the given state before the first event.
+    pub fn new(initial: S) -> Self {
+        TransitionAsserter {
+            initial,
+            hops: Vec::new(),
+        }
+    }
+
+    /// Expects the event to move the machine to the given state.
+    pub fn hop(mut self, event: E, to: S) -> Self {
+        self.hops.push(Hop {
+            event,
+            to,
+            is_final: false,
+        });
+        self
+    }
+
+    /// Expects the event to move the machine to the given state and complete it.
+    pub fn final_hop(mut self, event: E, to: S) -> Self {
+        self.hops.push(Hop {
+            event,
+            to,
+            is_final: true,
+        });
+        self
+    }
+}
+
+impl<S, E> TransitionAsserter<S, E>
+where
+    S: PartialEq + Clone + Debug,
+    E: PartialEq + Clone + Debug,
+{
+    /// Sends the events to the machine and panics if any state differs from the expected one.
+    #[track_caller]
+    pub fn assert<Ctx, F, A>(self, machine: &mut Machine<'_, S, E, Ctx, F, Ready, A>)
+    where
+        F: OnTransition<S, E, Ctx>,
+        A: OnAction<S, E, Ctx> + ?Sized,
+    {
+        let mut matches = *machine.current() == self.initial;
+        let mut trace = format!(
+            "{} initial: expected {:?}, actual {:?}\n",
+            mark(matches),
+            self.initial,
+            machine.current()
+        );
+
+        for hop in self.hops {
+            let actual: Result<S, TransitionError> = machine
+                .send(hop.event.clone())
+                .map(|_| machine.current().clone());
+
+            let ok = match &actual {
+                Ok(state) => *state == hop.to && machine.is_done() == hop.is_final,
+                Err(_) => false,
+            };
+            matches &= ok;
+
+            let actual = match actual {
+                Ok(state) => describe(&state, machine.is_done()),
+                Err(error) => format!("error: {error}"),
+            };
+
+            let _ = writeln!(
+                trace,
+                "{} {:?}: expected {}, actual {}",
+                mark(ok),
+                hop.event,
+                describe(&hop.to, hop.is_final),
+                actual
+            );
+        }
+
+        assert!(matches, "the transitions didn't match:\n{trace}");
+    }
+}
+
+fn mark(ok: bool) -> &'static str {
+    if ok {
+        "  "
+    } else {
+        "=>"
+    }
+}
+
+fn describe<S: Debug>(state: &S, is_final: bool) -> String {
+    if is_final {
+        format!("{state:?} (final)")
+    } else {
+        format!("{state:?}")
+    }
+}
+
+/// Asserts the states a machine goes through when sending a sequence of events, using a `TransitionAsserter`.
+///
+/// The syntax is `assert_transitions!(machine, initial => [event -> state, ...])`,
+/// a state followed by `(final)` expects the machine to be done after that event.
+/// Each event and state is a single token, so paths or other expressions must be wrapped in parentheses.
+///
+/// # Example
+///
+/// ```rust
+/// use restate::assert_transitions;
+/// use restate::blocking::*;
+///
+/// #[derive(Debug, Clone, PartialEq)]
+/// enum State {
+///     Idle,
+///     Running,
+///     Paused,
+/// }
+///
+/// use State::*;
+///
+/// let mut sm = Machine::new()
+///     .on_next(Builder::new(Idle).on("start").go_to(Running))
+///     .on_next(Builder::new(Running).on("pause").go_to(Paused))
+///     .on_next(Builder::new(Paused).on("stop").go_to(Idle).is_final())
+///     .start(Idle);
+///
+/// assert_transitions!(sm, Idle => ["start" -> Running, "pause" -> (State::Paused), "stop" -> Idle(final)]);
+/// ```
+#[macro_export]
+macro_rules! assert_transitions {
+    ($machine:expr, $initial:expr => [$($event:tt -> $state:tt $(($final:ident))?),* $(,)?]) => {{
+        let asserter = $crate::testing::TransitionAsserter::new($initial);
+        $(
+            let asserter = $crate::assert_transitions!(@hop asserter, $event, $state $(, $final)?);
+        )*
+        asserter.assert(&mut $machine);
+    }};
+    (@hop $asserter:ident, $event:tt, $state:tt) => {
+        $asserter.hop($event, $state)
+    };
+    (@hop $asserter:ident, $event:tt, $state:tt, final) => {
+        $asserter.final_hop($event, $state)
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{random_event_sequence, RandomWalker, TransitionAsserter};
+    use crate::blocking::{Builder, ContextMut, Machine};
+    use crate::random::SeededRng;
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum State {
+        Idle,
+        Running,
+        Paused,
+        Stopped,
+    }
+
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    enum Event {
+        Start,
+        Pause,
+        Resume,
+        Stop,
+    }
+
+    fn machine() -> Machine<'static, State, Event, u32, ()> {
+        Machine::with_context(0)
+            .on_next(
+                Builder::new(State::Idle)
+                    .on(Event::Start)
+                    .go_to(State::Running),
+            )
+            .on_next(
+                Builder::new(State::Running)
+                    .on(Event::Pause)
+                    .go_to(State::Paused)
+                    .action(|cx: ContextMut<State, Event, u32>| {
+                        *cx.context += 1;
+                    }),
+            )
+            .on_next(
+                Builder::new(State::Paused)
+                    .on(Event::Resume)
+                    .go_to(State::Running),
+            )
+            .on_next(
+                Builder::new(State::Paused)
+                    .on(Event::Stop)
+                    .go_to(State::Stopped)
+                    .is_final(),
+            )
+    }
+
+    #[test]
+    fn random_walker_test() {
+        for seed in 0..20 {
+            let mut sm = machine().start(State::Idle);
+            let mut walker = RandomWalker::new(&mut sm, SeededRng::new(seed)).max_steps(50);
+            let mut pauses = 0;
+
+            while let Some(record) = walker.step() {
+                if record.event == Event::Pause {
+                    pauses += 1;
+                }
+
+                assert_eq!(*walker.machine().context(), pauses);
+            }
+
+            assert!(walker.steps() <= 50);
+            assert_eq!(sm.is_done(), *sm.current() == State::Stopped);
+        }
+    }
+
+    #[test]
+    fn random_walker_is_deterministic_test() {
+        let mut a = machine().start(State::Idle);
+        let mut b = machine().start(State::Idle);
+
+        let a = RandomWalker::new(&mut a, SeededRng::new(3))
+            .max_steps(20)
+            .collect::<Vec<_>>();
+        let b = RandomWalker::new(&mut b, SeededRng::new(3))
+            .max_steps(20)
+            .collect::<Vec<_>>();
+
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn assert_transitions_test() {
+        use State::*;
+
+        let mut sm = machine().start(Idle);
+        assert_transitions!(sm, Idle => [
+            (Event::Start) -> Running,
+            (Event::Pause) -> Paused,
+            (Event::Resume) -> Running,
+            (Event::Pause) -> Paused,
+            (Event::Stop) -> Stopped(final),
+        ]);
+        assert_eq!(*sm.context(), 2);
+    }
+
+    #[test]
+    fn transition_asserter_trace_test() {
+        let result = std::panic::catch_unwind(|| {
+            let mut sm = machine().start(State::Idle);
+            TransitionAsserter::new(State::Idle)
+                .hop(Event::Start, State::Paused)
+                .hop(Event::Stop, State::Stopped)
+                .hop(Event::Pause, State::Paused)
+                .assert(&mut sm);
+        });
+
+        let error = result.unwrap_err();
+        let message = error.downcast_ref::<String>().unwrap();
+        assert_eq!(
+            message,
+            "the transitions didn't match:\n\
+            \x20  initial: expected Idle, actual Idle\n\
+            => Start: expected Paused, actual Running\n\
+            => Stop: expected Stopped, actual error: invalid transition\n\
+            \x20  Pause: expected Paused, actual Paused\n"
+        );
+    }
+
+    #[test]
+    fn random_event_sequence_test() {
+        let mut rng = SeededRng::new(10);
+
+        for _ in 0..20 {
+            let sm = machine().start(State::Idle);
+            let events = random_event_sequence(&sm, &mut rng, 10);
+
+            assert!(events.len() <= 10);
+            assert!(sm.accepts(&events));
+        }
+    }
+}